@@ -0,0 +1,236 @@
+use std::{path::PathBuf, time::Duration};
+
+use crate::{data, process::OutputFormat, watch::{self, WatchSettings}};
+
+/// This enum represents the different ways of handling an input
+/// file which had issues while parsing, in place of the yes/no
+/// dialogs shown by the gui.
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum ErrorPolicy {
+    /// Files with issues are still used in the output.
+    Include,
+    /// Files with issues are left out of the output.
+    Skip,
+    /// Processing stops at the first file with issues.
+    Abort,
+}//end enum ErrorPolicy
+
+impl ErrorPolicy {
+    /// Attempts to match label to variant.
+    pub fn from_str(str: &str) -> Option<ErrorPolicy> {
+        match str {
+            "include" => Some(ErrorPolicy::Include),
+            "skip" => Some(ErrorPolicy::Skip),
+            "abort" => Some(ErrorPolicy::Abort),
+            _ => None,
+        }//end matching str
+    }//end from_str()
+}//end impl for ErrorPolicy
+
+/// This struct holds the options given on the command line.
+#[derive(Clone,PartialEq,Debug)]
+pub struct CliArgs {
+    /// The input files and directories given.
+    pub inputs: Vec<PathBuf>,
+    /// The path to write output to, if given.
+    pub output: Option<PathBuf>,
+    /// The path of a database to save results to, if given.
+    pub database: Option<PathBuf>,
+    /// The kind of output file to write, if given.
+    pub format: Option<OutputFormat>,
+    /// Whether to also write long-format output.
+    pub long: bool,
+    /// Whether to also write an issues sheet.
+    pub issues: bool,
+    /// An optional path to a config file.
+    pub config: Option<PathBuf>,
+    /// The name of a config profile to use, if given.
+    pub profile: Option<String>,
+    /// An optional path to a spec profiles file.
+    pub specs: Option<PathBuf>,
+    /// The name of a spec profile to grade every test against, if given.
+    pub spec: Option<String>,
+    /// How to handle files with parsing issues.
+    pub on_error: ErrorPolicy,
+    /// The categories of parsing issues which shouldn't trigger on_error.
+    pub ignored_issues: Vec<String>,
+    /// Whether to look for input files in the folders inside directory inputs.
+    pub recursive: bool,
+    /// Include patterns to use instead of the ones in the config, if any were given.
+    pub include_patterns: Vec<String>,
+    /// Exclude patterns to use instead of the ones in the config, if any were given.
+    pub exclude_patterns: Vec<String>,
+}//end struct CliArgs
+
+/// This struct holds the options given on the command line for watch mode.
+#[derive(Clone,PartialEq,Debug)]
+pub struct WatchArgs {
+    /// The folders and options to watch with.
+    pub settings: WatchSettings,
+    /// An optional path to a config file.
+    pub config: Option<PathBuf>,
+    /// The name of a config profile to use, if given.
+    pub profile: Option<String>,
+    /// An optional path to a spec profiles file.
+    pub specs: Option<PathBuf>,
+    /// How long to wait between checks of the watched folder.
+    pub interval: Duration,
+    /// Whether to check the watched folder only once.
+    pub once: bool,
+}//end struct WatchArgs
+
+/// The first arguments which mean the program should run headless instead of opening the gui.
+pub const CLI_COMMANDS: [&str; 5] = ["export", "watch", "help", "-h", "--help"];
+
+/// Returns true if the program was started with a subcommand or help flag,
+/// meaning it should run headless instead of opening the gui.  
+/// Anything else, such as files dropped onto the program, opens the gui.
+pub fn is_cli_invocation(args: &[String]) -> bool {
+    args.get(1).is_some_and(|arg| CLI_COMMANDS.contains(&arg.as_str()))
+}//end is_cli_invocation()
+
+/// Parses the arguments given after the export subcommand.
+/// Returns Ok(None) if the user asked for help.
+pub fn parse_export_args(args: &[String]) -> Result<Option<CliArgs>,String> {
+    let mut inputs = Vec::new();
+    let mut output = None;
+    let mut config = None;
+    let mut profile = None;
+    let mut specs = None;
+    let mut spec = None;
+    let mut format = None;
+    let mut database = None;
+    let mut long = false;
+    let mut issues = false;
+    let mut on_error = ErrorPolicy::Include;
+    let mut ignored_issues = Vec::new();
+    let mut recursive = false;
+    let mut include_patterns = Vec::new();
+    let mut exclude_patterns = Vec::new();
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-o" | "--output" => match args_iter.next() {
+                Some(path) => output = Some(PathBuf::from(path)),
+                None => return Err(format!("Missing a path after {}", arg)),
+            },
+            "-c" | "--config" => match args_iter.next() {
+                Some(path) => config = Some(PathBuf::from(path)),
+                None => return Err(format!("Missing a path after {}", arg)),
+            },
+            "-p" | "--profile" => match args_iter.next() {
+                Some(name) => profile = Some(name.clone()),
+                None => return Err(format!("Missing a profile name after {}", arg)),
+            },
+            "--specs" => match args_iter.next() {
+                Some(path) => specs = Some(PathBuf::from(path)),
+                None => return Err(format!("Missing a path after {}", arg)),
+            },
+            "--spec" => match args_iter.next() {
+                Some(name) => spec = Some(name.clone()),
+                None => return Err(format!("Missing a spec name after {}", arg)),
+            },
+            "-d" | "--database" => match args_iter.next() {
+                Some(path) => database = Some(PathBuf::from(path)),
+                None => return Err(format!("Missing a path after {}", arg)),
+            },
+            "-f" | "--format" => match args_iter.next().and_then(|fmt| OutputFormat::from_str(fmt)) {
+                Some(fmt) => format = Some(fmt),
+                None => return Err(format!("{} must be followed by xlsx, csv, or tsv", arg)),
+            },
+            "-l" | "--long" => long = true,
+            "-i" | "--issues" => issues = true,
+            "--on-error" => match args_iter.next().and_then(|policy| ErrorPolicy::from_str(policy)) {
+                Some(policy) => on_error = policy,
+                None => return Err("--on-error must be followed by include, skip, or abort".to_string()),
+            },
+            "--ignore-issue" => match args_iter.next() {
+                Some(kind) if data::ParseIssueKind::CATEGORIES.contains(&kind.as_str()) => ignored_issues.push(kind.clone()),
+                _ => return Err(format!("--ignore-issue must be followed by one of: {}", data::ParseIssueKind::CATEGORIES.join(", "))),
+            },
+            "-r" | "--recursive" => recursive = true,
+            "--include" => match args_iter.next() {
+                Some(pattern) => {include_patterns.push(pattern.clone()); recursive = true;},
+                None => return Err(format!("Missing a pattern after {}", arg)),
+            },
+            "--exclude" => match args_iter.next() {
+                Some(pattern) => {exclude_patterns.push(pattern.clone()); recursive = true;},
+                None => return Err(format!("Missing a pattern after {}", arg)),
+            },
+            _ if arg.starts_with('-') => return Err(format!("Unknown option \"{}\"", arg)),
+            _ => inputs.push(PathBuf::from(arg)),
+        }//end matching each argument
+    }//end looping over each argument
+
+    if inputs.is_empty() {return Err("No input files or directories were given.".to_string());}
+    if output.is_none() && database.is_none() {
+        return Err("No output path or database was given. Use --output <PATH> or --database <PATH>.".to_string());
+    }//end if there's nowhere to put output
+    Ok(Some(CliArgs{inputs, output, database, format, long, issues, config, profile, specs, spec, on_error, ignored_issues, recursive, include_patterns, exclude_patterns}))
+}//end parse_export_args()
+
+/// Parses the arguments given after the watch subcommand.
+/// Returns Ok(None) if the user asked for help.
+pub fn parse_watch_args(args: &[String]) -> Result<Option<WatchArgs>,String> {
+    let mut watch_dir = None;
+    let mut output_dir = None;
+    let mut database = None;
+    let mut workbook = true;
+    let mut config = None;
+    let mut profile = None;
+    let mut specs = None;
+    let mut interval = Duration::from_secs(watch::DEFAULT_POLL_SECS);
+    let mut settle = Duration::from_secs(watch::DEFAULT_SETTLE_SECS);
+    let mut once = false;
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-o" | "--output-dir" => match args_iter.next() {
+                Some(path) => output_dir = Some(PathBuf::from(path)),
+                None => return Err(format!("Missing a folder after {}", arg)),
+            },
+            "-d" | "--database" => match args_iter.next() {
+                Some(path) => database = Some(PathBuf::from(path)),
+                None => return Err(format!("Missing a path after {}", arg)),
+            },
+            "--no-workbook" => workbook = false,
+            "-c" | "--config" => match args_iter.next() {
+                Some(path) => config = Some(PathBuf::from(path)),
+                None => return Err(format!("Missing a path after {}", arg)),
+            },
+            "-p" | "--profile" => match args_iter.next() {
+                Some(name) => profile = Some(name.clone()),
+                None => return Err(format!("Missing a profile name after {}", arg)),
+            },
+            "--specs" => match args_iter.next() {
+                Some(path) => specs = Some(PathBuf::from(path)),
+                None => return Err(format!("Missing a path after {}", arg)),
+            },
+            "--interval" => match args_iter.next().and_then(|secs| secs.parse::<u64>().ok()) {
+                Some(secs) if secs > 0 => interval = Duration::from_secs(secs),
+                _ => return Err(format!("{} must be followed by a whole number of seconds above 0", arg)),
+            },
+            "--settle" => match args_iter.next().and_then(|secs| secs.parse::<u64>().ok()) {
+                Some(secs) => settle = Duration::from_secs(secs),
+                None => return Err(format!("{} must be followed by a whole number of seconds", arg)),
+            },
+            "--once" => once = true,
+            _ if arg.starts_with('-') => return Err(format!("Unknown option \"{}\"", arg)),
+            _ if watch_dir.is_none() => watch_dir = Some(PathBuf::from(arg)),
+            _ => return Err("Only one folder can be watched at a time.".to_string()),
+        }//end matching each argument
+    }//end looping over each argument
+
+    let watch_dir = match watch_dir {
+        Some(watch_dir) => watch_dir,
+        None => return Err("No folder to watch was given.".to_string()),
+    };
+    if !workbook && database.is_none() {
+        return Err("With --no-workbook, a database must be given with --database <PATH>.".to_string());
+    }//end if there's nowhere to put output
+    let output_dir = output_dir.unwrap_or_else(|| watch_dir.clone());
+    let settings = WatchSettings{watch_dir, output_dir, workbook, database_path: database, settle};
+    Ok(Some(WatchArgs{settings, config, profile, specs, interval, once}))
+}//end parse_watch_args()
@@ -0,0 +1,232 @@
+use std::{path::{Path, PathBuf}, sync::{atomic::{AtomicBool, AtomicUsize, Ordering}, mpsc::{self, Receiver, Sender}, Arc}, thread::{self, JoinHandle}};
+
+use crate::{config_store::ConfigStore, data::{self, Data, ParseIssue}, input::{self, TextEncoding}};
+
+/// The most worker threads a ParseBatch will start, no matter how many cores there are.
+pub const MAX_WORKER_THREADS: usize = 8;
+
+/// What happened when a worker thread tried to read and parse a file.
+#[derive(Clone,PartialEq,Debug)]
+pub enum FileOutcome {
+    /// The file couldn't be read, with the reason why.
+    Unreadable(String),
+    /// The file was read, but data couldn't be parsed from it.
+    Failed(ParseIssue),
+    /// Data was parsed from the file, along with any issues found along the way,
+    /// including a note if the file wasn't plain UTF-8.
+    Parsed(Data, Vec<ParseIssue>),
+}//end enum FileOutcome
+
+/// The result of reading and parsing a single file in a ParseBatch.
+#[derive(Clone,PartialEq,Debug)]
+pub struct FileResult {
+    /// The position of the file in the list of paths the batch was started with.
+    pub index: usize,
+    /// The path of the file.
+    pub path: PathBuf,
+    /// The name of the file, from input::get_filename().
+    pub filename: String,
+    /// The encoding the file was read as, if it could be read.
+    pub encoding: Option<TextEncoding>,
+    /// What happened when reading and parsing the file.
+    pub outcome: FileOutcome,
+}//end struct FileResult
+
+impl FileResult {
+    /// Gets a short description of the outcome, to show next to the file while the batch runs.
+    pub fn get_status(&self) -> String {
+        let status = match &self.outcome {
+            FileOutcome::Unreadable(_) => "Couldn't read file".to_string(),
+            FileOutcome::Failed(issue) => format!("Failed: {}", issue.kind.category()),
+            FileOutcome::Parsed(_, issues) => match data::get_problems(issues) {
+                problems if problems.is_empty() => "Done".to_string(),
+                problems => format!("Done, {}", data::summarize_issues(&problems)),
+            },
+        };
+        match self.encoding {
+            Some(encoding) if encoding != TextEncoding::Utf8 => format!("{} (read as {})", status, encoding.to_string()),
+            _ => status,
+        }//end matching whether the encoding is worth mentioning
+    }//end get_status(self)
+
+    /// Gets a note saying which encoding the file was read as, or None if it was plain UTF-8 or couldn't be read.
+    pub fn get_encoding_issue(&self) -> Option<ParseIssue> {
+        self.encoding.and_then(|encoding| encoding.get_issue(&self.filename))
+    }//end get_encoding_issue(self)
+}//end impl for FileResult
+
+/// The messages sent back from worker threads as they work through files.
+#[derive(Clone,PartialEq,Debug)]
+enum BatchEvent {
+    /// A worker started reading the file at this index.
+    Started(usize),
+    /// A worker finished with a file. The result is boxed, since it holds all the data from the file.
+    Finished(Box<FileResult>),
+}//end enum BatchEvent
+
+/// The progress made on a single file in a ParseBatch, as returned by ParseBatch::poll().
+#[derive(Clone,PartialEq,Eq,Debug)]
+pub enum BatchProgress {
+    /// A worker started reading the file at this index.
+    Started(usize),
+    /// A worker finished the file at this index, with the status from FileResult::get_status().
+    Finished(usize, String),
+}//end enum BatchProgress
+
+/// Reads and parses a list of input files on worker threads.
+/// Call poll() regularly to collect progress, then take_results() once is_done()
+/// is true to get the results in the same order as the paths given.
+pub struct ParseBatch {
+    /// The paths of the files to read, in order.
+    paths: Vec<PathBuf>,
+    /// Results received so far, indexed the same as paths.
+    results: Vec<Option<FileResult>>,
+    /// The number of results received so far.
+    finished: usize,
+    /// Set to true to stop workers from starting any more files.
+    cancelled: Arc<AtomicBool>,
+    /// Receives events from every worker.
+    receiver: Receiver<BatchEvent>,
+    /// The worker threads, joined once the batch is done.
+    workers: Vec<JoinHandle<()>>,
+}//end struct ParseBatch
+
+impl ParseBatch {
+    /// Starts reading and parsing each of paths with config, spread over
+    /// as many worker threads as there are cores, up to MAX_WORKER_THREADS.
+    pub fn start(paths: &[PathBuf], config: &ConfigStore) -> ParseBatch {
+        let cores = thread::available_parallelism().map(|cores| cores.get()).unwrap_or(1);
+        ParseBatch::start_with_threads(paths, config, cores.min(MAX_WORKER_THREADS))
+    }//end start()
+
+    /// Starts reading and parsing each of paths with config, using the given number of worker threads.
+    /// At least one thread is used, and never more than there are files.
+    pub fn start_with_threads(paths: &[PathBuf], config: &ConfigStore, threads: usize) -> ParseBatch {
+        let paths = paths.to_vec();
+        let shared_paths = Arc::new(paths.clone());
+        let shared_config = Arc::new(config.clone());
+        let next_index = Arc::new(AtomicUsize::new(0));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let mut workers = Vec::new();
+        for _ in 0..threads.max(1).min(paths.len()) {
+            let paths = shared_paths.clone();
+            let config = shared_config.clone();
+            let next_index = next_index.clone();
+            let cancelled = cancelled.clone();
+            let sender = sender.clone();
+            workers.push(thread::spawn(move || parse_worker(&paths, &config, &next_index, &cancelled, &sender)));
+        }//end starting each worker
+        let results = vec![None; paths.len()];
+        ParseBatch{paths, results, finished: 0, cancelled, receiver, workers}
+    }//end start_with_threads()
+
+    /// Collects any events sent by the workers since the last call, without waiting.
+    /// Results are kept for take_results(), and the progress made is returned to show while the batch runs.
+    pub fn poll(&mut self) -> Vec<BatchProgress> {
+        let mut progress = Vec::new();
+        for event in self.receiver.try_iter() {
+            match event {
+                BatchEvent::Started(index) => progress.push(BatchProgress::Started(index)),
+                BatchEvent::Finished(result) => {
+                    let index = result.index;
+                    if index < self.results.len() && self.results[index].is_none() {
+                        progress.push(BatchProgress::Finished(index, result.get_status()));
+                        self.results[index] = Some(*result);
+                        self.finished += 1;
+                    }//end if this is a new result
+                },
+            }//end matching what the worker sent
+        }//end keeping each result
+        progress
+    }//end poll(self)
+
+    /// Stops workers from starting any more files.
+    /// Files already being read are still finished.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }//end cancel(self)
+
+    /// Gets the flag checked by workers before each file, so that a batch
+    /// can be cancelled from a callback which can't borrow the batch.
+    pub fn get_cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }//end get_cancel_flag(self)
+
+    /// Returns true if cancel() was called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }//end is_cancelled(self)
+
+    /// Returns true once every worker has stopped, either from finishing every file or from being cancelled.
+    pub fn is_done(&self) -> bool {
+        self.workers.iter().all(|worker| worker.is_finished())
+    }//end is_done(self)
+
+    /// The number of files in the batch.
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }//end len(self)
+
+    /// Returns true if the batch doesn't have any files.
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }//end is_empty(self)
+
+    /// The number of files results have been received for.
+    pub fn finished_count(&self) -> usize {
+        self.finished
+    }//end finished_count(self)
+
+    /// The paths of the files in the batch, in order.
+    pub fn get_paths(&self) -> &Vec<PathBuf> {
+        &self.paths
+    }//end get_paths(self)
+
+    /// Waits for the workers to stop, then returns every result received,
+    /// in the same order as the paths the batch was started with.
+    /// If the batch was cancelled, files which were never started are left out.
+    pub fn take_results(mut self) -> Vec<FileResult> {
+        for worker in self.workers.drain(..) {
+            if worker.join().is_err() {eprintln!("A worker thread stopped unexpectedly while parsing files.");}
+        }//end waiting for each worker
+        self.poll();
+        self.results.into_iter().flatten().collect()
+    }//end take_results(self)
+}//end impl for ParseBatch
+
+/// Reads and parses every file in paths, in order, on worker threads, waiting for all of them to finish.
+pub fn parse_files(paths: &[PathBuf], config: &ConfigStore) -> Vec<FileResult> {
+    ParseBatch::start(paths, config).take_results()
+}//end parse_files()
+
+/// Reads and parses a single file, the same way as every worker does.
+pub fn parse_file(index: usize, path: &Path, config: &ConfigStore) -> FileResult {
+    let filename = input::get_filename(path);
+    let mut encoding = None;
+    let outcome = match input::read_input_file_with_encoding(path) {
+        Err(msg) => FileOutcome::Unreadable(msg),
+        Ok(input_file) => {encoding = Some(input_file.encoding); match data::read_data_from_file(&filename, &input_file.contents, config) {
+            Err(issue) => FileOutcome::Failed(issue),
+            Ok((mut data, mut issues)) => {
+                data.source_path = path.to_path_buf();
+                data.source_hash = input_file.hash;
+                if let Some(note) = input_file.encoding.get_issue(&filename) {issues.insert(0, note);}
+                FileOutcome::Parsed(data, issues)
+            },
+        }},
+    };
+    FileResult{index, path: path.to_path_buf(), filename, encoding, outcome}
+}//end parse_file()
+
+/// Takes the next unstarted file until there are none left or the batch is cancelled,
+/// sending events back through sender.
+fn parse_worker(paths: &[PathBuf], config: &ConfigStore, next_index: &AtomicUsize, cancelled: &AtomicBool, sender: &Sender<BatchEvent>) {
+    while !cancelled.load(Ordering::SeqCst) {
+        let index = next_index.fetch_add(1, Ordering::SeqCst);
+        if index >= paths.len() {break;}
+        // if the batch was dropped, nobody is listening anymore
+        if sender.send(BatchEvent::Started(index)).is_err() {break;}
+        if sender.send(BatchEvent::Finished(Box::new(parse_file(index, &paths[index], config)))).is_err() {break;}
+    }//end looping until there aren't any files left
+}//end parse_worker()
@@ -0,0 +1,426 @@
+use std::{path::PathBuf, thread, time::SystemTime};
+
+use alveograph_exporter_s::{args::{self, ErrorPolicy, WatchArgs}, config_store::{self, ConfigLocation, ConfigProfiles, ConfigStore}, data::{self, Data}, database, input, process::{self, close_workbook, get_workbook, write_output_to_delimited, write_output_to_sheet, FileIssues, OutputFormat}, spec::{self, SpecProfile, SpecStore}, watch};
+
+/// Exit code for when everything was processed without any files being left out.
+pub const EXIT_SUCCESS: i32 = 0;
+/// Exit code for when output was written, but at least one input file was left out.
+pub const EXIT_PARTIAL: i32 = 1;
+/// Exit code for when the command-line arguments couldn't be understood.
+pub const EXIT_USAGE: i32 = 2;
+/// Exit code for when the config file couldn't be found or read.
+pub const EXIT_CONFIG: i32 = 3;
+/// Exit code for when there weren't any input files we could use.
+pub const EXIT_INPUT: i32 = 4;
+/// Exit code for when processing was stopped because of the --on-error abort policy.
+pub const EXIT_ABORTED: i32 = 5;
+/// Exit code for when the output file couldn't be written.
+pub const EXIT_OUTPUT: i32 = 6;
+
+/// The usage message printed for --help or for bad arguments, filled in by get_usage().
+const USAGE: &str = "Usage: alveograph-exporter-s export [OPTIONS] <INPUT>...
+       alveograph-exporter-s watch [OPTIONS] <FOLDER>
+
+Processes alveograph txt files without opening a window.
+Each INPUT may be a txt file or a directory containing txt files.
+
+Options:
+  -o, --output <PATH>       The file to write output to
+  -d, --database <PATH>     A SQLite database to save results to, created if needed.
+                              Tests already saved from the same file are skipped.
+                              At least one of --output or --database is required
+  -f, --format <FORMAT>     The kind of output file to write: xlsx, csv, or tsv.
+                              Defaults to the extension of the output path, or xlsx
+  -c, --config <PATH>       A config json file to use instead of the saved config
+  -p, --profile <NAME>      The config profile to use, instead of the one used last
+      --specs <PATH>        A spec profiles json file to use instead of the saved specs
+      --spec <NAME>         Grade every test against the spec profile NAME, instead
+                              of choosing a profile by test name
+  -l, --long                Also write long-format output, with one line per value.
+                              For xlsx this is an extra sheet, and for csv or tsv
+                              it is a second file ending in -long
+  -i, --issues              Also write an Issues sheet listing every issue found,
+                              and whether each file was included. For csv or tsv
+                              it is a second file ending in -issues
+      --on-error <POLICY>   What to do with files that had issues while parsing:
+                              include  use the file anyway (default)
+                              skip     leave the file out of the output
+                              abort    stop without writing any output
+      --ignore-issue <KIND> Don't count issues of KIND when applying --on-error.
+                              They are still printed. May be given more than once.
+                              KIND is one of: {kinds}
+  -r, --recursive           Also look for input files in the folders inside each
+                              directory INPUT, using the include and exclude
+                              patterns from the config
+      --include <GLOB>      Use files matching GLOB from directory inputs, in place
+                              of the config's include patterns. Implies --recursive.
+                              May be given more than once
+      --exclude <GLOB>      Leave out files matching GLOB from directory inputs, in
+                              place of the config's exclude patterns. Implies
+                              --recursive. May be given more than once
+  -h, --help                Print this message
+
+In a GLOB, * matches any part of a file or folder name, ? matches one character,
+and ** matches any number of folders. A GLOB ending in / matches everything in
+that folder, and a GLOB without any other / can match at any depth.
+
+Watch mode checks FOLDER for new or modified txt files, and processes each
+one once it has stopped changing. Every file processed on the same local date
+goes into a daily workbook named alveograph-YYYY-MM-DD.xlsx, which is rewritten
+as files arrive. If the local time zone can't be determined, the date in UTC is
+used instead. A watch-ledger.json file in the output folder records which
+files were processed, so restarting doesn't process them again.
+
+Watch options:
+  -o, --output-dir <DIR>    The folder for daily workbooks and the ledger.
+                              Defaults to FOLDER
+  -d, --database <PATH>     A SQLite database to save results to as well
+      --no-workbook         Don't write daily workbooks, such as when only
+                              saving to a database
+  -c, --config <PATH>       Same as for export
+  -p, --profile <NAME>      Same as for export
+      --specs <PATH>        Same as for export
+      --interval <SECS>     Seconds between checks of FOLDER. Defaults to 10
+      --settle <SECS>       Seconds a file must go unchanged before it's read.
+                              Defaults to 5
+      --once                Check FOLDER once and exit, such as from a scheduled task
+
+Saved config and specs files are looked for in the folder named by the
+ALVEOGRAPH_EXPORTER_CONFIG_DIR environment variable if it is set, otherwise
+in your user config folder, and otherwise next to the program.
+
+Exit codes:
+  0  success             1  some files were left out   2  bad arguments
+  3  config problem      4  no usable input files      5  aborted by --on-error
+  6  couldn't write output";
+
+/// Gets the usage message, with every kind of issue --ignore-issue accepts listed
+/// from data::ParseIssueKind::CATEGORIES and wrapped to the width of the option descriptions.
+fn get_usage() -> String {
+    let indent = " ".repeat(30);
+    let mut kinds = String::new();
+    let mut line_len = indent.len() + "KIND is one of: ".len();
+    for (index, kind) in data::ParseIssueKind::CATEGORIES.iter().enumerate() {
+        let item = if index + 1 < data::ParseIssueKind::CATEGORIES.len() {format!("{},", kind)} else {kind.to_string()};
+        if index > 0 {
+            if line_len + 1 + item.len() > 85 {
+                kinds.push('\n');
+                kinds.push_str(&indent);
+                line_len = indent.len();
+            } else {
+                kinds.push(' ');
+                line_len += 1;
+            }//end if the next kind goes on a new line
+        }//end if this isn't the first kind
+        kinds.push_str(&item);
+        line_len += item.len();
+    }//end adding each kind of issue
+    USAGE.replace("{kinds}", &kinds)
+}//end get_usage()
+
+/// Runs the command-line interface with the given arguments, which
+/// should include the program name first.
+/// Returns the exit code the program should finish with.
+pub fn run(args: &[String]) -> i32 {
+    let cli_args = match args.get(1).map(|s| s.as_str()) {
+        Some("export") => match args::parse_export_args(&args[2..]) {
+            Ok(Some(cli_args)) => cli_args,
+            Ok(None) => {println!("{}", get_usage()); return EXIT_SUCCESS;},
+            Err(msg) => {eprintln!("{}\n\n{}", msg, get_usage()); return EXIT_USAGE;},
+        },
+        Some("watch") => match args::parse_watch_args(&args[2..]) {
+            Ok(Some(watch_args)) => return run_watch(&watch_args),
+            Ok(None) => {println!("{}", get_usage()); return EXIT_SUCCESS;},
+            Err(msg) => {eprintln!("{}\n\n{}", msg, get_usage()); return EXIT_USAGE;},
+        },
+        Some("help") | Some("-h") | Some("--help") => {println!("{}", get_usage()); return EXIT_SUCCESS;},
+        Some(other) => {eprintln!("Unknown command \"{}\"\n\n{}", other, get_usage()); return EXIT_USAGE;},
+        None => {eprintln!("{}", get_usage()); return EXIT_USAGE;},
+    };//end matching subcommand
+
+    let config = match get_config(&cli_args.config, &cli_args.profile) {
+        Ok(config) => config,
+        Err(msg) => {eprintln!("{}", msg); return EXIT_CONFIG;},
+    };
+    // problems found in the input files themselves are reported as they're read
+    let config_problems = config.validate();
+    for problem in config_problems.iter() {eprintln!("Config {}: {}", problem.severity(), problem);}
+    if config_problems.iter().any(|problem| problem.severity() == data::IssueSeverity::Error) {return EXIT_CONFIG;}
+    let specs = match get_specs(&cli_args.specs) {
+        Ok(specs) => specs,
+        Err(msg) => {eprintln!("{}", msg); return EXIT_CONFIG;},
+    };
+    for problem in config.validate_specs(&specs).iter() {eprintln!("Config {}: {}", problem.severity(), problem);}
+    if let Some(spec_name) = &cli_args.spec {
+        if specs.get_profile(spec_name).is_none() {
+            eprintln!("There isn't a spec profile named \"{}\".", spec_name);
+            return EXIT_CONFIG;
+        }//end if the spec doesn't exist
+    }//end if we were given a spec to use
+
+    let input_paths = if cli_args.recursive {
+        let include_patterns = if cli_args.include_patterns.is_empty() {&config.input_include_patterns} else {&cli_args.include_patterns};
+        let exclude_patterns = if cli_args.exclude_patterns.is_empty() {&config.input_exclude_patterns} else {&cli_args.exclude_patterns};
+        gather_recursive_input_paths(&cli_args.inputs, include_patterns, exclude_patterns)
+    } else {input::gather_input_paths(&cli_args.inputs)};
+    let input_paths = match input_paths {
+        Ok(input_paths) => input_paths,
+        Err(msg) => {eprintln!("{}", msg); return EXIT_INPUT;},
+    };
+    if input_paths.is_empty() {
+        eprintln!("There weren't any input files to process.");
+        return EXIT_INPUT;
+    }//end if we didn't find any input files
+
+    let mut data_files: Vec<Data> = Vec::new();
+    let mut file_issues: Vec<FileIssues> = Vec::new();
+    let mut left_out_files = 0;
+    for input_path in input_paths.iter() {
+        let path_str = input_path.to_string_lossy();
+        let filename = input::get_filename(input_path);
+        let input_file = match input::read_input_file_with_encoding(input_path) {
+            Ok(input_file) => input_file,
+            Err(msg) => {
+                eprintln!("There was an error reading from path \"{}\":\n{}", path_str, msg);
+                if cli_args.on_error == ErrorPolicy::Abort {return EXIT_ABORTED;}
+                file_issues.push(FileIssues::new(&filename, input_path, vec![data::ParseIssue::new(&filename, None, data::ParseIssueKind::FileUnreadable{msg})], false));
+                left_out_files += 1;
+                continue;
+            },
+        };
+        // the note is kept with the file's issues, but never counts towards on_error
+        let encoding_issue = input_file.encoding.get_issue(&filename);
+        if let Some(note) = &encoding_issue {eprintln!("{}", note);}
+        match data::read_data_from_file(&filename, &input_file.contents, &config) {
+            Err(issue) => {
+                eprintln!("There was an issue reading from path \"{}\". The issue was:\n{}", path_str, issue);
+                if cli_args.on_error == ErrorPolicy::Abort {return EXIT_ABORTED;}
+                file_issues.push(FileIssues::new(&filename, input_path, encoding_issue.into_iter().chain([issue]).collect(), false));
+                left_out_files += 1;
+            },
+            Ok((mut data,errs)) => {
+                data.source_path = input_path.clone();
+                data.source_hash = input_file.hash.clone();
+                if !errs.is_empty() {
+                    eprintln!("There were issue(s) parsing data from path \"{}\", {}\n{}", path_str, data::summarize_issues(&errs), data::format_issues(&errs));
+                }//end if there were any issues to print
+                let mut included = true;
+                if errs.iter().any(|issue| !issue.is_note() && !cli_args.ignored_issues.iter().any(|kind| kind.eq(issue.kind.category()))) {
+                    match cli_args.on_error {
+                        ErrorPolicy::Include => {},
+                        ErrorPolicy::Skip => {included = false; left_out_files += 1;},
+                        ErrorPolicy::Abort => return EXIT_ABORTED,
+                    }//end matching what to do with this file
+                }//end if there were issues we weren't told to ignore
+                let issues: Vec<data::ParseIssue> = encoding_issue.into_iter().chain(errs).collect();
+                if !issues.is_empty() {file_issues.push(FileIssues::new(&data.source_file, &data.source_path, issues, included));}
+                if included {data_files.push(data);}
+            },
+        }//end matching whether we can read data from this file
+    }//end looping over each input file to read from
+
+    if data_files.is_empty() {
+        eprintln!("None of the input files could be used, so no output was written.");
+        return EXIT_INPUT;
+    }//end if there's nothing to write
+
+    if let Some(spec_name) = &cli_args.spec {
+        for data in data_files.iter_mut() {data.spec_name = spec_name.clone();}
+    }//end if every test should use the same spec
+    spec::assign_spec_names(&mut data_files, &specs);
+
+    if let Some(output) = &cli_args.output {
+        let format = cli_args.format
+            .or_else(|| OutputFormat::from_path(output))
+            .unwrap_or(OutputFormat::Xlsx);
+        let mut output_path = output.clone();
+        if output_path.extension().is_none() {output_path.set_extension(format.extension());}
+        let issues = if cli_args.issues {Some(&file_issues)} else {None};
+        if let Err(msg) = write_output(&data_files, &output_path, format, cli_args.long, issues, &config, &specs) {
+            eprintln!("{}", msg);
+            return EXIT_OUTPUT;
+        }//end if we couldn't write the output
+        eprintln!("Wrote {} of {} file(s) to \"{}\".", data_files.len(), input_paths.len(), output_path.to_string_lossy());
+    }//end if we should write an output file
+
+    if let Some(db_path) = &cli_args.database {
+        match database::write_output_to_database(db_path, &data_files, &config) {
+            Ok((inserted, skipped)) => eprintln!("Saved {} test(s) to database \"{}\", skipping {} already saved.", inserted, db_path.to_string_lossy(), skipped),
+            Err(msg) => {
+                eprintln!("There was an issue saving to the database \"{}\":\n{}", db_path.to_string_lossy(), msg);
+                return EXIT_OUTPUT;
+            },
+        }//end matching whether we could save to the database
+    }//end if we should save to a database
+
+    if !config.diff_tolerances.is_empty() {
+        let flagged: Vec<&Data> = data_files.iter().filter(|data| !data.get_flagged_diffs(&config.diff_tolerances).is_empty()).collect();
+        eprintln!("{} of {} test(s) had a Diff outside tolerance.", flagged.len(), data_files.len());
+        for data in flagged {
+            eprintln!("  {}: {}", data.test_name, data.get_flagged_diffs(&config.diff_tolerances).join(", "));
+        }//end listing each flagged test
+    }//end if there are tolerances to check
+
+    let graded: Vec<(&Data, &SpecProfile)> = data_files.iter()
+        .filter_map(|data| specs.get_profile(&data.spec_name).map(|profile| (data, profile)))
+        .collect();
+    if !graded.is_empty() {
+        let failed = graded.iter().filter(|(data, profile)| !profile.get_failed_headers(&spec::get_graded_rows(data)).is_empty()).count();
+        eprintln!("{} of {} graded test(s) passed their spec, and {} failed.", graded.len() - failed, graded.len(), failed);
+    }//end if any tests were graded
+
+    if left_out_files > 0 {EXIT_PARTIAL} else {EXIT_SUCCESS}
+}//end run()
+
+/// Gathers input files like input::gather_input_paths(), except that directories are
+/// searched along with the folders inside them, using include_patterns and exclude_patterns.  
+/// Prints how many files were found in each directory.
+fn gather_recursive_input_paths(paths: &Vec<PathBuf>, include_patterns: &[String], exclude_patterns: &[String]) -> Result<Vec<PathBuf>,String> {
+    let mut input_paths: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let scan = input::scan_input_folder(path, include_patterns, exclude_patterns)?;
+            eprintln!("Found {} file(s) in \"{}\", leaving out {} matching the exclude patterns and {} not matching the include patterns.", scan.included.len(), path.to_string_lossy(), scan.excluded.len(), scan.unmatched);
+            for excluded_dir in scan.excluded_dirs.iter() {eprintln!("Left out the folder \"{}\", which matches an exclude pattern.", excluded_dir.to_string_lossy());}
+            for msg in scan.unreadable_dirs.iter() {eprintln!("{}", msg);}
+            for dir_path in scan.included {
+                if !input_paths.contains(&dir_path) {input_paths.push(dir_path);}
+            }//end adding each file from the directory
+        }//end if path is a directory
+        else if path.is_file() {
+            if !input_paths.contains(path) {input_paths.push(path.clone());}
+        }//end else if path is a file
+        else {
+            return Err(format!("The input path \"{}\" doesn't exist.", path.to_string_lossy()));
+        }//end else path isn't anything we can read
+    }//end looping over each path given
+    Ok(input_paths)
+}//end gather_recursive_input_paths()
+
+/// Writes data to output_path as the given format.
+/// If long is true, long-format output is written as well.
+/// If issues are given, they are written to an issues sheet, or for csv or tsv, a second file ending in -issues.  
+/// Columns are ordered by the row order preference in config.
+/// For xlsx, Diff values outside the tolerances in config are highlighted, and tests are graded against specs.
+fn write_output(data_files: &Vec<Data>, output_path: &PathBuf, format: OutputFormat, long: bool, issues: Option<&Vec<FileIssues>>, config: &ConfigStore, specs: &SpecStore) -> Result<(),String> {
+    match format.delimiter() {
+        Some(delimiter) => {
+            if let Err(err) = write_output_to_delimited(data_files, output_path, delimiter, &config.row_order_preference) {
+                return Err(format!("There was an issue writing output to \"{}\":\n{}", output_path.to_string_lossy(), err));
+            }//end if there was an error writing the delimited file
+            if long {
+                let long_path = process::get_long_output_path(output_path);
+                if let Err(err) = process::write_long_output_to_delimited(data_files, &long_path, delimiter) {
+                    return Err(format!("There was an issue writing long-format output to \"{}\":\n{}", long_path.to_string_lossy(), err));
+                }//end if there was an error writing the long delimited file
+            }//end if we should write long-format output too
+            if let Some(issues) = issues {
+                let issues_path = process::get_issues_output_path(output_path);
+                if let Err(err) = process::write_issues_to_delimited(issues, &issues_path, delimiter) {
+                    return Err(format!("There was an issue writing issues to \"{}\":\n{}", issues_path.to_string_lossy(), err));
+                }//end if there was an error writing the issues delimited file
+            }//end if we should write issues too
+            Ok(())
+        },
+        None => {
+            let mut wb = get_workbook();
+            if let Err(err) = write_output_to_sheet(&mut wb, data_files, "alveograph-exporter-output", &config.row_order_preference, &config.diff_tolerances, specs) {
+                return Err(format!("There was an issue writing output data to the sheet:\n{}", err));
+            }//end if there was an error writing to the sheet
+            if long {
+                if let Err(err) = process::write_long_output_to_sheet(&mut wb, data_files, process::LONG_OUTPUT_SHEET_NAME) {
+                    return Err(format!("There was an issue writing long-format output to the sheet:\n{}", err));
+                }//end if there was an error writing to the long sheet
+            }//end if we should write long-format output too
+            if let Some(issues) = issues {
+                if let Err(err) = process::write_issues_to_sheet(&mut wb, issues, process::ISSUES_SHEET_NAME) {
+                    return Err(format!("There was an issue writing the issues sheet:\n{}", err));
+                }//end if there was an error writing to the issues sheet
+            }//end if we should write the issues sheet
+            if let Err(err) = close_workbook(&mut wb, output_path) {
+                return Err(format!("There was an issue closing the workbook \"{}\":\n{}", output_path.to_string_lossy(), err));
+            }//end if there was an error closing the workbook
+            Ok(())
+        },
+    }//end matching whether this is a delimited format
+}//end write_output()
+
+/// Runs watch mode with the given arguments, checking the watched folder until the
+/// program is stopped, or just once if asked.  
+/// Returns the exit code the program should finish with.
+fn run_watch(watch_args: &WatchArgs) -> i32 {
+    let config = match get_config(&watch_args.config, &watch_args.profile) {
+        Ok(config) => config,
+        Err(msg) => {eprintln!("{}", msg); return EXIT_CONFIG;},
+    };
+    let config_problems = config.validate();
+    for problem in config_problems.iter() {eprintln!("Config {}: {}", problem.severity(), problem);}
+    if config_problems.iter().any(|problem| problem.severity() == data::IssueSeverity::Error) {return EXIT_CONFIG;}
+    let specs = match get_specs(&watch_args.specs) {
+        Ok(specs) => specs,
+        Err(msg) => {eprintln!("{}", msg); return EXIT_CONFIG;},
+    };
+    for problem in config.validate_specs(&specs).iter() {eprintln!("Config {}: {}", problem.severity(), problem);}
+    let settings = &watch_args.settings;
+    if !settings.watch_dir.is_dir() {
+        eprintln!("The folder to watch \"{}\" doesn't exist.", settings.watch_dir.to_string_lossy());
+        return EXIT_INPUT;
+    }//end if there's nothing to watch
+    let ledger_path = settings.ledger_path();
+    let mut ledger = match watch::try_read_ledger(&ledger_path) {
+        Ok(ledger) => ledger,
+        Err(msg) => {eprintln!("Couldn't read the ledger \"{}\":\n{}", ledger_path.to_string_lossy(), msg); return EXIT_CONFIG;},
+    };
+
+    // the local offset can only be found reliably before other threads are running
+    let offset = watch::get_local_offset();
+    if !watch_args.once {eprintln!("Watching \"{}\". Press Ctrl+C to stop.", settings.watch_dir.to_string_lossy());}
+    loop {
+        let report = watch::poll_watch_folder(settings, &mut ledger, &config, &specs, SystemTime::now(), offset);
+        for line in report.get_lines() {eprintln!("{}", line);}
+        if let Err(msg) = watch::try_write_ledger(&ledger_path, &ledger) {
+            eprintln!("Couldn't save the ledger \"{}\":\n{}", ledger_path.to_string_lossy(), msg);
+            return EXIT_OUTPUT;
+        }//end if we couldn't save the ledger
+        if watch_args.once {
+            return if report.problems.is_empty() {EXIT_SUCCESS} else {EXIT_PARTIAL};
+        }//end if we should only check once
+        thread::sleep(watch_args.interval);
+    }//end checking the watched folder until stopped
+}//end run_watch()
+
+/// Gets the config to use for processing.
+/// If a path was given, it's used as the override for config_store::find_config_path(),
+/// and must exist. Otherwise, the config saved by the gui is used if it exists, or the
+/// default config if it doesn't, found the same way as the gui.  
+/// If a profile name was given, that profile is used, otherwise the one used last.
+fn get_config(config_path: &Option<PathBuf>, profile: &Option<String>) -> Result<ConfigStore,String> {
+    let (config_path, location) = config_store::find_config_path(config_store::CONFIG_FILE_NAME, config_path.as_ref())?;
+    let config_profiles = match location {
+        ConfigLocation::Override => config_store::try_read_profiles(&config_path)
+            .map_err(|msg| format!("Couldn't read the config file \"{}\":\n{}", config_path.to_string_lossy(), msg))?,
+        _ if config_path.exists() => config_store::try_read_profiles(&config_path)
+            .map_err(|msg| format!("Couldn't read the saved config file \"{}\":\n{}", config_path.to_string_lossy(), msg))?,
+        _ => ConfigProfiles::default(),
+    };//end matching where the config file came from
+    match profile {
+        Some(name) => match config_profiles.get(name) {
+            Some(config) => Ok(config.clone()),
+            None => Err(format!("There isn't a config profile named \"{}\". The profiles are: {}", name, config_profiles.names().join(", "))),
+        },
+        None => Ok(config_profiles.current()),
+    }//end matching whether we were given a profile name
+}//end get_config()
+
+/// Gets the spec profiles to use, from specs_path if given, otherwise from
+/// the saved specs file next to the saved config if there is one, otherwise an empty SpecStore.
+fn get_specs(specs_path: &Option<PathBuf>) -> Result<SpecStore,String> {
+    let (specs_path, location) = config_store::find_config_path(spec::SPEC_FILE_NAME, specs_path.as_ref())?;
+    match location {
+        ConfigLocation::Override => spec::try_read_specs(&specs_path)
+            .map_err(|msg| format!("Couldn't read the specs file \"{}\":\n{}", specs_path.to_string_lossy(), msg)),
+        _ if specs_path.exists() => spec::try_read_specs(&specs_path)
+            .map_err(|msg| format!("Couldn't read the saved specs file \"{}\":\n{}", specs_path.to_string_lossy(), msg)),
+        _ => Ok(SpecStore::default()),
+    }//end matching where the specs file came from
+}//end get_specs()
@@ -1,5 +1,15 @@
 use serde::{Deserialize, Serialize};
-use std::{env, fs::{self, File}, io::Write, path::PathBuf};
+use serde_json::{json, Value};
+use std::{cmp::Ordering, env, ffi::OsString, fmt, fs::{self, File}, hash::{Hash, Hasher}, io::Write, path::{Path, PathBuf}};
+use time::OffsetDateTime;
+
+use crate::{data::{self, IssueSeverity}, spec::SpecStore};
+
+/// The version of the config file written by this version of the program.  
+/// Fields added to ConfigStore are filled in from ConfigStore::default() when they're
+/// missing, so this only needs to increase when a change can't be handled that way,
+/// such as a field being renamed. When it does, add a migration to migrate_config_json().
+pub const CONFIG_VERSION: u32 = 1;
 
 /// An enum to represent different ways of finding the header in a file.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
@@ -64,10 +74,204 @@ impl ReadRowMode {
     }//end from_str()
 }//end impl for ReadRowMode
 
+/// An enum to represent the character between the whole and fractional parts of numbers in a file.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+pub enum DecimalSeparator {
+    /// The separator is decided for each file from the numbers in it,
+    /// using data::NumberFormat::detect().
+    Auto,
+    /// Numbers are written like 85.3
+    Point,
+    /// Numbers are written like 85,3
+    Comma,
+}//end enum DecimalSeparator
+
+impl DecimalSeparator {
+    /// Returns string representation of variant.
+    pub fn to_string(&self) -> String {
+        match self {
+            DecimalSeparator::Auto => "Auto".to_string(),
+            DecimalSeparator::Point => "Point".to_string(),
+            DecimalSeparator::Comma => "Comma".to_string(),
+        }//end matching self
+    }//end to_string()
+
+    /// Attempts to match label to variant.
+    pub fn from_str(str: &str) -> Option<DecimalSeparator> {
+        match str {
+            "Auto" => Some(DecimalSeparator::Auto),
+            "Point" => Some(DecimalSeparator::Point),
+            "Comma" => Some(DecimalSeparator::Comma),
+            _ => None,
+        }//end matching str
+    }//end from_str()
+
+    /// Gets the character this separator stands for, or None for Auto.
+    pub fn get_char(&self) -> Option<char> {
+        match self {
+            DecimalSeparator::Auto => None,
+            DecimalSeparator::Point => Some('.'),
+            DecimalSeparator::Comma => Some(','),
+        }//end matching self
+    }//end get_char()
+}//end impl for DecimalSeparator
+
+/// An enum to represent the character used to group thousands in numbers in a file, such as in 1,234.5
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+pub enum ThousandsSeparator {
+    /// Whichever of a point or comma isn't the decimal separator.
+    Auto,
+    /// Numbers aren't grouped in thousands.
+    None,
+    /// Numbers are written like 1.234,5
+    Point,
+    /// Numbers are written like 1,234.5
+    Comma,
+    /// Numbers are written like 1 234,5
+    Space,
+    /// Numbers are written like 1'234.5
+    Apostrophe,
+}//end enum ThousandsSeparator
+
+impl ThousandsSeparator {
+    /// Returns string representation of variant.
+    pub fn to_string(&self) -> String {
+        match self {
+            ThousandsSeparator::Auto => "Auto".to_string(),
+            ThousandsSeparator::None => "None".to_string(),
+            ThousandsSeparator::Point => "Point".to_string(),
+            ThousandsSeparator::Comma => "Comma".to_string(),
+            ThousandsSeparator::Space => "Space".to_string(),
+            ThousandsSeparator::Apostrophe => "Apostrophe".to_string(),
+        }//end matching self
+    }//end to_string()
+
+    /// Attempts to match label to variant.
+    pub fn from_str(str: &str) -> Option<ThousandsSeparator> {
+        match str {
+            "Auto" => Some(ThousandsSeparator::Auto),
+            "None" => Some(ThousandsSeparator::None),
+            "Point" => Some(ThousandsSeparator::Point),
+            "Comma" => Some(ThousandsSeparator::Comma),
+            "Space" => Some(ThousandsSeparator::Space),
+            "Apostrophe" => Some(ThousandsSeparator::Apostrophe),
+            _ => None,
+        }//end matching str
+    }//end from_str()
+
+    /// Gets the character this separator stands for, or None for Auto or None.
+    pub fn get_char(&self) -> Option<char> {
+        match self {
+            ThousandsSeparator::Auto | ThousandsSeparator::None => None,
+            ThousandsSeparator::Point => Some('.'),
+            ThousandsSeparator::Comma => Some(','),
+            ThousandsSeparator::Space => Some(' '),
+            ThousandsSeparator::Apostrophe => Some('\''),
+        }//end matching self
+    }//end get_char()
+}//end impl for ThousandsSeparator
+
+/// An enum to represent different ways of limiting the difference
+/// between the calculated average and the average from the file.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+pub enum ToleranceMode {
+    /// The limit is the largest allowed difference, in the units of the row.
+    Absolute,
+    /// The limit is the largest allowed difference, as a percentage of
+    /// the average read from the file.
+    Relative,
+}//end enum ToleranceMode
+
+impl ToleranceMode {
+    /// Returns string representation of variant.
+    pub fn to_string(&self) -> String {
+        match self {
+            ToleranceMode::Absolute => "Absolute".to_string(),
+            ToleranceMode::Relative => "Relative".to_string(),
+        }//end matching self
+    }//end to_string()
+
+    /// Attempts to match label to variant.
+    pub fn from_str(str: &str) -> Option<ToleranceMode> {
+        match str {
+            "Absolute" => Some(ToleranceMode::Absolute),
+            "Relative" => Some(ToleranceMode::Relative),
+            _ => None,
+        }//end matching str
+    }//end from_str()
+}//end impl for ToleranceMode
+
+/// The largest difference allowed between the calculated average
+/// and the average from the file for a single row header.  
+/// Comparisons and hashing use the bits of limit, so that ConfigStore can keep
+/// deriving Eq, Ord, and Hash even though limit is an f64.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DiffTolerance {
+    /// The row header this tolerance applies to, such as "W".
+    pub header: String,
+    pub mode: ToleranceMode,
+    /// The largest allowed difference. Depending on mode, this is
+    /// either an absolute value or a percentage.
+    pub limit: f64,
+}//end struct DiffTolerance
+
+impl DiffTolerance {
+    /// Creates a new DiffTolerance with given header, mode, and limit.
+    pub fn new(header: &str, mode: ToleranceMode, limit: f64) -> DiffTolerance {
+        DiffTolerance{header: header.to_string(), mode, limit}
+    }
+
+    /// Gets the largest difference allowed, given the average read from the file.
+    pub fn allowed_diff(&self, file_avg: f64) -> f64 {
+        match self.mode {
+            ToleranceMode::Absolute => self.limit.abs(),
+            ToleranceMode::Relative => file_avg.abs() * self.limit.abs() / 100.,
+        }//end matching mode
+    }//end allowed_diff()
+
+    /// Returns true if diff is further from 0 than allowed, given the average read from the file.
+    pub fn is_exceeded(&self, diff: f64, file_avg: f64) -> bool {
+        diff.abs() > self.allowed_diff(file_avg)
+    }//end is_exceeded()
+}//end impl DiffTolerance
+
+impl PartialEq for DiffTolerance {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }//end eq()
+}//end impl PartialEq for DiffTolerance
+
+impl Eq for DiffTolerance {}
+
+impl PartialOrd for DiffTolerance {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }//end partial_cmp()
+}//end impl PartialOrd for DiffTolerance
+
+impl Ord for DiffTolerance {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.header.cmp(&other.header)
+            .then(self.mode.cmp(&other.mode))
+            .then(self.limit.total_cmp(&other.limit))
+    }//end cmp()
+}//end impl Ord for DiffTolerance
+
+impl Hash for DiffTolerance {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.header.hash(state);
+        self.mode.hash(state);
+        self.limit.to_bits().hash(state);
+    }//end hash()
+}//end impl Hash for DiffTolerance
+
 /// This struct is meant to store configuration information
 /// in a way that is not reliant on a specific ui implementation,
-/// such that it can be passed around easily.
+/// such that it can be passed around easily.  
+/// Any fields missing when deserializing are taken from ConfigStore::default(),
+/// so that config files from older versions can still be read.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+#[serde(default)]
 pub struct ConfigStore {
     /// The exact string header above where the data rows start.
     pub read_start_header: String,
@@ -97,8 +301,202 @@ pub struct ConfigStore {
     /// The string directly in front of the test-name, which is used to label
     /// which file data came from.
     pub read_test_name_prefix: String,
+    /// The exact string at the start of the header above each curve's rows.
+    /// Every line starting with this prefix is treated as the header of a curve,
+    /// so "Standard\t : \tCurve" will find "Standard\t : \tCurve3", however many curves there are.
+    pub read_curve_header_prefix: String,
+    /// The path to the SQLite database that results are saved to when
+    /// saving to a database is turned on. If empty, the user will be asked for one.
+    pub database_path: String,
+    /// The row header used to automatically exclude aberrant curves, such as "L".
+    pub curve_exclusion_header: String,
+    /// The largest percentage a curve's value for curve_exclusion_header can differ from
+    /// the median of all the curves before that curve is excluded. If 0, no curves
+    /// are excluded automatically.
+    pub curve_exclusion_max_deviation: u16,
+    /// The largest differences allowed between calcAVG and AVG for each row header.
+    /// Diff values outside these are highlighted in the output. Row headers
+    /// without a tolerance are never highlighted.
+    pub diff_tolerances: Vec<DiffTolerance>,
+    /// Glob patterns for the files to use when choosing a whole folder as input, such as "**/*ALV*.txt".
+    /// See input::glob_match() for how they're matched.
+    pub input_include_patterns: Vec<String>,
+    /// Glob patterns for files to leave out when choosing a whole folder as input,
+    /// such as "archive/" to leave out every folder named archive.
+    pub input_exclude_patterns: Vec<String>,
+    /// The character between the whole and fractional parts of values in the average and curve rows.
+    pub decimal_separator: DecimalSeparator,
+    /// The character used to group thousands in values in the average and curve rows.
+    pub thousands_separator: ThousandsSeparator,
 }//end struct ConfigStore
 
+/// The different problems that validate() can find with a ConfigStore.
+#[derive(Clone,PartialEq,Eq,Debug)]
+pub enum ConfigProblem {
+    /// read_start_mode is Header, but read_start_header is blank.
+    BlankStartHeader,
+    /// read_row_split_char is empty, so rows can't be split into a header and value.
+    EmptySplitChar,
+    /// read_row_mode is Header, but read_row_headers doesn't have any headers.
+    NoRowHeaders,
+    /// read_row_mode is Max, but read_max_rows is 0.
+    ZeroMaxRows,
+    /// read_row_mode is Max, and a file ends before read_max_rows rows after the start header.
+    MaxRowsExceedsFile {filename: String, read_max_rows: usize, rows_available: usize},
+    /// A header in row_order_preference isn't in read_row_headers.
+    UnknownOrderHeader {header: String},
+    /// decimal_separator and thousands_separator are the same character.
+    SameSeparators {separator: String},
+    /// read_row_mode is Header, and a spec profile has a limit for a header that isn't in read_row_headers.
+    UnknownSpecHeader {spec: String, header: String},
+}//end enum ConfigProblem
+
+impl ConfigProblem {
+    /// Returns the severity of the problem. Errors mean that processing can't work,
+    /// and warnings mean that it might not work as expected.
+    pub fn severity(&self) -> IssueSeverity {
+        match self {
+            ConfigProblem::BlankStartHeader => IssueSeverity::Error,
+            ConfigProblem::EmptySplitChar => IssueSeverity::Error,
+            ConfigProblem::NoRowHeaders => IssueSeverity::Error,
+            ConfigProblem::ZeroMaxRows => IssueSeverity::Error,
+            ConfigProblem::MaxRowsExceedsFile{..} => IssueSeverity::Warning,
+            ConfigProblem::UnknownOrderHeader{..} => IssueSeverity::Warning,
+            ConfigProblem::SameSeparators{..} => IssueSeverity::Error,
+            ConfigProblem::UnknownSpecHeader{..} => IssueSeverity::Warning,
+        }//end matching self
+    }//end severity()
+
+    /// Returns the name of the ConfigStore field with the problem.
+    pub fn field(&self) -> &'static str {
+        match self {
+            ConfigProblem::BlankStartHeader => "read_start_header",
+            ConfigProblem::EmptySplitChar => "read_row_split_char",
+            ConfigProblem::NoRowHeaders => "read_row_headers",
+            ConfigProblem::ZeroMaxRows => "read_max_rows",
+            ConfigProblem::MaxRowsExceedsFile{..} => "read_max_rows",
+            ConfigProblem::UnknownOrderHeader{..} => "row_order_preference",
+            ConfigProblem::SameSeparators{..} => "thousands_separator",
+            ConfigProblem::UnknownSpecHeader{..} => "read_row_headers",
+        }//end matching self
+    }//end field()
+}//end impl ConfigProblem
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigProblem::BlankStartHeader => write!(f, "Read Start Header is blank, so the start of the rows can't be found. Type the header, or use a Read Start Mode of Index."),
+            ConfigProblem::EmptySplitChar => write!(f, "Row Split Character is empty, so rows can't be split into a header and value. The default is a tab."),
+            ConfigProblem::NoRowHeaders => write!(f, "Read Row Headers is empty, so no rows will be read. List the headers, or use a Read Row Mode of Max."),
+            ConfigProblem::ZeroMaxRows => write!(f, "Read Rows Max is 0, so no rows will be read."),
+            ConfigProblem::MaxRowsExceedsFile{filename, read_max_rows, rows_available} => write!(f, "Read Rows Max is {}, but \"{}\" only has {} lines after the start header.", read_max_rows, filename, rows_available),
+            ConfigProblem::UnknownOrderHeader{header} => write!(f, "Row Order Pref. lists {:?}, which isn't in Read Row Headers.", header),
+            ConfigProblem::SameSeparators{separator} => write!(f, "Decimal Separator and Thousands Separator are both {}, so numbers can't be read. Change one of them.", separator),
+            ConfigProblem::UnknownSpecHeader{spec, header} => write!(f, "Spec \"{}\" has a limit for {:?}, which isn't in Read Row Headers, so that limit won't be graded.", spec, header),
+        }//end matching self
+    }//end fmt()
+}//end impl Display for ConfigProblem
+
+impl ConfigStore {
+    /// Checks the settings for problems which would keep processing from working as expected.  
+    /// Only the settings themselves are checked, so this is cheap enough to run on every edit.
+    /// Use validate_files() to check the settings against the files about to be processed.  
+    /// Returns every problem found, or an empty Vec if there weren't any.
+    pub fn validate(&self) -> Vec<ConfigProblem> {
+        let mut problems = Vec::new();
+        if self.read_start_mode == ReadStartMode::Header && self.read_start_header.trim().is_empty() {
+            problems.push(ConfigProblem::BlankStartHeader);
+        }//end if there's no header to look for
+        if self.read_row_split_char.is_empty() {problems.push(ConfigProblem::EmptySplitChar);}
+        match self.read_row_mode {
+            ReadRowMode::Header => {
+                if self.read_row_headers.iter().all(|header| header.trim().is_empty()) {problems.push(ConfigProblem::NoRowHeaders);}
+                // in Max mode, the headers read come from the file, so they can't be known ahead of time
+                for header in self.row_order_preference.iter().filter(|header| !header.trim().is_empty()) {
+                    if !self.read_row_headers.contains(header) {
+                        problems.push(ConfigProblem::UnknownOrderHeader{header: header.clone()});
+                    }//end if this header won't be read
+                }//end checking each header in row_order_preference
+            },
+            ReadRowMode::Max => {
+                if self.read_max_rows == 0 {problems.push(ConfigProblem::ZeroMaxRows);}
+            },
+        }//end matching read_row_mode
+        if self.decimal_separator.get_char().is_some() && self.decimal_separator.get_char() == self.thousands_separator.get_char() {
+            problems.push(ConfigProblem::SameSeparators{separator: self.decimal_separator.to_string()});
+        }//end if the separators can't be told apart
+        problems
+    }//end validate()
+
+    /// Checks the settings against files which are about to be processed.  
+    /// input_files holds the name and contents of each file.  
+    /// Returns every problem found, or an empty Vec if there weren't any.
+    pub fn validate_files(&self, input_files: &[(String,String)]) -> Vec<ConfigProblem> {
+        let mut problems = Vec::new();
+        if self.read_row_mode != ReadRowMode::Max {return problems;}
+        for (filename, file_contents) in input_files {
+            let lines = data::split_lines(file_contents);
+            // files without a start header are reported when they're read
+            if let Ok(header_idx) = data::get_header_idx_from_lines(filename, &lines, self) {
+                let rows_available = lines.len().saturating_sub(header_idx + 1);
+                if rows_available < self.read_max_rows as usize {
+                    problems.push(ConfigProblem::MaxRowsExceedsFile{filename: filename.clone(), read_max_rows: self.read_max_rows as usize, rows_available});
+                }//end if the file ends too soon
+            }//end if we can find the start header
+        }//end checking each input file
+        problems
+    }//end validate_files()
+
+    /// Checks the limits of each spec profile in specs against the headers which will be read.  
+    /// This can only be known ahead of time when read_row_mode is Header, so nothing is
+    /// checked for other modes.  
+    /// Returns every problem found, or an empty Vec if there weren't any.
+    pub fn validate_specs(&self, specs: &SpecStore) -> Vec<ConfigProblem> {
+        let mut problems = Vec::new();
+        if self.read_row_mode != ReadRowMode::Header {return problems;}
+        for profile in specs.profiles.iter() {
+            for limit in profile.limits.iter().filter(|limit| !self.read_row_headers.contains(&limit.header)) {
+                problems.push(ConfigProblem::UnknownSpecHeader{spec: profile.name.clone(), header: limit.header.clone()});
+            }//end checking each limit without a column
+        }//end checking each spec profile
+        problems
+    }//end validate_specs()
+}//end impl ConfigStore
+
+/// A single setting which is different between two ConfigStores.
+#[derive(Clone,PartialEq,Eq,Debug)]
+pub struct ConfigDifference {
+    /// The name of the ConfigStore field which is different.
+    pub field: String,
+    /// The value in the first config, as json.
+    pub old_value: String,
+    /// The value in the second config, as json.
+    pub new_value: String,
+}//end struct ConfigDifference
+
+impl fmt::Display for ConfigDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.old_value, self.new_value)
+    }//end fmt()
+}//end impl Display for ConfigDifference
+
+/// Gets every setting which is different between old_config and new_config,
+/// sorted by field name.
+pub fn get_config_differences(old_config: &ConfigStore, new_config: &ConfigStore) -> Vec<ConfigDifference> {
+    let (old_json, new_json) = match (serde_json::to_value(old_config), serde_json::to_value(new_config)) {
+        (Ok(Value::Object(old_json)), Ok(Value::Object(new_json))) => (old_json, new_json),
+        _ => return Vec::new(),
+    };
+    old_json.iter()
+        .filter(|(field, old_value)| new_json.get(*field) != Some(*old_value))
+        .map(|(field, old_value)| ConfigDifference {
+            field: field.clone(),
+            old_value: old_value.to_string(),
+            new_value: new_json.get(field).map(|new_value| new_value.to_string()).unwrap_or_default(),
+        })
+        .collect()
+}//end get_config_differences()
+
 impl Default for ConfigStore {
     fn default() -> Self {
         let read_row_headers = vec![
@@ -120,65 +518,422 @@ impl Default for ConfigStore {
             row_order_preference,
             read_row_split_char: "\t".to_string(),
             read_test_name_prefix: "Test name\t:\t".to_string(),
+            read_curve_header_prefix: "Standard\t : \tCurve".to_string(),
+            database_path: "".to_string(),
+            curve_exclusion_header: "L".to_string(),
+            curve_exclusion_max_deviation: 0,
+            diff_tolerances: Vec::new(),
+            input_include_patterns: vec!["**/*.txt".to_string()],
+            input_exclude_patterns: Vec::new(),
+            // configs saved before these settings existed read numbers the same way as before
+            decimal_separator: DecimalSeparator::Point,
+            thousands_separator: ThousandsSeparator::None,
         }//end struct construction
     }//end default()
 }//end impl Default for ConfigStore
 
-/// Attempts to determine the path to the config file.  
-/// Assumes that config file has filename of config_name and extension of .config.  
-/// If create_if_missing is true, and the file at path does not exist, then it will be created with default values.  
+/// The name of the config file, without its extension.
+pub const CONFIG_FILE_NAME: &str = "config";
+/// The environment variable which can be set to the directory config files should be kept in.
+pub const CONFIG_DIR_ENV_VAR: &str = "ALVEOGRAPH_EXPORTER_CONFIG_DIR";
+/// The name of the directory config files are kept in, inside the per-user config directory.
+pub const USER_CONFIG_DIR_NAME: &str = "alveograph-exporter-s";
+
+/// An enum to represent the different places a config file can be found.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum ConfigLocation {
+    /// The path was given explicitly, such as with --config.
+    Override,
+    /// The path is inside the directory named by CONFIG_DIR_ENV_VAR.
+    EnvVar,
+    /// The path is inside the per-user config directory.
+    UserDir,
+    /// The path is next to the exe, for portable installs.
+    ExeDir,
+}//end enum ConfigLocation
+
+impl ConfigLocation {
+    /// Returns a description of the variant, for showing to the user.
+    pub fn to_string(&self) -> String {
+        match self {
+            ConfigLocation::Override => "given explicitly".to_string(),
+            ConfigLocation::EnvVar => format!("from the {} environment variable", CONFIG_DIR_ENV_VAR),
+            ConfigLocation::UserDir => "in your user config folder".to_string(),
+            ConfigLocation::ExeDir => "next to the program, for a portable install".to_string(),
+        }//end matching self
+    }//end to_string()
+}//end impl for ConfigLocation
+
+/// Gets the per-user directory config files are kept in, if it can be determined.  
+/// This is USER_CONFIG_DIR_NAME inside %APPDATA% on Windows, ~/Library/Application Support
+/// on macOS, or $XDG_CONFIG_HOME (defaulting to ~/.config) elsewhere.
+pub fn get_user_config_dir() -> Option<PathBuf> {
+    let non_empty_var = |name: &str| env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+    let base_dir = if cfg!(windows) {non_empty_var("APPDATA")}
+    else if cfg!(target_os = "macos") {non_empty_var("HOME").map(|home| home.join("Library").join("Application Support"))}
+    else {non_empty_var("XDG_CONFIG_HOME").or_else(|| non_empty_var("HOME").map(|home| home.join(".config")))};
+    base_dir.map(|base_dir| base_dir.join(USER_CONFIG_DIR_NAME))
+}//end get_user_config_dir()
+
+/// Gets the directory containing the exe this program runs from.
+pub fn get_exe_dir() -> Result<PathBuf,String> {
+    match env::current_exe() {
+        Ok(exe_path) => match exe_path.parent() {
+            Some(exe_dir) => Ok(exe_dir.to_path_buf()),
+            None => Err(format!("Couldn't find the folder containing \"{}\"", exe_path.to_string_lossy())),
+        },
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we could get the current exe path
+}//end get_exe_dir()
+
+/// Attempts to determine the directory config.json and specs.json are kept in.
+/// Both files are always looked for in the same directory, which is the first of:
+/// 1. env_dir, the value of CONFIG_DIR_ENV_VAR, if it's set.
+/// 2. The per-user config directory from get_user_config_dir(), if CONFIG_FILE_NAME exists there.
+/// 3. The directory containing the exe, for portable installs, if CONFIG_FILE_NAME exists there.
+/// 4. The per-user config directory, unless it can't be determined, then the directory containing the exe.
+///
+/// env_dir is passed in rather than read here, so that callers and tests decide where it comes from.  
+/// This function does not create the directory.
+pub fn find_config_dir(env_dir: Option<OsString>) -> Result<(PathBuf,ConfigLocation), String> {
+    if let Some(env_dir) = env_dir.filter(|value| !value.is_empty()) {
+        return Ok((PathBuf::from(env_dir), ConfigLocation::EnvVar));
+    }//end if the user set the environment variable
+
+    let has_config = |dir: &PathBuf| get_config_file_path(dir, CONFIG_FILE_NAME).exists();
+    let user_dir = get_user_config_dir();
+    let exe_dir = get_exe_dir();
+    match (user_dir, exe_dir) {
+        (Some(user_dir), _) if has_config(&user_dir) => Ok((user_dir, ConfigLocation::UserDir)),
+        (_, Ok(exe_dir)) if has_config(&exe_dir) => Ok((exe_dir, ConfigLocation::ExeDir)),
+        (Some(user_dir), _) => Ok((user_dir, ConfigLocation::UserDir)),
+        (None, Ok(exe_dir)) => Ok((exe_dir, ConfigLocation::ExeDir)),
+        (None, Err(msg)) => Err(msg),
+    }//end matching which directory to use
+}//end find_config_dir()
+
+/// Gets the path of the file named config_name, with an extension of .json, inside config_dir.
+pub fn get_config_file_path(config_dir: &Path, config_name: &str) -> PathBuf {
+    let mut config_path = config_dir.join(config_name);
+    config_path.set_extension("json");
+    config_path
+}//end get_config_file_path()
+
+/// Attempts to determine the path to a config file named config_name, with an extension of .json.  
+/// If override_path is given, such as from --config, it's used whether it exists or not.
+/// Otherwise, the file is inside the directory from find_config_dir(), using CONFIG_DIR_ENV_VAR.  
+/// This function does not create the file or its directory.
+pub fn find_config_path(config_name: &str, override_path: Option<&PathBuf>) -> Result<(PathBuf,ConfigLocation), String> {
+    if let Some(override_path) = override_path {return Ok((override_path.clone(), ConfigLocation::Override));}
+    let (config_dir, location) = find_config_dir(env::var_os(CONFIG_DIR_ENV_VAR))?;
+    Ok((get_config_file_path(&config_dir, config_name), location))
+}//end find_config_path()
+
+/// Attempts to determine the path to the config file, using find_config_path() without an override.  
+/// Assumes that config file has filename of config_name and extension of .json.  
+/// If create_if_missing is true, and the file at path does not exist, then it will be created with a single default profile.  
 /// If create_if_missing is false, then this function does not check whether or not the filepath exists.
 pub fn try_read_config_path(config_name: &str, create_if_missing: bool) -> Result<PathBuf, String> {
-    // directory which contains exe this program runs from
-    let exe_path = {
-        match env::current_exe() {
-            Ok(exe_path) => exe_path,
-            Err(error) => return Err(error.to_string()),
-        }//end matching whether we could get the current exe path
-    };
-
-    // set config path to be same parent as exe_path, but config_name
-    let config_path = {
-        let mut config_path = exe_path.clone();
-        config_path.set_file_name(config_name);
-        config_path.set_extension("json");
-        config_path
-    };
+    let (config_path, _) = find_config_path(config_name, None)?;
 
     // depending on parameter, ensure config file exists
     if !config_path.exists() && create_if_missing {
-        match File::create(config_path.clone()) {
-            Ok(mut file) => {
-                let default_config = ConfigStore::default();
-                match serde_json::to_string_pretty(&default_config) {
-                    Ok(serialized_config) => {
-                        match file.write_all(serialized_config.as_bytes()) {
-                            Ok(_) => (),
-                            Err(error) => return Err(error.to_string()),
-                        }//end matching whether file write was successful
-                    },
-                    Err(error) => return Err(error.to_string()),
-                }//end matching whether or not serde serialization worked
-            },
-            Err(error) => return Err(error.to_string()),
-        }//end matching if file was created
+        try_write_profiles(&config_path, &ConfigProfiles::default())?;
     }//end if config_path does not exist
 
     Ok(config_path)
 }//end try_read_config_path()
 
-/// Attempts to read contents of file at path and deserialize into ConfigStore object.
-pub fn try_read_config(config_path: &PathBuf) -> Result<ConfigStore,String> {
+/// The name of the profile created when there aren't any profiles yet.
+pub const DEFAULT_PROFILE_NAME: &str = "Default";
+
+/// A ConfigStore along with the name the user gave it.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct ConfigProfile {
+    pub name: String,
+    pub config: ConfigStore,
+}//end struct ConfigProfile
+
+/// Holds every named config profile, along with the one used last,
+/// so that they can all be saved to the same file.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct ConfigProfiles {
+    /// The version of the config file format, which is CONFIG_VERSION once read.
+    pub version: u32,
+    /// The name of the profile that was used last.  
+    /// If it's missing, the first profile is used.
+    #[serde(default)]
+    pub last_used: String,
+    /// Every profile, in the order they were created.
+    pub profiles: Vec<ConfigProfile>,
+}//end struct ConfigProfiles
+
+impl Default for ConfigProfiles {
+    fn default() -> Self {
+        ConfigProfiles::new(ConfigStore::default())
+    }//end default()
+}//end impl Default for ConfigProfiles
+
+impl ConfigProfiles {
+    /// Creates a new ConfigProfiles with a single profile named DEFAULT_PROFILE_NAME.
+    pub fn new(config: ConfigStore) -> ConfigProfiles {
+        ConfigProfiles {
+            version: CONFIG_VERSION,
+            last_used: DEFAULT_PROFILE_NAME.to_string(),
+            profiles: vec![ConfigProfile{name: DEFAULT_PROFILE_NAME.to_string(), config}],
+        }//end struct construction
+    }//end new()
+
+    /// Gets the name of every profile, in order.
+    pub fn names(&self) -> Vec<String> {
+        self.profiles.iter().map(|profile| profile.name.clone()).collect()
+    }//end names()
+
+    /// Gets the config of the profile with the given name, if there is one.
+    pub fn get(&self, name: &str) -> Option<&ConfigStore> {
+        self.profiles.iter().find(|profile| profile.name.eq(name)).map(|profile| &profile.config)
+    }//end get()
+
+    /// Gets the name of the profile in use, which is last_used if it exists,
+    /// or else the first profile.
+    pub fn current_name(&self) -> String {
+        match self.get(&self.last_used) {
+            Some(_) => self.last_used.clone(),
+            None => self.profiles.first().map(|profile| profile.name.clone()).unwrap_or_default(),
+        }//end matching whether last_used still exists
+    }//end current_name()
+
+    /// Gets the config of the profile in use.
+    pub fn current(&self) -> ConfigStore {
+        self.get(&self.current_name()).cloned().unwrap_or_default()
+    }//end current()
+
+    /// Replaces the config of the profile in use.
+    pub fn set_current(&mut self, config: ConfigStore) {
+        let current_name = self.current_name();
+        match self.profiles.iter_mut().find(|profile| profile.name.eq(&current_name)) {
+            Some(profile) => profile.config = config,
+            None => *self = ConfigProfiles::new(config),
+        }//end matching whether we have a profile to update
+    }//end set_current()
+
+    /// Makes the profile with the given name the one in use.
+    pub fn switch(&mut self, name: &str) -> Result<(),String> {
+        if self.get(name).is_none() {return Err(format!("There isn't a profile named \"{}\".", name));}
+        self.last_used = name.to_string();
+        Ok(())
+    }//end switch()
+
+    /// Checks that name could be used for a new profile.
+    fn check_new_name(&self, name: &str) -> Result<(),String> {
+        if name.trim().is_empty() {return Err("Profile names can't be blank.".to_string());}
+        if self.get(name).is_some() {return Err(format!("There's already a profile named \"{}\".", name));}
+        Ok(())
+    }//end check_new_name()
+
+    /// Adds a new profile with the given name and config, and makes it the one in use.
+    pub fn create(&mut self, name: &str, config: ConfigStore) -> Result<(),String> {
+        self.check_new_name(name)?;
+        self.profiles.push(ConfigProfile{name: name.to_string(), config});
+        self.switch(name)
+    }//end create()
+
+    /// Adds a copy of the profile named from, called name, and makes it the one in use.
+    pub fn duplicate(&mut self, from: &str, name: &str) -> Result<(),String> {
+        let config = match self.get(from) {
+            Some(config) => config.clone(),
+            None => return Err(format!("There isn't a profile named \"{}\".", from)),
+        };
+        self.create(name, config)
+    }//end duplicate()
+
+    /// Renames the profile named from to name.
+    pub fn rename(&mut self, from: &str, name: &str) -> Result<(),String> {
+        self.check_new_name(name)?;
+        match self.profiles.iter_mut().find(|profile| profile.name.eq(from)) {
+            Some(profile) => profile.name = name.to_string(),
+            None => return Err(format!("There isn't a profile named \"{}\".", from)),
+        }//end matching whether we found the profile to rename
+        if self.last_used.eq(from) {self.last_used = name.to_string();}
+        Ok(())
+    }//end rename()
+
+    /// Deletes the profile with the given name. The last profile can't be deleted.  
+    /// If the profile was in use, the first remaining profile is used instead.
+    pub fn delete(&mut self, name: &str) -> Result<(),String> {
+        if self.profiles.len() <= 1 {return Err("The last profile can't be deleted.".to_string());}
+        match self.profiles.iter().position(|profile| profile.name.eq(name)) {
+            Some(idx) => {self.profiles.remove(idx);},
+            None => return Err(format!("There isn't a profile named \"{}\".", name)),
+        }//end matching whether we found the profile to delete
+        if self.last_used.eq(name) {self.last_used = self.profiles[0].name.clone();}
+        Ok(())
+    }//end delete()
+}//end impl ConfigProfiles
+
+/// Gets the version of a config file from its json.  
+/// Config files from before versions were added don't have a version field.
+/// Those with profiles are version 1, and those holding a single ConfigStore are version 0.
+pub fn get_config_version(file_json: &Value) -> u32 {
+    match file_json.get("version").and_then(|version| version.as_u64()) {
+        Some(version) => version as u32,
+        None if file_json.get("profiles").is_some() => 1,
+        None => 0,
+    }//end matching whether the file has a version
+}//end get_config_version()
+
+/// Updates the json of a config file to CONFIG_VERSION, one version at a time.  
+/// Files from a newer version than CONFIG_VERSION are left as they are.
+pub fn migrate_config_json(file_json: Value) -> Value {
+    let mut file_json = file_json;
+    let mut version = get_config_version(&file_json);
+    while version < CONFIG_VERSION {
+        file_json = match version {
+            0 => migrate_config_v0(file_json),
+            _ => file_json,
+        };//end matching which migration to use
+        version += 1;
+    }//end migrating until we reach the current version
+    if version == CONFIG_VERSION {file_json["version"] = json!(CONFIG_VERSION);}
+    file_json
+}//end migrate_config_json()
+
+/// Migrates a config file holding a single ConfigStore, from before profiles,
+/// into a file with that ConfigStore as its only profile.
+fn migrate_config_v0(file_json: Value) -> Value {
+    json!({
+        "version": 1,
+        "last_used": DEFAULT_PROFILE_NAME,
+        "profiles": [{"name": DEFAULT_PROFILE_NAME, "config": file_json}],
+    })
+}//end migrate_config_v0()
+
+/// Attempts to read the version of the config file at path, without migrating it.
+pub fn try_read_config_version(config_path: &PathBuf) -> Result<u32,String> {
     match fs::read_to_string(config_path) {
-        Ok(file_contents) => {
-            match serde_json::from_str(&file_contents) {
-                Ok(config_store) => Ok(config_store),
-                Err(error) => Err(error.to_string()),
-            }//end matching whether we can deserialize config
+        Ok(file_contents) => match serde_json::from_str::<Value>(&file_contents) {
+            Ok(file_json) => Ok(get_config_version(&file_json)),
+            Err(error) => Err(error.to_string()),
         },
-        Err(error) => Err(error.to_string())
+        Err(error) => Err(error.to_string()),
     }//end matching whether we could read string from file
-}//end try_read_config()
+}//end try_read_config_version()
+
+/// Attempts to read the contents of the file at path as a ConfigProfiles.  
+/// Files from older versions are migrated with migrate_config_json(), but the
+/// file itself isn't changed.
+pub fn try_read_profiles(config_path: &PathBuf) -> Result<ConfigProfiles,String> {
+    let file_contents = match fs::read_to_string(config_path) {
+        Ok(file_contents) => file_contents,
+        Err(error) => return Err(error.to_string()),
+    };
+    let file_json = match serde_json::from_str::<Value>(&file_contents) {
+        Ok(file_json) => file_json,
+        Err(error) => return Err(error.to_string()),
+    };
+    match serde_json::from_value::<ConfigProfiles>(migrate_config_json(file_json)) {
+        Ok(config_profiles) => Ok(config_profiles),
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we can deserialize profiles
+}//end try_read_profiles()
+
+/// Attempts to copy the config file at path to a backup next to it, named with
+/// the current UTC time, such as config-backup-20240131-154500.json.  
+/// If that backup already exists, a counter is added, such as config-backup-20240131-154500-2.json,
+/// so that an earlier backup is never overwritten.  
+/// This should be done before overwriting a config file that couldn't be read,
+/// or that came from a different version, so the user's settings aren't lost.  
+/// Returns the path of the backup.
+pub fn try_backup_config(config_path: &PathBuf) -> Result<PathBuf,String> {
+    let format_des = time::macros::format_description!("[year][month][day]-[hour][minute][second]");
+    let timestamp = OffsetDateTime::now_utc().format(format_des).unwrap_or_default();
+    let config_stem = config_path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let backup_name = format!("{}-backup-{}", config_stem, timestamp);
+    let mut backup_path = config_path.with_file_name(format!("{}.json", backup_name));
+    let mut counter = 1;
+    while backup_path.exists() {
+        counter += 1;
+        backup_path = config_path.with_file_name(format!("{}-{}.json", backup_name, counter));
+    }//end looking for a backup name that isn't taken
+    match fs::copy(config_path, &backup_path) {
+        Ok(_) => Ok(backup_path),
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we could copy the config file
+}//end try_backup_config()
+
+/// Attempts to write given config_profiles to the given path.
+pub fn try_write_profiles(config_path: &PathBuf, config_profiles: &ConfigProfiles) -> Result<(),String> {
+    // the per-user config directory might not exist yet
+    if let Some(config_dir) = config_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        if let Err(error) = fs::create_dir_all(config_dir) {return Err(error.to_string());}
+    }//end if the config file is in a directory
+    match serde_json::to_string_pretty(config_profiles) {
+        Ok(profiles_serial) => {
+            match fs::write(config_path, profiles_serial) {
+                Ok(_) => Ok(()),
+                Err(error) => Err(error.to_string()),
+            }//end matching whether or not write succeeded
+        },
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we could serialize profiles
+}//end try_write_profiles()
+
+/// The ConfigStore fields every config file has had since the first version.  
+/// A config file being imported has to have each of these, so that json which was
+/// never a config isn't silently read as the default settings.
+pub const REQUIRED_CONFIG_FIELDS: [&str; 9] = [
+    "read_start_header",
+    "read_start_idx",
+    "read_row_headers",
+    "read_max_rows",
+    "read_start_mode",
+    "read_row_mode",
+    "row_order_preference",
+    "read_row_split_char",
+    "read_test_name_prefix",
+];
+
+/// Attempts to read the file at path as settings to import into a profile.  
+/// If the file holds config profiles instead, such as a copy of config.json,
+/// the profile used last is read. Files from a newer version than CONFIG_VERSION,
+/// and settings missing any of REQUIRED_CONFIG_FIELDS, are rejected.  
+/// Returns the settings, along with the name of each field that wasn't in the file
+/// and so has its default value, sorted by name.
+pub fn try_import_config(config_path: &PathBuf) -> Result<(ConfigStore,Vec<String>),String> {
+    let file_contents = fs::read_to_string(config_path).map_err(|error| error.to_string())?;
+    let file_json = serde_json::from_str::<Value>(&file_contents).map_err(|error| error.to_string())?;
+    let file_version = get_config_version(&file_json);
+    if file_version > CONFIG_VERSION {
+        return Err(format!("The file is from a newer version of the program, with config version {}, but this version can only read up to version {}.", file_version, CONFIG_VERSION));
+    }//end if the file is from a newer version
+
+    // after migrating, every file holds profiles, even if it started as a single config
+    let file_json = migrate_config_json(file_json);
+    let profiles_json = match file_json.get("profiles").and_then(|profiles| profiles.as_array()) {
+        Some(profiles_json) if !profiles_json.is_empty() => profiles_json,
+        _ => return Err("The file doesn't have any config profiles in it.".to_string()),
+    };
+    let last_used = file_json.get("last_used").and_then(|last_used| last_used.as_str()).unwrap_or_default();
+    let profile_json = profiles_json.iter()
+        .find(|profile| profile.get("name").and_then(|name| name.as_str()) == Some(last_used))
+        .unwrap_or(&profiles_json[0]);
+    let config_json = match profile_json.get("config") {
+        Some(Value::Object(config_json)) => config_json,
+        _ => return Err("The config profile in the file doesn't have any settings.".to_string()),
+    };
+    let missing_fields: Vec<&str> = REQUIRED_CONFIG_FIELDS.iter().copied().filter(|field| !config_json.contains_key(*field)).collect();
+    if !missing_fields.is_empty() {
+        return Err(format!("The file is missing settings every config file should have: {}", missing_fields.join(", ")));
+    }//end if the file doesn't look like a config
+
+    let config_store = serde_json::from_value::<ConfigStore>(Value::Object(config_json.clone())).map_err(|error| error.to_string())?;
+    let mut defaulted_fields: Vec<String> = match serde_json::to_value(ConfigStore::default()) {
+        Ok(Value::Object(default_json)) => default_json.keys().filter(|field| !config_json.contains_key(*field)).cloned().collect(),
+        _ => Vec::new(),
+    };
+    defaulted_fields.sort();
+    Ok((config_store, defaulted_fields))
+}//end try_import_config()
 
 /// Attempts to write given config_store to the given path.
 pub fn try_write_config(config_path: &PathBuf, config_store: &ConfigStore) -> Result<(),String> {
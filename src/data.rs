@@ -1,239 +1,691 @@
-use crate::config_store::ConfigStore;
+use std::{fmt, path::PathBuf};
 
-/// Represents a single row with a single value and header.
-#[derive(Clone,PartialEq,PartialOrd,Debug,Default)]
+use crate::config_store::{ConfigStore, DecimalSeparator, DiffTolerance, ThousandsSeparator};
+
+/// Represents a single row with a single value and header.  
+/// Rows are compared by header and value only, so a row read from a file
+/// equals a calculated row with the same numbers.
+#[derive(Clone,Debug,Default)]
 pub struct Row {
     pub header: String,
     pub value: f64,
+    /// The value exactly as it was written in the file, such as "1.234,5", kept so that
+    /// values can be checked against their files. This is empty for calculated rows.
+    pub raw_text: String,
 }//end struct Row
 
 impl Row {
-    /// Creates a new Row with given header and value
-    pub fn new(header: String, value: f64) -> Row {Row{header,value}}
+    /// Creates a new Row with given header and value, which wasn't read from a file.
+    pub fn new(header: String, value: f64) -> Row {Row{header,value,raw_text: String::new()}}
+    /// Creates a new Row with given header and value, read from raw_text in a file.
+    pub fn new1(header: String, value: f64, raw_text: String) -> Row {Row{header,value,raw_text}}
 }//end impl for Row
 
+impl PartialEq for Row {
+    fn eq(&self, other: &Self) -> bool {
+        self.header == other.header && self.value == other.value
+    }//end eq()
+}//end impl PartialEq for Row
+
+impl PartialOrd for Row {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        match self.header.partial_cmp(&other.header) {
+            Some(std::cmp::Ordering::Equal) => self.value.partial_cmp(&other.value),
+            ordering => ordering,
+        }//end matching whether the headers decide it
+    }//end partial_cmp()
+}//end impl PartialOrd for Row
+
+/// The separators used to read the values in a file, with any left on
+/// Auto in the config decided from the file itself.
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub struct NumberFormat {
+    /// The character between the whole and fractional parts of a number.
+    pub decimal: char,
+    /// The character grouping thousands, if numbers might be grouped.
+    pub thousands: Option<char>,
+}//end struct NumberFormat
+
+impl NumberFormat {
+    /// Gets the separators from config, deciding any left on Auto from value_texts,
+    /// which should be the text of every value read from a file.  
+    /// When the decimal separator is Auto, each value with both a point and a comma counts
+    /// towards whichever comes last, and each value with only one kind counts towards that one
+    /// unless it appears more than once, or is followed by exactly three digits after a whole part
+    /// other than 0, since that could just as well be grouping thousands. If the counts are tied,
+    /// a point is used, since that's what most files have.  
+    /// When the thousands separator is Auto, it's whichever of a point or comma isn't the decimal separator.
+    pub fn detect(value_texts: &[&str], config: &ConfigStore) -> NumberFormat {
+        let decimal = match (config.decimal_separator.get_char(), config.thousands_separator) {
+            (Some(decimal), _) => decimal,
+            (None, ThousandsSeparator::Point) => ',',
+            (None, ThousandsSeparator::Comma) => '.',
+            (None, _) => {
+                let mut point_votes = 0;
+                let mut comma_votes = 0;
+                for text in value_texts.iter().map(|text| text.trim()) {
+                    // only look at things which could be numbers
+                    if !text.chars().any(|c| c.is_ascii_digit()) || !text.chars().all(|c| c.is_ascii_digit() || "+-.,' ".contains(c)) {continue;}
+                    match (text.rfind('.'), text.rfind(',')) {
+                        (Some(point_idx), Some(comma_idx)) => if comma_idx > point_idx {comma_votes += 1} else {point_votes += 1},
+                        (Some(_), None) => match NumberFormat::vote_on_separator(text, '.') {
+                            Some(true) => point_votes += 1,
+                            Some(false) => comma_votes += 1,
+                            None => {},
+                        },
+                        (None, Some(_)) => match NumberFormat::vote_on_separator(text, ',') {
+                            Some(true) => comma_votes += 1,
+                            Some(false) => point_votes += 1,
+                            None => {},
+                        },
+                        (None, None) => {},
+                    }//end matching which separators are in the text
+                }//end counting up each value
+                if comma_votes > point_votes {','} else {'.'}
+            },
+        };
+        let thousands = match config.thousands_separator {
+            ThousandsSeparator::Auto => Some(if decimal == ',' {'.'} else {','}),
+            other => other.get_char(),
+        };
+        NumberFormat{decimal, thousands}
+    }//end detect()
+
+    /// Decides whether separator is a decimal separator in text, which only has that one kind of separator.  
+    /// Returns Some(true) if it must be, Some(false) if it must be grouping thousands, or None if it could be either.
+    fn vote_on_separator(text: &str, separator: char) -> Option<bool> {
+        if text.matches(separator).count() > 1 {return Some(false);}
+        let (whole, fraction) = text.split_once(separator)?;
+        let whole = whole.trim_start_matches(['+', '-']);
+        if fraction.len() != 3 || whole.is_empty() || whole == "0" {Some(true)} else {None}
+    }//end vote_on_separator()
+
+    /// Parses text as a number written with these separators.  
+    /// Thousands separators must group the whole part of the number in threes,
+    /// so that a value written with the wrong decimal separator isn't silently read as something else.
+    pub fn parse(&self, text: &str) -> Result<f64,String> {
+        let text = text.trim();
+        let other = if self.decimal == ',' {'.'} else {','};
+        let (whole, fraction) = match text.split_once(self.decimal) {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (text, None),
+        };
+        let whole = match self.thousands {
+            Some(thousands) if whole.contains(thousands) => {
+                let groups: Vec<&str> = whole.split(thousands).collect();
+                let first_digits = groups[0].trim_start_matches(['+', '-']).len();
+                if first_digits == 0 || first_digits > 3 || groups[1..].iter().any(|group| group.len() != 3 || !group.chars().all(|c| c.is_ascii_digit())) {
+                    return Err(format!("{:?} isn't grouped in thousands by {:?}", text, thousands));
+                }//end if the groups aren't in threes
+                groups.concat()
+            },
+            _ => whole.to_string(),
+        };
+        if whole.contains(other) || fraction.is_some_and(|fraction| fraction.contains(other) || fraction.contains(self.decimal)) {
+            return Err(format!("found {:?}, but the decimal separator is {:?}", other, self.decimal));
+        }//end if there are separators left which shouldn't be there
+        let number_text = match fraction {
+            Some(fraction) => format!("{}.{}", whole, fraction),
+            None => whole,
+        };
+        match number_text.parse::<f64>() {
+            Ok(value) => Ok(value),
+            Err(msg) => Err(msg.to_string()),
+        }//end matching whether the cleaned up text is a number
+    }//end parse()
+
+    /// Gets a copy of config with the separators set to these, so that nothing is left on Auto.
+    pub fn apply_to(&self, config: &ConfigStore) -> ConfigStore {
+        let mut config = config.clone();
+        config.decimal_separator = if self.decimal == ',' {DecimalSeparator::Comma} else {DecimalSeparator::Point};
+        config.thousands_separator = match self.thousands {
+            Some('.') => ThousandsSeparator::Point,
+            Some(',') => ThousandsSeparator::Comma,
+            Some(' ') => ThousandsSeparator::Space,
+            Some('\'') => ThousandsSeparator::Apostrophe,
+            _ => ThousandsSeparator::None,
+        };
+        config
+    }//end apply_to()
+}//end impl for NumberFormat
+
+/// Represents the rows of a single curve (or bubble) from a file,
+/// along with the label it was given in the file, such as "Curve3".
+#[derive(Clone,PartialEq,PartialOrd,Debug,Default)]
+pub struct Curve {
+    pub label: String,
+    pub row_data: Vec<Row>,
+    /// Whether this curve is left out of calc_avg() and the other calculations
+    /// across curves, such as for a bubble that burst early.
+    pub excluded: bool,
+}//end struct Curve
+
+impl Curve {
+    /// Creates a new Curve with given label and row_data, which isn't excluded.
+    pub fn new(label: String, row_data: Vec<Row>) -> Curve {Curve{label,row_data,excluded: false}}
+}//end impl for Curve
+
 /// Represents all the data from a file.
 #[derive(Clone,PartialEq,PartialOrd,Debug,Default)]
 pub struct Data {
     pub test_name: String,
+    /// The name of the file this data was read from, if known.
+    pub source_file: String,
+    /// The full path of the file this data was read from, if known.  
+    /// This isn't set by read_data_from_file(), since it only has the file contents.
+    pub source_path: PathBuf,
+    /// A hash of the bytes of the file this data was read from, from input::hash_file_contents().  
+    /// Like source_path, this isn't set by read_data_from_file(), since the bytes are needed.
+    pub source_hash: String,
     pub row_data: Vec<Row>,
-	pub curve_data1: Vec<Row>,
-	pub curve_data2: Vec<Row>,
-	pub curve_data3: Vec<Row>,
-	pub curve_data4: Vec<Row>,
-	pub curve_data5: Vec<Row>
+    /// The individual curves from the file, in the order they were found.
+    pub curves: Vec<Curve>,
+    /// The name of the spec profile this test is graded against, or empty if there isn't one.
+    pub spec_name: String,
 }//end struct Data
 
 impl Data {
-    /// Creates a new Data struct with given test_name, empty row_data.
+    /// Creates a new Data struct with given test_name, empty row_data and curves.
     pub fn new(test_name: String) -> Data {
-		Data{
-			test_name,
-			row_data:Vec::new(),
-			curve_data1:Vec::new(),
-			curve_data2:Vec::new(),
-			curve_data3:Vec::new(),
-			curve_data4:Vec::new(),
-			curve_data5:Vec::new(),
-			
-		}
-	}
-    /// Creates a new Data struct with given test_name and row_data.
-    pub fn new1(test_name: String, row_data: Vec<Row>, curve_data1: Vec<Row>, curve_data2: Vec<Row>, curve_data3: Vec<Row>, curve_data4: Vec<Row>,curve_data5: Vec<Row> ) -> Data {
-		Data{
-			test_name,
-			row_data,
-			curve_data1,
-			curve_data2,
-			curve_data3,
-			curve_data4,
-			curve_data5,
+        Data{
+            test_name,
+            source_file: String::new(),
+            source_path: PathBuf::new(),
+            source_hash: String::new(),
+            row_data: Vec::new(),
+            curves: Vec::new(),
+            spec_name: String::new(),
+        }
+    }
+    /// Creates a new Data struct with given test_name, row_data, and curves.
+    pub fn new1(test_name: String, row_data: Vec<Row>, curves: Vec<Curve>) -> Data {
+        Data{test_name, source_file: String::new(), source_path: PathBuf::new(), source_hash: String::new(), row_data, curves, spec_name: String::new()}
+    }
 
-		}
-	}
+    /// Gets every value of each row header across all the curves which aren't excluded.  
+    /// Headers are given in the order they are first found in the curves.
+    pub fn curve_values(&self) -> Vec<(String,Vec<f64>)> {
+        let mut values: Vec<(String,Vec<f64>)> = Vec::new();
+        for curve in self.curves.iter().filter(|curve| !curve.excluded) {
+            for row in curve.row_data.iter() {
+                match values.iter_mut().find(|(header,_)| header.eq(&row.header)) {
+                    Some((_,header_values)) => header_values.push(row.value),
+                    None => values.push((row.header.clone(), vec![row.value])),
+                }//end matching whether we've seen this header before
+            }//end adding each row in the curve
+        }//end looping over each curve
+        return values;
+    }//end curve_values()
+
+    /// Calculates a single value for each row header from its values in curve_values().  
+    /// If calc returns None for a header, that header is left out.
+    fn calc_per_header(&self, calc: impl Fn(&Vec<f64>) -> Option<f64>) -> Vec<Row> {
+        self.curve_values().into_iter()
+            .filter_map(|(header,values)| calc(&values).map(|value| Row::new(header, value)))
+            .collect()
+    }//end calc_per_header()
+
+    /// Calculates the average value of each row header across all the curves which aren't excluded.  
+    /// Headers are given in the order they are first found in the curves. If a curve
+    /// is missing a header, the average for that header only uses the curves which have it.
+    pub fn calc_avg(&self) -> Vec<Row> {
+        self.calc_per_header(|values| Some(mean(values)))
+    }//end calc_avg()
+
+    /// Calculates the sample standard deviation of each row header across all the curves which aren't excluded.  
+    /// Headers with fewer than two values are left out.
+    pub fn calc_std_dev(&self) -> Vec<Row> {
+        self.calc_per_header(std_dev)
+    }//end calc_std_dev()
+
+    /// Calculates the coefficient of variation of each row header across all the curves which aren't excluded,
+    /// as a percentage of the average.  
+    /// Headers with fewer than two values or an average of 0 are left out.
+    pub fn calc_cv(&self) -> Vec<Row> {
+        self.calc_per_header(|values| {
+            let avg = mean(values);
+            if avg == 0. {return None;}
+            std_dev(values).map(|std_dev| std_dev / avg.abs() * 100.)
+        })
+    }//end calc_cv()
+
+    /// Calculates the lowest value of each row header across all the curves which aren't excluded.
+    pub fn calc_min(&self) -> Vec<Row> {
+        self.calc_per_header(|values| values.iter().cloned().reduce(f64::min))
+    }//end calc_min()
+
+    /// Calculates the highest value of each row header across all the curves which aren't excluded.
+    pub fn calc_max(&self) -> Vec<Row> {
+        self.calc_per_header(|values| values.iter().cloned().reduce(f64::max))
+    }//end calc_max()
+
+    /// Calculates the difference between the highest and lowest value of
+    /// each row header across all the curves which aren't excluded.
+    pub fn calc_range(&self) -> Vec<Row> {
+        self.calc_per_header(|values| {
+            let min = values.iter().cloned().reduce(f64::min)?;
+            let max = values.iter().cloned().reduce(f64::max)?;
+            Some(max - min)
+        })
+    }//end calc_range()
+
+    /// Excludes each curve whose value for header differs from the median of all the curves
+    /// which aren't excluded by more than max_deviation percent of the median.  
+    /// Curves are only judged when at least three of those curves have a value for header,
+    /// since a median of fewer doesn't say which curve is the odd one out.  
+    /// Returns the labels of the curves newly excluded.
+    pub fn exclude_curves_by_median(&mut self, header: &str, max_deviation: f64) -> Vec<String> {
+        let mut values: Vec<f64> = self.curves.iter()
+            .filter(|curve| !curve.excluded)
+            .filter_map(|curve| curve.row_data.iter().find(|row| row.header.eq(header)))
+            .map(|row| row.value)
+            .collect();
+        if values.len() < 3 {return Vec::new();}
+        values.sort_by(|a,b| a.total_cmp(b));
+        let median = match values.len() % 2 {
+            0 => (values[values.len() / 2 - 1] + values[values.len() / 2]) / 2.,
+            _ => values[values.len() / 2],
+        };
+        let max_diff = median.abs() * max_deviation / 100.;
+        let mut excluded = Vec::new();
+        for curve in self.curves.iter_mut().filter(|curve| !curve.excluded) {
+            if let Some(row) = curve.row_data.iter().find(|row| row.header.eq(header)) {
+                if (row.value - median).abs() > max_diff {
+                    curve.excluded = true;
+                    excluded.push(curve.label.clone());
+                }//end if this curve is too far from the median
+            }//end if this curve has a value for header
+        }//end checking each curve against the median
+        return excluded;
+    }//end exclude_curves_by_median()
+
+    /// Calculates the difference between calc_avg() and the average
+    /// rows read from the file, for each header found in both.
+    pub fn calc_diff(&self) -> Vec<Row> {
+        let mut diff_rows = Vec::new();
+        for avg_row in self.calc_avg() {
+            if let Some(file_row) = self.row_data.iter().find(|row| row.header.eq(&avg_row.header)) {
+                diff_rows.push(Row::new(avg_row.header, avg_row.value - file_row.value));
+            }//end if the file has an average for this header
+        }//end looping over each calculated average
+        return diff_rows;
+    }//end calc_diff()
+
+    /// Gets the headers from calc_diff() which are outside their tolerance in tolerances.  
+    /// Headers without a tolerance are never included.
+    pub fn get_flagged_diffs(&self, tolerances: &[DiffTolerance]) -> Vec<String> {
+        let mut flagged = Vec::new();
+        for diff_row in self.calc_diff() {
+            let tolerance = tolerances.iter().find(|tolerance| tolerance.header.eq(&diff_row.header));
+            let file_row = self.row_data.iter().find(|row| row.header.eq(&diff_row.header));
+            if let (Some(tolerance), Some(file_row)) = (tolerance, file_row) {
+                if tolerance.is_exceeded(diff_row.value, file_row.value) {flagged.push(diff_row.header);}
+            }//end if we have a tolerance to check against
+        }//end checking each diff against its tolerance
+        return flagged;
+    }//end get_flagged_diffs()
 }//end impl Data
 
+/// Calculates the arithmetic mean of values.
+fn mean(values: &Vec<f64>) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}//end mean()
+
+/// Calculates the sample standard deviation of values,
+/// or None if there are fewer than two values.
+fn std_dev(values: &Vec<f64>) -> Option<f64> {
+    if values.len() < 2 {return None;}
+    let avg = mean(values);
+    let sum_sq = values.iter().map(|value| (value - avg).powi(2)).sum::<f64>();
+    Some((sum_sq / (values.len() - 1) as f64).sqrt())
+}//end std_dev()
+
+/// Represents how serious an issue found while reading a file is.
+#[derive(Clone,Copy,PartialEq,Eq,PartialOrd,Ord,Debug,Hash)]
+pub enum IssueSeverity {
+    /// Something worth knowing about the file, which doesn't affect the data read from it.
+    Note,
+    /// Reading could continue, but the data might be incomplete or wrong.
+    Warning,
+    /// Reading couldn't continue, so no data was read from the file.
+    Error,
+}//end enum IssueSeverity
+
+impl fmt::Display for IssueSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IssueSeverity::Note => write!(f, "Note"),
+            IssueSeverity::Warning => write!(f, "Warning"),
+            IssueSeverity::Error => write!(f, "Error"),
+        }//end matching self
+    }//end fmt()
+}//end impl Display for IssueSeverity
+
+/// The different kinds of issues that can come up while reading data from a file.
+#[derive(Clone,PartialEq,Debug)]
+pub enum ParseIssueKind {
+    /// The read start header couldn't be found in the file.
+    MissingHeader {header: String},
+    /// The read start header index is past the end of the file.
+    HeaderOutOfRange {header_idx: usize, line_count: usize},
+    /// There weren't any lines starting with the curve header prefix.
+    MissingCurves {prefix: String},
+    /// A curve label was found more than once. Only the first is used.
+    DuplicateCurve {label: String, first_line_idx: usize},
+    /// A row couldn't be split into a header and value.
+    BadSplit {line: String, split: String},
+    /// The value in a row couldn't be parsed as a number.
+    /// The column is the 0-based index of the first character of the value in the line.
+    BadNumber {text: String, column: usize, msg: String},
+    /// A row didn't start with the next header expected from read_row_headers.
+    UnexpectedRow {line: String, expected: String},
+    /// The file ended before all the rows in read_max_rows could be read.
+    NotEnoughRows {expected: usize, found: usize},
+    /// A header in row_order_preference wasn't in the rows read.
+    MissingSortHeader {header: String},
+    /// The file itself couldn't be read, so it was never parsed.
+    FileUnreadable {msg: String},
+    /// The file wasn't plain UTF-8, so it was read as this encoding instead.
+    Encoding {encoding: String},
+}//end enum ParseIssueKind
+
+impl ParseIssueKind {
+    /// Every category name returned by category(), in declaration order.
+    pub const CATEGORIES: [&'static str; 11] = [
+        "missing-header", "header-out-of-range", "missing-curves", "duplicate-curve",
+        "bad-split", "bad-number", "unexpected-row", "not-enough-rows", "missing-sort-header",
+        "file-unreadable", "encoding",
+    ];
+
+    /// Returns a short name for the kind of issue, ignoring its details.  
+    /// This is useful for counting or filtering issues.
+    pub fn category(&self) -> &'static str {
+        match self {
+            ParseIssueKind::MissingHeader{..} => "missing-header",
+            ParseIssueKind::HeaderOutOfRange{..} => "header-out-of-range",
+            ParseIssueKind::MissingCurves{..} => "missing-curves",
+            ParseIssueKind::DuplicateCurve{..} => "duplicate-curve",
+            ParseIssueKind::BadSplit{..} => "bad-split",
+            ParseIssueKind::BadNumber{..} => "bad-number",
+            ParseIssueKind::UnexpectedRow{..} => "unexpected-row",
+            ParseIssueKind::NotEnoughRows{..} => "not-enough-rows",
+            ParseIssueKind::MissingSortHeader{..} => "missing-sort-header",
+            ParseIssueKind::FileUnreadable{..} => "file-unreadable",
+            ParseIssueKind::Encoding{..} => "encoding",
+        }//end matching self
+    }//end category()
+
+    /// Returns the severity of this kind of issue.
+    pub fn severity(&self) -> IssueSeverity {
+        match self {
+            ParseIssueKind::MissingHeader{..} | ParseIssueKind::HeaderOutOfRange{..} | ParseIssueKind::FileUnreadable{..} => IssueSeverity::Error,
+            ParseIssueKind::Encoding{..} => IssueSeverity::Note,
+            _ => IssueSeverity::Warning,
+        }//end matching self
+    }//end severity()
+}//end impl ParseIssueKind
+
+impl fmt::Display for ParseIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseIssueKind::MissingHeader{header} => write!(f, "Could not find the header str {:?}", header),
+            ParseIssueKind::HeaderOutOfRange{header_idx, line_count} => write!(f, "The start header index {} is past the end of the file, which has {} lines", header_idx, line_count),
+            ParseIssueKind::MissingCurves{prefix} => write!(f, "Could not find any curve headers starting with {:?}", prefix),
+            ParseIssueKind::DuplicateCurve{label, first_line_idx} => write!(f, "Found the curve header {:?} again, after first finding it on line {}. Only the first will be used.", label, first_line_idx + 1),
+            ParseIssueKind::BadSplit{line, split} => write!(f, "Couldn't split {:?} into a header and value using {:?}", line, split),
+            ParseIssueKind::BadNumber{text, column, msg} => write!(f, "Failed to parse {:?} at column {} as a number: {}", text, column + 1, msg),
+            ParseIssueKind::UnexpectedRow{line, expected} => write!(f, "{:?} breaks row pattern, expected a row starting with {:?}", line, expected),
+            ParseIssueKind::NotEnoughRows{expected, found} => write!(f, "Expected {} rows, but the file only has {} more lines", expected, found),
+            ParseIssueKind::MissingSortHeader{header} => write!(f, "header not found: {:?}", header),
+            ParseIssueKind::FileUnreadable{msg} => write!(f, "Couldn't read the file: {}", msg),
+            ParseIssueKind::Encoding{encoding} => write!(f, "The file was read as {}, since it wasn't plain UTF-8", encoding),
+        }//end matching self
+    }//end fmt()
+}//end impl Display for ParseIssueKind
+
+/// Represents a single issue found while reading data from a file.
+#[derive(Clone,PartialEq,Debug)]
+pub struct ParseIssue {
+    /// The name of the file the issue was found in.
+    pub filename: String,
+    /// The 0-based index of the line the issue was found on, if it's about a particular line.
+    pub line_idx: Option<usize>,
+    pub kind: ParseIssueKind,
+}//end struct ParseIssue
+
+impl ParseIssue {
+    /// Creates a new ParseIssue with given filename, line index, and kind.
+    pub fn new(filename: &str, line_idx: Option<usize>, kind: ParseIssueKind) -> ParseIssue {
+        ParseIssue{filename: filename.to_string(), line_idx, kind}
+    }
+
+    /// Returns the severity of the issue.
+    pub fn severity(&self) -> IssueSeverity {self.kind.severity()}
+
+    /// Returns true if the issue is only a note, which shouldn't count as a problem with the file.
+    pub fn is_note(&self) -> bool {self.severity() == IssueSeverity::Note}
+}//end impl ParseIssue
+
+impl fmt::Display for ParseIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line_idx {
+            Some(line_idx) => write!(f, "{} in \"{}\" on line {}: {}", self.severity(), self.filename, line_idx + 1, self.kind),
+            None => write!(f, "{} in \"{}\": {}", self.severity(), self.filename, self.kind),
+        }//end matching whether we know the line
+    }//end fmt()
+}//end impl Display for ParseIssue
+
+/// Gives a short summary of how many issues of each category are in issues,
+/// such as "3 issue(s): 1 duplicate-curve, 2 missing-sort-header".
+pub fn summarize_issues(issues: &[ParseIssue]) -> String {
+    let counts: Vec<String> = ParseIssueKind::CATEGORIES.iter()
+        .map(|category| (category, issues.iter().filter(|issue| issue.kind.category().eq(*category)).count()))
+        .filter(|(_,count)| *count > 0)
+        .map(|(category,count)| format!("{} {}", count, category))
+        .collect();
+    format!("{} issue(s): {}", issues.len(), counts.join(", "))
+}//end summarize_issues()
+
+/// Gets the issues which aren't notes, for deciding whether a file had any problems.
+pub fn get_problems(issues: &[ParseIssue]) -> Vec<ParseIssue> {
+    issues.iter().filter(|issue| !issue.is_note()).cloned().collect()
+}//end get_problems()
+
+/// Formats each issue for display, one per line.
+pub fn format_issues(issues: &[ParseIssue]) -> String {
+    issues.iter().map(|issue| issue.to_string()).collect::<Vec<String>>().join("\n")
+}//end format_issues()
+
 /// Gets the test name, such as "24-PCF4001", from the lines of a file.
 pub fn get_test_name_from_lines(lines: &Vec<String>, config: &ConfigStore) -> Option<String> {
-    for line in lines.iter() {
-        if line.starts_with(&config.read_test_name_prefix) {
-            let test_name = line.replace(&config.read_test_name_prefix,"");
-            return Some(test_name);
-        }//end if we found the test_name_prefix
-    }//end looking for test_name in each line
-    return None;
+    find_test_name_idx(lines, config).map(|line_idx| lines[line_idx].replace(&config.read_test_name_prefix,""))
 }//end get_test_name_from_lines()
 
+/// Gets the index of the first line starting with the test name prefix, if there is one.
+pub fn find_test_name_idx(lines: &Vec<String>, config: &ConfigStore) -> Option<usize> {
+    lines.iter().position(|line| line.starts_with(&config.read_test_name_prefix))
+}//end find_test_name_idx()
+
+/// Gets the indices of every line exactly matching the given header.
+pub fn find_header_idxs(lines: &Vec<String>, header: &str) -> Vec<usize> {
+    lines.iter()
+        .enumerate()
+        .filter(|(_,line)| line.eq(&header))
+        .map(|(i,_)| i)
+        .collect()
+}//end find_header_idxs()
+
 /// Gets the index of the header before the rows we want to read, such as "Standard\t : \tAverage".
-pub fn get_header_idx_from_lines(filename: &str, lines: &Vec<String>, config: &ConfigStore) -> Result<usize,String> {
+pub fn get_header_idx_from_lines(filename: &str, lines: &Vec<String>, config: &ConfigStore) -> Result<usize,ParseIssue> {
     match config.read_start_mode {
         crate::config_store::ReadStartMode::Index => Ok(config.read_start_idx as usize),
         crate::config_store::ReadStartMode::Header => {
-            for (i,line) in lines.iter().enumerate() {
-                if line.eq(&config.read_start_header) {
-                    return Ok(i);
-                }//end if we found the start_header
-            }//end looking for start header in each line
-            return Err(format!("Could not find the header str \"{}\" in file \"{}\"", config.read_start_header, filename));
+            match find_header_idxs(lines, &config.read_start_header).first() {
+                Some(i) => Ok(*i),
+                None => Err(ParseIssue::new(filename, None, ParseIssueKind::MissingHeader{header: config.read_start_header.clone()})),
+            }//end matching whether we found the start header
         },
     }//end matching the read_start_mode
 }//end get_header_idx_from_lines()
 
-/// Reads data from a given file.  
-/// If the process fails, a string will be returned, holding a message about the failure.  
-/// If any issues occur that don't prevent completion, they will be returned as well, as strings.
-pub fn read_data_from_file(filename: &str, file_contents: &str, config: &ConfigStore) -> Result<(Data,Vec<String>),String> {
-    // init error message list
-    let mut errs = Vec::new();
-    // split up contents into lines
-    let lines: Vec<&str> = file_contents.split(['\n']).collect();
-    // clean out any carriage returns and convert to string
-    let lines: Vec<String> = lines.iter().map(|s| s.trim_matches('\r').to_string()).collect();
-    // find the test_name
-    let test_name = get_test_name_from_lines(&lines, config).unwrap_or_else(|| format!("Unknown test name from {:?}", filename));
-    // find the line with the header
-    let header_idx = match get_header_idx_from_lines(filename, &lines, config) {
-        Ok(h) => h,
-        Err(err) => return Err(err),
+/// Gets the label and index of the header before the rows of each curve, such as "Standard\t : \tCurve3".  
+/// Any line starting with config.read_curve_header_prefix is treated as a curve header, and the label
+/// is taken from the text after the last ':' in the line. If a label shows up more than once, the first
+/// one is used, and an issue about the duplicates is pushed to errs.
+pub fn get_curve_header_idxs_from_lines(filename: &str, lines: &Vec<String>, config: &ConfigStore, errs: &mut Vec<ParseIssue>) -> Vec<(String,usize)> {
+    let mut curve_headers: Vec<(String,usize)> = Vec::new();
+    if config.read_curve_header_prefix.is_empty() {return curve_headers;}
+    for (i,line) in lines.iter().enumerate() {
+        if !line.starts_with(&config.read_curve_header_prefix) {continue;}
+        let label = line.rsplit(':').next().unwrap_or(line).trim().to_string();
+        match curve_headers.iter().find(|(other_label,_)| other_label.eq(&label)) {
+            Some((_,first_idx)) => errs.push(ParseIssue::new(filename, Some(i), ParseIssueKind::DuplicateCurve{label, first_line_idx: *first_idx})),
+            None => curve_headers.push((label,i)),
+        }//end matching whether this is a duplicate curve header
+    }//end looking for curve headers in each line
+    if curve_headers.is_empty() {
+        errs.push(ParseIssue::new(filename, None, ParseIssueKind::MissingCurves{prefix: config.read_curve_header_prefix.clone()}));
+    }//end if we didn't find any curves
+    return curve_headers;
+}//end get_curve_header_idxs_from_lines()
+
+/// Gets the text of the value in each line which could be a row under the start header or a curve header,
+/// for deciding the number format of the file with NumberFormat::detect().
+pub fn get_value_texts_from_lines<'a>(lines: &'a Vec<String>, config: &ConfigStore) -> Vec<&'a str> {
+    let rows_per_block = match config.read_row_mode {
+        crate::config_store::ReadRowMode::Max => config.read_max_rows as usize,
+        crate::config_store::ReadRowMode::Header => config.read_row_headers.len(),
     };
-    // start reading rows after the header idx
+    let mut block_idxs: Vec<usize> = get_header_idx_from_lines("", lines, config).into_iter().collect();
+    if !config.read_curve_header_prefix.is_empty() {
+        block_idxs.extend(lines.iter().enumerate().filter(|(_,line)| line.starts_with(&config.read_curve_header_prefix)).map(|(i,_)| i));
+    }//end if there are curves to look for
+    let mut value_texts = Vec::new();
+    for block_idx in block_idxs {
+        for line in lines.iter().skip(block_idx + 1).take(rows_per_block) {
+            if let Some(value_text) = line.split(&config.read_row_split_char).nth(1) {value_texts.push(value_text);}
+        }//end getting the value from each row in the block
+    }//end looking at each block of rows
+    value_texts
+}//end get_value_texts_from_lines()
+
+/// Gets a copy of config with any separators left on Auto decided from the values in lines,
+/// so that the average and curve rows of a file are all read the same way.
+pub fn get_config_for_lines(lines: &Vec<String>, config: &ConfigStore) -> ConfigStore {
+    NumberFormat::detect(&get_value_texts_from_lines(lines, config), config).apply_to(config)
+}//end get_config_for_lines()
+
+/// Parses a single data row, at line_idx in the file, into a Row, using the split character
+/// and number separators from config. If the separators are left on Auto, they're decided from
+/// just this row, so use get_config_for_lines() first to read every row of a file the same way.  
+/// If the row can't be parsed, an issue is pushed to errs.
+fn parse_row_from_line(filename: &str, line_idx: usize, line: &str, config: &ConfigStore, errs: &mut Vec<ParseIssue>) -> Option<Row> {
+    let split_row: Vec<&str> = line.split(&config.read_row_split_char).collect();
+    if split_row.len() < 2 {
+        errs.push(ParseIssue::new(filename, Some(line_idx), ParseIssueKind::BadSplit{line: line.to_string(), split: config.read_row_split_char.clone()}));
+        None
+    } else {
+        let row_header = split_row[0].to_string();
+        let raw_text = split_row[1].trim();
+        match NumberFormat::detect(&[raw_text], config).parse(raw_text) {
+            Ok(row_value) => Some(Row::new1(row_header, row_value, raw_text.to_string())),
+            Err(msg) => {
+                let column = split_row[0].chars().count() + config.read_row_split_char.chars().count();
+                errs.push(ParseIssue::new(filename, Some(line_idx), ParseIssueKind::BadNumber{text: split_row[1].to_string(), column, msg}));
+                None
+            },
+        }//end matching whether we can parse the raw value
+    }//end else we can get split stuff find
+}//end parse_row_from_line()
+
+/// Reads the rows of data directly underneath the header at header_idx,
+/// using the read_row_mode from config to decide which rows to read.  
+/// Any issues encountered are pushed to errs.
+pub fn read_rows_from_lines(filename: &str, lines: &Vec<String>, header_idx: usize, config: &ConfigStore, errs: &mut Vec<ParseIssue>) -> Vec<Row> {
+    read_indexed_rows_from_lines(filename, lines, header_idx, config, errs)
+        .into_iter()
+        .map(|(_,row)| row)
+        .collect()
+}//end read_rows_from_lines()
+
+/// Reads rows the same way as read_rows_from_lines(), but also gives
+/// the index of the line each row was read from.
+pub fn read_indexed_rows_from_lines(filename: &str, lines: &Vec<String>, header_idx: usize, config: &ConfigStore, errs: &mut Vec<ParseIssue>) -> Vec<(usize,Row)> {
     let mut row_data = Vec::new();
     match config.read_row_mode {
         crate::config_store::ReadRowMode::Max => {
-            for line in &lines[(header_idx+1)..=(header_idx+(config.read_max_rows as usize))] {
-                let split_row: Vec<&str> = line.split(&config.read_row_split_char).collect();
-                if split_row.len() < 2 {errs.push(format!("Couldn't find a proper split for \"{:?}\", len < 2", split_row));}
-                else {
-                    let row_header = split_row[0].to_string();
-                    let row_value = split_row[1].trim().parse::<f64>();
-                    match row_value {
-                        Ok(row_value) => row_data.push(Row::new(row_header, row_value)),
-                        Err(msg) => errs.push(format!("Failed to parse \"{}\" in line \"{}\" as f64:\n{}",split_row[1],line,msg)),
-                    }//end matching whether we can parse the raw value
-                }//end else we can get split stuff find
+            let max_rows = config.read_max_rows as usize;
+            let rows_available = lines.len().saturating_sub(header_idx + 1);
+            if rows_available < max_rows {
+                errs.push(ParseIssue::new(filename, Some(header_idx), ParseIssueKind::NotEnoughRows{expected: max_rows, found: rows_available}));
+            }//end if the file ends before we can read all the rows
+            for (line_idx, line) in lines.iter().enumerate().skip(header_idx + 1).take(max_rows) {
+                if let Some(row) = parse_row_from_line(filename, line_idx, line, config, errs) {row_data.push((line_idx,row));}
             }//end looping over each line specified
         },
         crate::config_store::ReadRowMode::Header => {
             let mut header_offset = 0;
-            for line in &lines[(header_idx+1)..] {
+            for (line_idx, line) in lines.iter().enumerate().skip(header_idx + 1) {
                 if config.read_row_headers.len() <= header_offset {break;}
                 let this_row_header = config.read_row_headers.get(header_offset).expect("Already checked.");
                 if line.starts_with(this_row_header) {
-                    let split_row: Vec<&str> = line.split(&config.read_row_split_char).collect();
-                    if split_row.len() < 2 {errs.push(format!("Couldn't find a proper split for \"{:?}\", len < 2", split_row));}
-                    else {
-                        let row_header = split_row[0].to_string();
-                        let row_value = split_row[1].trim().parse::<f64>();
-                        match row_value {
-                            Ok(row_value) => row_data.push(Row::new(row_header, row_value)),
-                            Err(msg) => errs.push(format!("Failed to parse \"{}\" in line \"{}\" as f64:\n{}",split_row[1],line,msg)),
-                        }//end matching whether we can parse the row value
-                    }//end else we can get split stuff find
-                } else {errs.push(format!("{} breaks row pattern", line)); break;}
+                    if let Some(row) = parse_row_from_line(filename, line_idx, line, config, errs) {row_data.push((line_idx,row));}
+                } else {
+                    errs.push(ParseIssue::new(filename, Some(line_idx), ParseIssueKind::UnexpectedRow{line: line.to_string(), expected: this_row_header.clone()}));
+                    break;
+                }//end else this line breaks the pattern
                 header_offset += 1;
             }//end looping over each line specified
         },
     }//end matching the row read method
+    return row_data;
+}//end read_indexed_rows_from_lines()
+
+/// Splits the contents of a file into lines, cleaning out any carriage returns.
+pub fn split_lines(file_contents: &str) -> Vec<String> {
+    file_contents.split(['\n']).map(|s| s.trim_matches('\r').to_string()).collect()
+}//end split_lines()
+
+/// Reads data from a given file.  
+/// If the process fails, an issue with a severity of Error will be returned, describing the failure.  
+/// If any issues occur that don't prevent completion, they will be returned as well.
+pub fn read_data_from_file(filename: &str, file_contents: &str, config: &ConfigStore) -> Result<(Data,Vec<ParseIssue>),ParseIssue> {
+    // init error message list
+    let mut errs = Vec::new();
+    // split up contents into lines
+    let lines = split_lines(file_contents);
+    // decide the number format once, so every block is read the same way
+    let config = &get_config_for_lines(&lines, config);
+    // find the test_name
+    let test_name = get_test_name_from_lines(&lines, config).unwrap_or_else(|| format!("Unknown test name from {:?}", filename));
+    // find the line with the header
+    let header_idx = get_header_idx_from_lines(filename, &lines, config)?;
+    if header_idx >= lines.len() {
+        return Err(ParseIssue::new(filename, None, ParseIssueKind::HeaderOutOfRange{header_idx, line_count: lines.len()}));
+    }//end if header_idx is outside the file
+    // start reading rows after the header idx
+    let row_data = read_rows_from_lines(filename, &lines, header_idx, config, &mut errs);
 
     // sort the row_data based off config
     let mut sorted_0 = sort_row_data(filename, row_data, config);
-	row_data = sorted_0.0;
-	errs.append(&mut sorted_0.1);
-
-	let mut curve1 = Vec::new();
-	for line in &lines[41..=51] {
-		let split_row: Vec<&str> = line.split("\t").collect();
-		if split_row.len() < 2 {errs.push(format!("Couldn't find a proper split for \"{:?}\", len < 2", split_row));}
-		else {
-			let row_header = split_row[0].to_string();
-			let row_value = split_row[1].trim().parse::<f64>();
-			match row_value {
-				Ok(row_value) => curve1.push(Row::new(row_header, row_value)),
-				Err(msg) => errs.push(format!("Failed to parse \"{}\" in line \"{}\" as f64:\n{}",split_row[1],line,msg)),
-			}//end matching whether we can parse the raw value
-		}//end else we can get split stuff find
-	}
-	let mut sorted_1 = sort_row_data(filename, curve1, config);
-	curve1 = sorted_1.0;
-	errs.append(&mut sorted_1.1);
-
-	let mut curve2 = Vec::new();
-	for line in &lines[54..=64] {
-		let split_row: Vec<&str> = line.split("\t").collect();
-		if split_row.len() < 2 {errs.push(format!("Couldn't find a proper split for \"{:?}\", len < 2", split_row));}
-		else {
-			let row_header = split_row[0].to_string();
-			let row_value = split_row[1].trim().parse::<f64>();
-			match row_value {
-				Ok(row_value) => curve2.push(Row::new(row_header, row_value)),
-				Err(msg) => errs.push(format!("Failed to parse \"{}\" in line \"{}\" as f64:\n{}",split_row[1],line,msg)),
-			}//end matching whether we can parse the raw value
-		}//end else we can get split stuff find
-	}
-	let mut sorted_2 = sort_row_data(filename, curve2, config);
-	curve2 = sorted_2.0;
-	errs.append(&mut sorted_2.1);
-
-	let mut curve3 = Vec::new();
-	for line in &lines[67..=77] {
-		let split_row: Vec<&str> = line.split("\t").collect();
-		if split_row.len() < 2 {errs.push(format!("Couldn't find a proper split for \"{:?}\", len < 2", split_row));}
-		else {
-			let row_header = split_row[0].to_string();
-			let row_value = split_row[1].trim().parse::<f64>();
-			match row_value {
-				Ok(row_value) => curve3.push(Row::new(row_header, row_value)),
-				Err(msg) => errs.push(format!("Failed to parse \"{}\" in line \"{}\" as f64:\n{}",split_row[1],line,msg)),
-			}//end matching whether we can parse the raw value
-		}//end else we can get split stuff find
-	}
-	let mut sorted_3 = sort_row_data(filename, curve3, config);
-	curve3 = sorted_3.0;
-	errs.append(&mut sorted_3.1);
-
-	let mut curve4 = Vec::new();
-	for line in &lines[80..=90] {
-		let split_row: Vec<&str> = line.split("\t").collect();
-		if split_row.len() < 2 {errs.push(format!("Couldn't find a proper split for \"{:?}\", len < 2", split_row));}
-		else {
-			let row_header = split_row[0].to_string();
-			let row_value = split_row[1].trim().parse::<f64>();
-			match row_value {
-				Ok(row_value) => curve4.push(Row::new(row_header, row_value)),
-				Err(msg) => errs.push(format!("Failed to parse \"{}\" in line \"{}\" as f64:\n{}",split_row[1],line,msg)),
-			}//end matching whether we can parse the raw value
-		}//end else we can get split stuff find
-	}
-	let mut sorted_4 = sort_row_data(filename, curve4, config);
-	curve4 = sorted_4.0;
-	errs.append(&mut sorted_4.1);
-
-	let mut curve5 = Vec::new();
-	for line in &lines[93..=103] {
-		let split_row: Vec<&str> = line.split("\t").collect();
-		if split_row.len() < 2 {errs.push(format!("Couldn't find a proper split for \"{:?}\", len < 2", split_row));}
-		else {
-			let row_header = split_row[0].to_string();
-			let row_value = split_row[1].trim().parse::<f64>();
-			match row_value {
-				Ok(row_value) => curve5.push(Row::new(row_header, row_value)),
-				Err(msg) => errs.push(format!("Failed to parse \"{}\" in line \"{}\" as f64:\n{}",split_row[1],line,msg)),
-			}//end matching whether we can parse the raw value
-		}//end else we can get split stuff find
-	}
-	let mut sorted_5 = sort_row_data(filename, curve5, config);
-	curve5 = sorted_5.0;
-	errs.append(&mut sorted_5.1);
-
-    Ok((Data::new1(
-		test_name,
-		row_data,
-		curve1,
-		curve2,
-		curve3,
-		curve4,
-		curve5,
-	),errs))
+    let row_data = sorted_0.0;
+    errs.append(&mut sorted_0.1);
+
+    // find and read each of the curves
+    let mut curves = Vec::new();
+    for (label, curve_idx) in get_curve_header_idxs_from_lines(filename, &lines, config, &mut errs) {
+        let curve_rows = read_rows_from_lines(filename, &lines, curve_idx, config, &mut errs);
+        let mut sorted = sort_row_data(filename, curve_rows, config);
+        errs.append(&mut sorted.1);
+        curves.push(Curve::new(label, sorted.0));
+    }//end reading each curve
+
+    let mut data = Data::new1(test_name, row_data, curves);
+    if config.curve_exclusion_max_deviation > 0 {
+        data.exclude_curves_by_median(&config.curve_exclusion_header, config.curve_exclusion_max_deviation as f64);
+    }//end if we should exclude aberrant curves
+    data.source_file = filename.to_string();
+    Ok((data,errs))
 }//end read_data_from_file()
 
 /// Sorts the Vec of Rows based off of config row order pref.  
@@ -241,7 +693,7 @@ pub fn read_data_from_file(filename: &str, file_contents: &str, config: &ConfigS
 /// in front of unspecified rows.  
 /// Note: The sorting doesn't have great O(n) for speed or space, but n is small
 /// enough for the expected input that it shouldn't matter.
-pub fn sort_row_data(filename: &str, row_data: Vec<Row>, config: &ConfigStore) -> (Vec<Row>, Vec<String>) {
+pub fn sort_row_data(filename: &str, row_data: Vec<Row>, config: &ConfigStore) -> (Vec<Row>, Vec<ParseIssue>) {
     let mut new_row_data = Vec::new();
 	let mut errors = Vec::new();
     let mut row_data_taken: Vec<bool> = vec![false; row_data.len()];
@@ -255,7 +707,7 @@ pub fn sort_row_data(filename: &str, row_data: Vec<Row>, config: &ConfigStore) -
             }//end if we found a match
         }//end searching for position of matching header
 		if foundheader == false {
-			errors.push(format!("header not found: \"{}\" in {}",header_template,filename));
+			errors.push(ParseIssue::new(filename, None, ParseIssueKind::MissingSortHeader{header: header_template.clone()}));
 		}
 	}//end finding all the sorted headers we can
 
@@ -268,3 +720,97 @@ pub fn sort_row_data(filename: &str, row_data: Vec<Row>, config: &ConfigStore) -
 
     return (new_row_data, errors);
 }//end sort_row_data()
+
+/// The part a line of a file plays when reading data from it, as shown in a ParsePreview.
+#[derive(Clone,Copy,PartialEq,Eq,Debug,Hash)]
+pub enum PreviewLineKind {
+    /// The line isn't used.
+    Unused,
+    /// The line holds the test name.
+    TestName,
+    /// The line is the start header, above the average rows.
+    Header,
+    /// The line is one of the average rows which are read.
+    AverageRow,
+    /// The line is the header above a curve's rows.
+    CurveHeader,
+    /// The line is one of a curve's rows which are read.
+    CurveRow,
+}//end enum PreviewLineKind
+
+/// Shows which lines of a file are used when reading data from it,
+/// and what was read, so that config settings can be checked before processing.
+#[derive(Clone,PartialEq,Debug)]
+pub struct ParsePreview {
+    /// The lines of the file.
+    pub lines: Vec<String>,
+    /// The index of the line holding the test name, if found.
+    pub test_name_idx: Option<usize>,
+    /// The index of the start header, if found.
+    pub header_idx: Option<usize>,
+    /// The indices of the average rows which were read.
+    pub row_idxs: Vec<usize>,
+    /// The indices of each curve header.
+    pub curve_header_idxs: Vec<usize>,
+    /// The indices of the rows read for every curve.
+    pub curve_row_idxs: Vec<usize>,
+    /// The result of reading the file with read_data_from_file().
+    pub result: Result<(Data,Vec<ParseIssue>),ParseIssue>,
+}//end struct ParsePreview
+
+impl ParsePreview {
+    /// Gets the part the line at line_idx plays when reading data.
+    pub fn get_line_kind(&self, line_idx: usize) -> PreviewLineKind {
+        if self.test_name_idx == Some(line_idx) {PreviewLineKind::TestName}
+        else if self.header_idx == Some(line_idx) {PreviewLineKind::Header}
+        else if self.row_idxs.contains(&line_idx) {PreviewLineKind::AverageRow}
+        else if self.curve_header_idxs.contains(&line_idx) {PreviewLineKind::CurveHeader}
+        else if self.curve_row_idxs.contains(&line_idx) {PreviewLineKind::CurveRow}
+        else {PreviewLineKind::Unused}
+    }//end get_line_kind()
+
+    /// Gets lines describing the values that were read, followed by any issues.
+    pub fn get_value_lines(&self) -> Vec<String> {
+        // show what was in the file too, if it was written differently
+        let format_rows = |rows: &Vec<Row>| rows.iter().map(|row| {
+            if row.raw_text.is_empty() || row.raw_text == row.value.to_string() {format!("  {}: {}", row.header, row.value)}
+            else {format!("  {}: {} (from \"{}\")", row.header, row.value, row.raw_text)}
+        }).collect::<Vec<String>>();
+        match &self.result {
+            Err(issue) => vec![issue.to_string()],
+            Ok((data, issues)) => {
+                let mut value_lines = vec![format!("Test name: {}", data.test_name), "Average:".to_string()];
+                value_lines.append(&mut format_rows(&data.row_data));
+                for curve in data.curves.iter() {
+                    value_lines.push(format!("{}:{}", curve.label, if curve.excluded {" (excluded)"} else {""}));
+                    value_lines.append(&mut format_rows(&curve.row_data));
+                }//end adding the values of each curve
+                value_lines.extend(issues.iter().map(|issue| issue.to_string()));
+                value_lines
+            },
+        }//end matching whether the file could be read
+    }//end get_value_lines()
+}//end impl ParsePreview
+
+/// Reads data from a given file, keeping track of which lines were used, for previewing config settings.
+pub fn preview_file(filename: &str, file_contents: &str, config: &ConfigStore) -> ParsePreview {
+    let lines = split_lines(file_contents);
+    let number_config = get_config_for_lines(&lines, config);
+    let config = &number_config;
+    // issues are found again by read_data_from_file()
+    let mut errs = Vec::new();
+    let test_name_idx = find_test_name_idx(&lines, config);
+    let header_idx = get_header_idx_from_lines(filename, &lines, config).ok().filter(|header_idx| *header_idx < lines.len());
+    let row_idxs = match header_idx {
+        Some(header_idx) => read_indexed_rows_from_lines(filename, &lines, header_idx, config, &mut errs).into_iter().map(|(line_idx,_)| line_idx).collect(),
+        None => Vec::new(),
+    };
+    let mut curve_header_idxs = Vec::new();
+    let mut curve_row_idxs = Vec::new();
+    for (_, curve_idx) in get_curve_header_idxs_from_lines(filename, &lines, config, &mut errs) {
+        curve_header_idxs.push(curve_idx);
+        curve_row_idxs.extend(read_indexed_rows_from_lines(filename, &lines, curve_idx, config, &mut errs).into_iter().map(|(line_idx,_)| line_idx));
+    }//end finding the rows of each curve
+    let result = read_data_from_file(filename, file_contents, config);
+    ParsePreview{lines, test_name_idx, header_idx, row_idxs, curve_header_idxs, curve_row_idxs, result}
+}//end preview_file()
@@ -0,0 +1,126 @@
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::{config_store::ConfigStore, data::{Data, Row}, process::AVERAGE_BLOCK_LABEL};
+
+/// The statements used to create the tables in a new database.
+/// Each test is only stored once for each file hash, so processing the same
+/// file twice won't add the same results twice.
+pub const CREATE_TABLES: &str = "
+CREATE TABLE IF NOT EXISTS tests (
+    id INTEGER PRIMARY KEY,
+    test_name TEXT NOT NULL,
+    source_file TEXT NOT NULL,
+    source_path TEXT NOT NULL,
+    file_hash TEXT NOT NULL,
+    processed_at TEXT NOT NULL,
+    config TEXT NOT NULL,
+    UNIQUE (test_name, file_hash)
+);
+CREATE TABLE IF NOT EXISTS curves (
+    id INTEGER PRIMARY KEY,
+    test_id INTEGER NOT NULL REFERENCES tests(id),
+    position INTEGER NOT NULL,
+    label TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS row_values (
+    id INTEGER PRIMARY KEY,
+    test_id INTEGER NOT NULL REFERENCES tests(id),
+    curve_id INTEGER REFERENCES curves(id),
+    block TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    header TEXT NOT NULL,
+    value REAL NOT NULL,
+    raw_text TEXT NOT NULL DEFAULT ''
+);
+";
+
+/// Opens the database at db_path, creating it and its tables if they don't exist yet.
+pub fn open_database(db_path: &Path) -> Result<Connection,String> {
+    let conn = match Connection::open(db_path) {
+        Ok(conn) => conn,
+        Err(error) => return Err(error.to_string()),
+    };
+    match conn.execute_batch(CREATE_TABLES).and_then(|_| add_missing_columns(&conn)) {
+        Ok(_) => Ok(conn),
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we could create the tables
+}//end open_database()
+
+/// Adds any columns to the tables that databases created by older versions don't have yet.  
+/// Rows saved before a column was added get its default value.
+pub fn add_missing_columns(conn: &Connection) -> Result<(),rusqlite::Error> {
+    let has_raw_text: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('row_values') WHERE name = 'raw_text'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_raw_text {conn.execute_batch("ALTER TABLE row_values ADD COLUMN raw_text TEXT NOT NULL DEFAULT ''")?;}
+    Ok(())
+}//end add_missing_columns()
+
+/// Inserts a single Data into the database, along with its curves and rows.
+/// Excluded curves are left out, since they weren't used, but the position of
+/// each curve stored is still its position in the file.  
+/// If a test with the same name and file hash is already in the database, nothing is
+/// inserted and false is returned. Otherwise, true is returned.
+pub fn insert_data(conn: &mut Connection, data: &Data, config: &ConfigStore, processed_at: &str) -> Result<bool,rusqlite::Error> {
+    let tx = conn.transaction()?;
+    let existing: Option<i64> = tx.query_row(
+        "SELECT id FROM tests WHERE test_name = ?1 AND file_hash = ?2",
+        params![data.test_name, data.source_hash],
+        |row| row.get(0),
+    ).optional()?;
+    if existing.is_some() {return Ok(false);}
+
+    let config_json = serde_json::to_string(config).unwrap_or_default();
+    tx.execute(
+        "INSERT INTO tests (test_name, source_file, source_path, file_hash, processed_at, config) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![data.test_name, data.source_file, data.source_path.to_string_lossy(), data.source_hash, processed_at, config_json],
+    )?;
+    let test_id = tx.last_insert_rowid();
+    for (position, curve) in data.curves.iter().enumerate().filter(|(_, curve)| !curve.excluded) {
+        tx.execute(
+            "INSERT INTO curves (test_id, position, label) VALUES (?1, ?2, ?3)",
+            params![test_id, position as i64, curve.label],
+        )?;
+        let curve_id = tx.last_insert_rowid();
+        insert_rows(&tx, test_id, Some(curve_id), &curve.label, &curve.row_data)?;
+    }//end inserting each curve and its rows
+    insert_rows(&tx, test_id, None, AVERAGE_BLOCK_LABEL, &data.row_data)?;
+    tx.commit()?;
+    Ok(true)
+}//end insert_data()
+
+/// Inserts each row of a single block (a curve or the average) into the row_values table,
+/// along with the text each value was read from.
+fn insert_rows(tx: &rusqlite::Transaction, test_id: i64, curve_id: Option<i64>, block: &str, rows: &Vec<Row>) -> Result<(),rusqlite::Error> {
+    for (position, row) in rows.iter().enumerate() {
+        tx.execute(
+            "INSERT INTO row_values (test_id, curve_id, block, position, header, value, raw_text) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![test_id, curve_id, block, position as i64, row.header, row.value, row.raw_text],
+        )?;
+    }//end inserting each row
+    Ok(())
+}//end insert_rows()
+
+/// Opens the database at db_path and inserts every Data in data_files,
+/// all marked with the current time.
+/// Returns the number of tests inserted and the number skipped because
+/// they were already in the database.
+pub fn write_output_to_database(db_path: &Path, data_files: &Vec<Data>, config: &ConfigStore) -> Result<(usize,usize),String> {
+    let mut conn = open_database(db_path)?;
+    let processed_at = OffsetDateTime::now_utc().format(&Rfc3339).unwrap_or_default();
+    let mut inserted = 0;
+    let mut skipped = 0;
+    for data in data_files {
+        match insert_data(&mut conn, data, config, &processed_at) {
+            Ok(true) => inserted += 1,
+            Ok(false) => skipped += 1,
+            Err(error) => return Err(format!("Couldn't save test \"{}\" to the database:\n{}", data.test_name, error)),
+        }//end matching whether we inserted this data
+    }//end inserting each data file
+    Ok((inserted,skipped))
+}//end write_output_to_database()
@@ -1,12 +1,12 @@
-use std::{cell::RefCell, path::PathBuf, rc::Rc};
+use std::{cell::RefCell, path::{Path, PathBuf}, rc::Rc};
 
-use alveograph_exporter_s::config_store::{ConfigStore, ReadRowMode, ReadStartMode};
-use fltk::{app::{self, App, Receiver, Sender}, button::Button, dialog::{self, BeepType, FileDialogOptions, FileDialogType, NativeFileChooser}, enums::{Align, Color, Event, FrameType}, frame::Frame, group::{Flex, FlexType, Group, Tile}, image::PngImage, input::IntInput, menu::Choice, misc::HelpView, prelude::{ButtonExt, DisplayExt, GroupExt, InputExt, MenuExt, WidgetBase, WidgetExt, WindowExt}, text::{TextBuffer, TextDisplay, TextEditor, WrapMode}, window::{self, Window}};
+use alveograph_exporter_s::{batch::{BatchProgress, ParseBatch}, config_store::{ConfigProblem, ConfigStore, DecimalSeparator, DiffTolerance, ReadRowMode, ReadStartMode, ThousandsSeparator}, data::{Data, ParsePreview, PreviewLineKind}, input, process::{self, FileIssues, OutputFormat, ReviewRecord}, spec::SpecStore};
+use fltk::{app::{self, App, Receiver, Sender}, browser::{CheckBrowser, HoldBrowser}, button::{Button, CheckButton}, dialog::{self, BeepType, FileDialogOptions, FileDialogType, NativeFileChooser}, enums::{Align, CallbackTrigger, Color, Event, Font, FrameType, Shortcut}, frame::Frame, group::{Flex, FlexType, Group, Tile}, image::PngImage, input::{Input, IntInput}, menu::{Choice, MenuButton, MenuFlag}, misc::{HelpView, Progress}, prelude::{BrowserExt, ButtonExt, DisplayExt, GroupExt, InputExt, MenuExt, WidgetBase, WidgetExt, WindowExt}, text::{StyleTableEntryExt, TextAttr, TextBuffer, TextDisplay, TextEditor, WrapMode}, window::{self, Window}};
 
 /// Width in pixels of the main window
-const WINDOW_WIDTH: i32 = 750;
+const WINDOW_WIDTH: i32 = 800;
 /// Height in pixels of the main window
-const WINDOW_HEIGHT: i32 = 465;
+const WINDOW_HEIGHT: i32 = 685;
 
 /// FrameType to use for all major groups of widgets
 const GROUP_FRAME: FrameType = FrameType::GtkThinUpBox;
@@ -19,13 +19,17 @@ const HEADER_GROUP_WIDTH: i32 = 450;
 /// This will affect the sizes of other groups.
 const HEADER_GROUP_HEIGHT: i32 = 90;
 /// The height in pixels of the io_controls group
-const IO_CONTROLS_GROUP_HEIGHT: i32 = 175;
+const IO_CONTROLS_GROUP_HEIGHT: i32 = 195;
 /// Background color (set_color()) for the major group of io controls
 const IO_CONTROLS_GROUP_COLOR: Color = Color::from_rgb(245,255,250);
 /// Background color (set_color()) for the major group of config settings
 const CONFIG_GROUP_COLOR: Color = Color::from_rgb(220,239,220);
 /// Background color (set_color()) for the major group of integrated dialog
 const DIALOG_GROUP_COLOR: Color = Color::from_rgb(255,248,220);
+/// The height in pixels of the parse preview group along the bottom of the window
+const PREVIEW_GROUP_HEIGHT: i32 = 200;
+/// Background color (set_color()) for the major group of the parse preview
+const PREVIEW_GROUP_COLOR: Color = Color::from_rgb(240,245,255);
 
 /// Alignment to use for labels in the header group
 const HEADER_LABEL_ALIGN: Align = Align::Inside.union(Align::Center);
@@ -36,6 +40,8 @@ const HEADER_LABEL_COLOR: Color = Color::from_rgb(0,0,64);
 const IO_BTN_WIDTH: i32 = 150;
 /// The height in pixels of each fileIO button in the fileIO section.
 const IO_BTN_HEIGHT: i32 = 30;
+/// The width in pixels of the button for choosing an input folder in the fileIO section.
+const IO_DIR_BTN_WIDTH: i32 = 60;
 /// The amount of padding in pixels to put around each fileIO button in the fileIO section.
 const IO_BTN_PADDING: i32 = 10;
 /// The FrameType to use with each fileIO button in the fileIO section.
@@ -60,6 +66,47 @@ const IO_PRC_BTN_PADDING: i32 = 10;
 /// The width in pixels of the process button in the fileIO section. 
 /// The height is calculated based on the space available and the padding.
 const IO_PRC_BTN_WIDTH: i32 = 250;
+/// The width in pixels of the output format choice in the fileIO section.
+const IO_FORMAT_CHOICE_WIDTH: i32 = 80;
+/// The height in pixels of the output format choice in the fileIO section.
+const IO_FORMAT_CHOICE_HEIGHT: i32 = 25;
+/// The height in pixels of the watch folder button in the fileIO section.
+/// The width is the same as the output format choice.
+const IO_WATCH_BTN_HEIGHT: i32 = 22;
+/// The width in pixels of the window for choosing which curves to keep.
+const CURVE_REVIEW_WINDOW_WIDTH: i32 = 700;
+/// The height in pixels of the window for choosing which curves to keep.
+const CURVE_REVIEW_WINDOW_HEIGHT: i32 = 400;
+/// The width in pixels of the window for reviewing data before writing output.
+const DATA_REVIEW_WINDOW_WIDTH: i32 = 1000;
+/// The height in pixels of the window for reviewing data before writing output.
+const DATA_REVIEW_WINDOW_HEIGHT: i32 = 500;
+/// The height in pixels of the display showing details of the selected file when reviewing data.
+const DATA_REVIEW_DETAILS_HEIGHT: i32 = 120;
+/// The width in pixels of each column in the table when reviewing data.
+const DATA_REVIEW_COLUMN_WIDTHS: [i32; 6] = [60, 170, 150, 210, 150, 220];
+/// The width in pixels of the window for choosing the spec profile of a test.
+const SPEC_PICK_WINDOW_WIDTH: i32 = 400;
+/// The height in pixels of the window for choosing the spec profile of a test.
+const SPEC_PICK_WINDOW_HEIGHT: i32 = 110;
+/// The label of the choice for grading a test against no spec profile.
+const SPEC_PICK_NONE_LABEL: &str = "(no spec)";
+/// The width in pixels of the window showing progress while input files are parsed.
+const PARSE_PROGRESS_WINDOW_WIDTH: i32 = 600;
+/// The height in pixels of the window showing progress while input files are parsed.
+const PARSE_PROGRESS_WINDOW_HEIGHT: i32 = 400;
+/// The height in pixels of the progress bar while input files are parsed.
+const PARSE_PROGRESS_BAR_HEIGHT: i32 = 25;
+/// The width in pixels of each column in the list of files while input files are parsed.
+const PARSE_PROGRESS_COLUMN_WIDTHS: [i32; 2] = [300, 280];
+/// The number of seconds to wait between checks on worker threads while input files are parsed.
+const PARSE_PROGRESS_POLL_SECS: f64 = 0.05;
+/// The width in pixels of the window for choosing files from an input folder.
+const FOLDER_REVIEW_WINDOW_WIDTH: i32 = 600;
+/// The height in pixels of the window for choosing files from an input folder.
+const FOLDER_REVIEW_WINDOW_HEIGHT: i32 = 450;
+/// The height in pixels of each pattern input in the window for choosing files from an input folder.
+const FOLDER_REVIEW_INPUT_HEIGHT: i32 = 25;
 /// The Color to use for the textbox for input files in the fileIO section. 
 /// A gray color is recommended in order to indicate that it cannot be edited by the user.
 const IO_INPUT_BOX_COLOR: Color = Color::from_rgb(240,240,240);
@@ -129,7 +176,7 @@ const CONF_INPUT_FRAME: FrameType = FrameType::GleamRoundUpBox;
 /// The size in pixels of the scrollbar for input widgets in the config section.
 const CONF_INPUT_SCROLLBAR_SIZE: i32 = 5;
 /// THe height in pixels of the multiline input flex in the config section.
-const CONF_MULTI_INPUT_HEIGHT: i32 = 185;
+const CONF_MULTI_INPUT_HEIGHT: i32 = 145;
 /// The height in pixels of the button flex in the config section.
 const CONF_BUTTON_HEIGHT: i32 = 30;
 /// The text size in pixels to use for the line number in multiline inputs in the config group.
@@ -138,11 +185,42 @@ const CONF_MULTI_INPUT_LINENUMBER_SIZE: i32 = 10;
 const CONF_MULTI_INPUT_LINENUMBER_WIDTH: i32 = 15;
 /// The alignment to use for multi-line inputs in the config group.
 const CONF_MULIT_INPUT_SCROLLBAR_ALIGN: Align = Align::Right;
+/// The color of config widgets which don't have a problem.
+const CONF_INPUT_COLOR: Color = Color::Background2;
+/// The color of config widgets with a problem found by ConfigStore::validate().
+const CONF_PROBLEM_COLOR: Color = Color::from_rgb(255,215,215);
+/// The width of the label in front of the config profile choice.
+const CONF_PROFILE_LABEL_WIDTH: i32 = 50;
+/// The width of the menu button for editing config profiles.
+const CONF_PROFILE_MENU_WIDTH: i32 = 100;
+/// The width of the Config Reset button, which has the longest label of the config buttons.
+const CONF_RESET_BTN_WIDTH: i32 = 90;
+/// The width of the Config File button.
+const CONF_FILE_BTN_WIDTH: i32 = 80;
 /// The frame to use for buttons in the config section.
 const CONF_BTN_FRAME: FrameType = FrameType::GleamRoundUpBox;
 /// The down_frame to use for buttons in the config section.
 const CONF_BTN_DOWN_FRAME: FrameType = FrameType::GleamRoundDownBox;
 
+/// The padding in pixels to put around the displays in the parse preview section.
+const PREVIEW_BOX_PADDING: i32 = 5;
+/// The height in pixels of the label in the parse preview section.
+const PREVIEW_LABEL_HEIGHT: i32 = 20;
+/// The width in pixels of the display listing parsed values in the parse preview section.
+const PREVIEW_VALUES_WIDTH: i32 = 300;
+/// The text size to use for the displays in the parse preview section.
+const PREVIEW_TEXT_SIZE: i32 = 12;
+/// The width of the linenumber column in the file lines display in the parse preview section.
+const PREVIEW_LINENUMBER_WIDTH: i32 = 30;
+/// The background color of the line holding the test name in the parse preview section.
+const PREVIEW_TEST_NAME_COLOR: Color = Color::from_rgb(255,235,170);
+/// The background color of the start header and curve headers in the parse preview section.
+const PREVIEW_HEADER_COLOR: Color = Color::from_rgb(190,215,255);
+/// The background color of the average rows which are read in the parse preview section.
+const PREVIEW_ROW_COLOR: Color = Color::from_rgb(205,240,205);
+/// The background color of the curve rows which are read in the parse preview section.
+const PREVIEW_CURVE_ROW_COLOR: Color = Color::from_rgb(235,225,250);
+
 /// This enum is specifically intended for message passing from
 /// the GUI to the main function. This is done with Sender and 
 /// Receiver objects created in initialize().
@@ -153,9 +231,67 @@ pub enum InterfaceMessage {
     /// Indicates that the user wants to close the program
     AppClosing,
     /// Indicates that the user wants to reset the config to the default value
-    ConfigReset
+    ConfigReset,
+    /// Indicates that the user changed a config setting
+    ConfigEdited,
+    /// Indicates that the user wants to replace the config with one from a file
+    ConfigImport,
+    /// Indicates that the user wants to save the config to a file
+    ConfigExport,
+    /// Indicates that the user wants to know which config file is in use
+    ConfigFileInfo,
+    /// Indicates that the user chose a different config profile
+    ProfileSwitch,
+    /// Indicates that the user wants to create a new config profile
+    ProfileNew,
+    /// Indicates that the user wants to copy the current config profile
+    ProfileDuplicate,
+    /// Indicates that the user wants to rename the current config profile
+    ProfileRename,
+    /// Indicates that the user wants to delete the current config profile
+    ProfileDelete,
+    /// Indicates that the user chose different input files
+    InputsChanged,
+    /// Indicates that the user wants to choose input files from a folder
+    InputFolder,
+    /// Indicates that the user wants to start or stop watching a folder
+    WatchToggle,
+    /// Indicates that it's time to check the watched folder again
+    WatchTick,
+    /// Indicates that a check of the watched folder has finished on its worker thread
+    WatchDone,
 }//end enum InterfaceMessage
 
+/// The things a user can do while reviewing data in GUI::review_data_files().
+#[derive(Clone,Copy,PartialEq,Debug)]
+enum DataReviewAction {
+    /// The user selected a file in the table.
+    Select,
+    /// The user wants to include or leave out the selected file.
+    ToggleFile,
+    /// The user wants to change the test name of the selected file.
+    EditTestName,
+    /// The user wants to choose which curves of the selected file to keep.
+    PickCurves,
+    /// The user wants to choose the spec profile the selected file is graded against.
+    PickSpec,
+    /// The user is done reviewing and wants to write output.
+    Write,
+    /// The user wants to stop processing without writing output.
+    Cancel,
+}//end enum DataReviewAction
+
+/// The things a user can do while choosing files in GUI::review_input_folder().
+#[derive(Clone,Copy,PartialEq,Debug)]
+enum FolderReviewAction {
+    /// The user wants to find the files again with the patterns typed.
+    Refresh,
+    /// The user wants to use the files found.
+    Use,
+    /// The user doesn't want to use the folder.
+    Cancel,
+}//end enum FolderReviewAction
+
 /// This struct holds together all the objects and functions for manipulating and using the GUI.
 #[allow(dead_code)]
 pub struct GUI {
@@ -175,6 +311,22 @@ pub struct GUI {
     ux_output_box: Rc<RefCell<TextEditor>>,
     /// A reference to the path of a potential output path chosen by the user.
     last_output_path: Rc<RefCell<Option<PathBuf>>>,
+    /// The choice which displays options for the OutputFormat.
+    ux_output_format_choice: Choice,
+    /// The check button for whether to also write long-format output.
+    ux_long_output_check: CheckButton,
+    /// The check button for whether to also save results to a database.
+    ux_database_check: CheckButton,
+    /// The check button for whether to also write an issues sheet, or an issues file for delimited output.
+    ux_issues_check: CheckButton,
+    /// The check button for whether to let the user review the data read before writing output.
+    ux_review_data_check: CheckButton,
+    /// The check button for whether to let the user pick curves to exclude before writing output.
+    ux_pick_curves_check: CheckButton,
+    /// The button for starting and stopping watch mode.
+    ux_watch_btn: Button,
+    /// The timer which sends WatchTick messages while watching a folder.
+    watch_timer: Option<app::TimeoutHandle>,
     /// The group holding all the configuration controls.
     /// This is stored here in order to disable during dialog.
     ux_config_group: Group,
@@ -189,6 +341,8 @@ pub struct GUI {
     /// The flex which holds buttons corresponding to the 
     /// dialog choices available to a user.
     ux_dialog_btns_flx: Flex,
+    /// The label at the top of the config group, which also shows config problems.
+    ux_cf_label: Frame,
     /// The choice which displays options for the ReadStartMode.
     ux_cf_read_start_mode_choice: Choice,
     /// The choice which displays options for the ReadRowMode.
@@ -207,6 +361,24 @@ pub struct GUI {
     ux_cf_split_char_box: TextEditor,
     /// THe text editor that displays setting for read_test_name_prefix
     ux_cf_test_name_prefix_box: TextEditor,
+    /// The choice which displays options for the DecimalSeparator.
+    ux_cf_decimal_separator_choice: Choice,
+    /// The choice which displays options for the ThousandsSeparator.
+    ux_cf_thousands_separator_choice: Choice,
+    /// The choice which displays the names of the config profiles.
+    ux_cf_profile_choice: Choice,
+    /// The names of the config profiles, in the order shown in ux_cf_profile_choice.
+    profile_names: Vec<String>,
+    /// The label at the top of the parse preview group, showing which file is previewed.
+    ux_preview_label: Frame,
+    /// The display which shows the lines of the previewed file, highlighting the ones that are read.
+    ux_preview_lines_box: TextDisplay,
+    /// The display which lists the values read from the previewed file.
+    ux_preview_values_box: TextDisplay,
+    /// The config last passed to set_config_store().  
+    /// Settings without a widget are taken from here in get_config_store(),
+    /// so that they aren't lost when the gui config is saved.
+    config_base: ConfigStore,
 }//end struct GUI
 
 impl GUI {
@@ -216,10 +388,16 @@ impl GUI {
         return self.msg_receiver.clone();
     }//end get_receiver(self)
 
+    /// Returns a copy of the sender used by the gui, so that
+    /// worker threads can send messages back to the main loop.
+    pub fn get_sender(&self) -> Sender<InterfaceMessage> {
+        self.msg_sender
+    }//end get_sender(self)
+
     /// Gets a config store that represents the configuratino chosen
     /// by the user.
     pub fn get_config_store(&self) -> Result<ConfigStore,String> {
-        let mut config = ConfigStore::default();
+        let mut config = self.config_base.clone();
         
         match self.ux_cf_read_start_mode_choice.value() {
             0 => config.read_start_mode = ReadStartMode::Header,
@@ -281,11 +459,21 @@ impl GUI {
             Some(buf) => config.read_test_name_prefix = buf.text(),
         }//end matching whether or not we can access buffer for read_test_name_prefix
 
+        match self.ux_cf_decimal_separator_choice.choice().and_then(|label| DecimalSeparator::from_str(&label)) {
+            None => return Err(format!("Invalid decimal_separator menu choice {} !!!", self.ux_cf_decimal_separator_choice.value())),
+            Some(separator) => config.decimal_separator = separator,
+        }//end matching from label to variant for decimal_separator
+        match self.ux_cf_thousands_separator_choice.choice().and_then(|label| ThousandsSeparator::from_str(&label)) {
+            None => return Err(format!("Invalid thousands_separator menu choice {} !!!", self.ux_cf_thousands_separator_choice.value())),
+            Some(separator) => config.thousands_separator = separator,
+        }//end matching from label to variant for thousands_separator
+
         Ok(config)
     }//end get_config_store()
 
     /// Updates the gui to show the given configuration settings
     pub fn set_config_store(&mut self, config: &ConfigStore) -> Result<(),String> {
+        self.config_base = config.clone();
         match config.read_start_mode {
             ReadStartMode::Header => {let _ = self.ux_cf_read_start_mode_choice.set_value(0);},
             ReadStartMode::Index => {let _ = self.ux_cf_read_start_mode_choice.set_value(1);},
@@ -339,6 +527,11 @@ impl GUI {
         buf5.set_text(&config.read_test_name_prefix);
         self.ux_cf_test_name_prefix_box.set_buffer(buf5);
 
+        let decimal_idx = self.ux_cf_decimal_separator_choice.find_index(&config.decimal_separator.to_string());
+        self.ux_cf_decimal_separator_choice.set_value(decimal_idx);
+        let thousands_idx = self.ux_cf_thousands_separator_choice.find_index(&config.thousands_separator.to_string());
+        self.ux_cf_thousands_separator_choice.set_value(thousands_idx);
+
         Ok(())
     }//end set_config_store()
 
@@ -373,6 +566,582 @@ impl GUI {
         last_output_path.clone()
     }//end get_last_output_paths()
 
+    /// Gets the kind of output file chosen by the user.
+    pub fn get_output_format(&self) -> OutputFormat {
+        match self.ux_output_format_choice.value() {
+            1 => OutputFormat::Csv,
+            2 => OutputFormat::Tsv,
+            _ => OutputFormat::Xlsx,
+        }//end matching from value to variant for output format
+    }//end get_output_format()
+
+    /// Highlights the config widget for each problem, and lists the problems
+    /// in the tooltip of the config label. Widgets without a problem are un-highlighted.
+    pub fn highlight_config_problems(&mut self, problems: &[ConfigProblem]) {
+        let color_for = |field: &str| {
+            if problems.iter().any(|problem| problem.field() == field) {CONF_PROBLEM_COLOR} else {CONF_INPUT_COLOR}
+        };
+        self.ux_cf_read_start_idx_input.borrow_mut().set_color(color_for("read_start_idx"));
+        self.ux_cf_read_rows_max_input.borrow_mut().set_color(color_for("read_max_rows"));
+        self.ux_cf_read_start_header_box.borrow_mut().set_color(color_for("read_start_header"));
+        self.ux_cf_read_row_headers_box.borrow_mut().set_color(color_for("read_row_headers"));
+        self.ux_cf_row_order_pref_box.set_color(color_for("row_order_preference"));
+        self.ux_cf_split_char_box.set_color(color_for("read_row_split_char"));
+        self.ux_cf_test_name_prefix_box.set_color(color_for("read_test_name_prefix"));
+        let choice_color_for = |field: &str| {
+            if problems.iter().any(|problem| problem.field() == field) {CONF_PROBLEM_COLOR} else {CONF_CHOICE_COLOR}
+        };
+        self.ux_cf_thousands_separator_choice.set_color(choice_color_for("thousands_separator"));
+
+        if problems.is_empty() {
+            self.ux_cf_label.set_label("Configuration Settings");
+            self.ux_cf_label.set_label_color(Color::Black);
+            self.ux_cf_label.set_tooltip("");
+        } else {
+            let problem_lines: Vec<String> = problems.iter().map(|problem| format!("{}: {}", problem.severity(), problem)).collect();
+            self.ux_cf_label.set_label(&format!("Configuration Settings ({} problem(s))", problems.len()));
+            self.ux_cf_label.set_label_color(Color::DarkRed);
+            self.ux_cf_label.set_tooltip(&problem_lines.join("\n"));
+        }//end else we need to show the problems
+        self.ux_config_group.redraw();
+    }//end highlight_config_problems()
+
+    /// Shows names in the config profile choice, with current selected.
+    pub fn set_profile_names(&mut self, names: &Vec<String>, current: &str) {
+        self.ux_cf_profile_choice.clear();
+        for name in names {
+            // keep fltk from treating these as submenus or shortcuts
+            let label = name.replace('\\', "\\\\").replace('/', "\\/").replace('&', "&&");
+            self.ux_cf_profile_choice.add_emit(&label, Shortcut::None, MenuFlag::Normal, self.msg_sender, InterfaceMessage::ProfileSwitch);
+        }//end adding each profile name
+        let current_idx = names.iter().position(|name| name.eq(current)).unwrap_or(0);
+        self.ux_cf_profile_choice.set_value(current_idx as i32);
+        self.profile_names = names.clone();
+    }//end set_profile_names()
+
+    /// Gets the name of the config profile selected by the user, if any.
+    pub fn get_selected_profile(&self) -> Option<String> {
+        let choice_idx = self.ux_cf_profile_choice.value();
+        if choice_idx < 0 {return None;}
+        self.profile_names.get(choice_idx as usize).cloned()
+    }//end get_selected_profile()
+
+    /// Asks the user to type a name for a config profile, starting with default.  
+    /// Returns None if the user cancelled.
+    pub fn get_profile_name_from_user(&mut self, prompt: &str, default: &str) -> Option<String> {
+        dialog::input_default(prompt, default).map(|name| name.trim().to_string())
+    }//end get_profile_name_from_user()
+
+    /// Shows the lines of filename in the parse preview, highlighting the test name,
+    /// the headers, and the rows that are read, and lists the values read from it.
+    pub fn show_parse_preview(&mut self, filename: &str, preview: &ParsePreview) {
+        let mut style_text = String::new();
+        for (line_idx, line) in preview.lines.iter().enumerate() {
+            let style_char = match preview.get_line_kind(line_idx) {
+                PreviewLineKind::Unused => 'A',
+                PreviewLineKind::TestName => 'B',
+                PreviewLineKind::Header | PreviewLineKind::CurveHeader => 'C',
+                PreviewLineKind::AverageRow => 'D',
+                PreviewLineKind::CurveRow => 'E',
+            };
+            // the style buffer needs a character for each byte of text, including the newline
+            style_text.push_str(&style_char.to_string().repeat(line.len() + 1));
+        }//end picking a style for each line
+        let style_entry = |bgcolor: Color| StyleTableEntryExt{color: Color::Black, font: Font::Courier, size: PREVIEW_TEXT_SIZE, attr: TextAttr::BgColorExt, bgcolor};
+        let style_entries = vec![
+            StyleTableEntryExt{attr: TextAttr::None, ..style_entry(Color::White)},
+            style_entry(PREVIEW_TEST_NAME_COLOR),
+            style_entry(PREVIEW_HEADER_COLOR),
+            style_entry(PREVIEW_ROW_COLOR),
+            style_entry(PREVIEW_CURVE_ROW_COLOR),
+        ];
+        let mut lines_buf = TextBuffer::default();
+        lines_buf.set_text(&(preview.lines.join("\n") + "\n"));
+        let mut style_buf = TextBuffer::default();
+        style_buf.set_text(&style_text);
+        self.ux_preview_lines_box.set_buffer(lines_buf);
+        self.ux_preview_lines_box.set_highlight_data_ext(style_buf, style_entries);
+        // scroll to the start header, keeping a few lines above it in view
+        let top_line = preview.test_name_idx.or(preview.header_idx).unwrap_or(0);
+        self.ux_preview_lines_box.scroll(top_line.saturating_sub(2) as i32 + 1, 0);
+
+        let mut values_buf = TextBuffer::default();
+        values_buf.set_text(&preview.get_value_lines().join("\n"));
+        self.ux_preview_values_box.set_buffer(values_buf);
+        self.ux_preview_label.set_label(&format!("Parse Preview of \"{}\"", filename.replace('@', "@@")));
+        self.ux_main_window.redraw();
+    }//end show_parse_preview()
+
+    /// Clears the parse preview, showing msg in place of the file lines.
+    pub fn clear_parse_preview(&mut self, msg: &str) {
+        let mut lines_buf = TextBuffer::default();
+        lines_buf.set_text(msg);
+        self.ux_preview_lines_box.set_buffer(lines_buf);
+        self.ux_preview_lines_box.set_highlight_data_ext(TextBuffer::default(), Vec::<StyleTableEntryExt>::new());
+        self.ux_preview_values_box.set_buffer(TextBuffer::default());
+        self.ux_preview_label.set_label("Parse Preview");
+        self.ux_main_window.redraw();
+    }//end clear_parse_preview()
+
+    /// Returns true if the user wants long-format output written as well.
+    pub fn get_long_output(&self) -> bool {
+        self.ux_long_output_check.is_checked()
+    }//end get_long_output()
+
+    /// Returns true if the user wants results saved to a database as well.
+    pub fn get_database_output(&self) -> bool {
+        self.ux_database_check.is_checked()
+    }//end get_database_output()
+
+    /// Returns whether the user wants issues written along with the output.
+    pub fn get_issues_output(&self) -> bool {
+        self.ux_issues_check.is_checked()
+    }//end get_issues_output()
+
+    /// Returns whether the user wants to review the data read before output is written.
+    pub fn get_review_data(&self) -> bool {
+        self.ux_review_data_check.is_checked()
+    }//end get_review_data()
+
+    /// Returns whether the user wants to pick curves to exclude before output is written.
+    pub fn get_pick_curves(&self) -> bool {
+        self.ux_pick_curves_check.is_checked()
+    }//end get_pick_curves()
+
+    /// Shows a window listing every curve of each Data, so the user can uncheck
+    /// any curves which should be excluded, such as a bubble that burst early.  
+    /// Curves which are already excluded start out unchecked.  
+    /// Returns false if the user cancelled, in which case data_files isn't changed.
+    pub fn review_curve_exclusions(&mut self, data_files: &mut Vec<Data>) -> bool {
+        let mut review_window = Window::default()
+            .with_size(CURVE_REVIEW_WINDOW_WIDTH, CURVE_REVIEW_WINDOW_HEIGHT)
+            .with_label("Choose Curves to Keep");
+        review_window.make_modal(true);
+        review_window.make_resizable(true);
+        let mut curve_browser = CheckBrowser::default()
+            .with_pos(IO_BOX_PADDING, IO_BOX_PADDING)
+            .with_size(review_window.w() - (2 * IO_BOX_PADDING), review_window.h() - (3 * IO_BOX_PADDING) - IO_BTN_HEIGHT);
+        curve_browser.set_tooltip("Uncheck any curve you don't want used in calcAVG, Diff, and the other calculations.");
+        // remember which data and curve each line of the browser belongs to
+        let mut line_curves: Vec<(usize,usize)> = Vec::new();
+        for (data_idx, data) in data_files.iter().enumerate() {
+            for (curve_idx, curve) in data.curves.iter().enumerate() {
+                let values: Vec<String> = curve.row_data.iter().map(|row| format!("{} {}", row.header, row.value)).collect();
+                curve_browser.add(&format!("{}  {}:  {}", data.test_name, curve.label, values.join(", ")), !curve.excluded);
+                line_curves.push((data_idx, curve_idx));
+            }//end adding a line for each curve
+        }//end looping over each data file
+        review_window.resizable(&curve_browser);
+
+        let review_btns = Flex::default()
+            .with_pos(curve_browser.x(), curve_browser.y() + curve_browser.h() + IO_BOX_PADDING)
+            .with_size(curve_browser.w(), IO_BTN_HEIGHT)
+            .with_type(FlexType::Row);
+        let mut keep_btn = Button::default().with_label("Keep Checked Curves");
+        keep_btn.set_frame(IO_BTN_FRAME);
+        keep_btn.set_down_frame(IO_BTN_DOWN_FRAME);
+        keep_btn.set_color(IO_BTN_COLOR);
+        keep_btn.set_selection_color(IO_BTN_DOWN_COLOR);
+        let mut cancel_btn = Button::default().with_label("Cancel Processing");
+        cancel_btn.set_frame(IO_BTN_FRAME);
+        cancel_btn.set_down_frame(IO_BTN_DOWN_FRAME);
+        cancel_btn.set_color(IO_BTN_COLOR);
+        cancel_btn.set_selection_color(IO_BTN_DOWN_COLOR);
+        review_btns.end();
+        review_window.end();
+        review_window.show();
+
+        let keep_pressed = Rc::from(RefCell::from(None));
+        keep_btn.set_callback({
+            let keep_pressed_ref = (&keep_pressed).clone();
+            move |_| {*keep_pressed_ref.borrow_mut() = Some(true);}
+        });
+        cancel_btn.set_callback({
+            let keep_pressed_ref = (&keep_pressed).clone();
+            move |_| {*keep_pressed_ref.borrow_mut() = Some(false);}
+        });
+
+        // wait for a button to be pressed, treating closing the window as cancelling
+        while review_window.shown() && self.app.wait() {
+            if keep_pressed.borrow().is_some() {break;}
+        }//end waiting for user to finish choosing curves
+        let keep = keep_pressed.borrow().unwrap_or(false);
+        if keep {
+            for (line_idx, (data_idx, curve_idx)) in line_curves.iter().enumerate() {
+                data_files[*data_idx].curves[*curve_idx].excluded = !curve_browser.checked(line_idx as i32 + 1);
+            }//end updating each curve from its line
+        }//end if user wants to keep their choices
+        review_window.hide();
+        return keep;
+    }//end review_curve_exclusions()
+
+    /// Shows a progress bar and the status of each file while batch reads and parses input files,
+    /// along with a button to cancel.  
+    /// Returns once every worker has stopped. Returns false if the user cancelled,
+    /// either with the button or by closing the window.
+    pub fn show_parse_progress(&mut self, batch: &mut ParseBatch) -> bool {
+        let mut progress_window = Window::default()
+            .with_size(PARSE_PROGRESS_WINDOW_WIDTH, PARSE_PROGRESS_WINDOW_HEIGHT)
+            .with_label("Reading Input Files");
+        progress_window.make_modal(true);
+        progress_window.make_resizable(true);
+        let mut progress_bar = Progress::default()
+            .with_pos(IO_BOX_PADDING, IO_BOX_PADDING)
+            .with_size(progress_window.w() - (2 * IO_BOX_PADDING), PARSE_PROGRESS_BAR_HEIGHT);
+        progress_bar.set_minimum(0.0);
+        progress_bar.set_maximum(batch.len() as f64);
+        progress_bar.set_value(0.0);
+        progress_bar.set_selection_color(IO_BTN_DOWN_COLOR);
+        let mut file_browser = HoldBrowser::default()
+            .with_pos(IO_BOX_PADDING, progress_bar.y() + progress_bar.h() + IO_BOX_PADDING)
+            .with_size(progress_bar.w(), progress_window.h() - progress_bar.h() - (4 * IO_BOX_PADDING) - IO_BTN_HEIGHT);
+        file_browser.set_column_widths(&PARSE_PROGRESS_COLUMN_WIDTHS);
+        file_browser.set_column_char('\t');
+        for path in batch.get_paths().iter() {file_browser.add(&format!("@.{}\t@.Waiting", input::get_filename(path)));}
+        progress_window.resizable(&file_browser);
+        let mut cancel_btn = Button::default()
+            .with_pos(file_browser.x(), file_browser.y() + file_browser.h() + IO_BOX_PADDING)
+            .with_size(file_browser.w(), IO_BTN_HEIGHT)
+            .with_label("Cancel");
+        cancel_btn.set_frame(IO_BTN_FRAME);
+        cancel_btn.set_down_frame(IO_BTN_DOWN_FRAME);
+        cancel_btn.set_color(IO_BTN_COLOR);
+        cancel_btn.set_selection_color(IO_BTN_DOWN_COLOR);
+        cancel_btn.set_callback({
+            let cancel_ref = batch.get_cancel_flag();
+            move |btn| {
+                cancel_ref.store(true, std::sync::atomic::Ordering::SeqCst);
+                btn.set_label("Cancelling...");
+                btn.deactivate();
+            }
+        });
+        progress_window.end();
+        progress_window.show();
+
+        // keep the window responsive while workers are busy
+        loop {
+            if !progress_window.shown() && !batch.is_cancelled() {batch.cancel();}
+            let workers_done = batch.is_done();
+            for event in batch.poll() {
+                match event {
+                    BatchProgress::Started(index) => file_browser.set_text(index as i32 + 1, &format!("@.{}\t@.Reading...", input::get_filename(&batch.get_paths()[index]))),
+                    BatchProgress::Finished(index, status) => file_browser.set_text(index as i32 + 1, &format!("@.{}\t@.{}", input::get_filename(&batch.get_paths()[index]), status)),
+                }//end matching what happened
+            }//end updating the status of each file
+            progress_bar.set_value(batch.finished_count() as f64);
+            progress_bar.set_label(&format!("{} of {} file(s) read", batch.finished_count(), batch.len()));
+            if workers_done {break;}
+            if app::wait_for(PARSE_PROGRESS_POLL_SECS).is_err() {batch.cancel();}
+        }//end waiting for every worker to stop
+        progress_window.hide();
+        !batch.is_cancelled()
+    }//end show_parse_progress()
+
+    /// Shows a table of every file in data_files, letting the user leave out files,
+    /// edit test names, exclude curves, and choose spec profiles from specs before output is written.  
+    /// Files left out are removed from data_files, and their issues in file_issues are marked as not included.  
+    /// Spec names should already be assigned, as a file whose spec is picked as none is left without one.  
+    /// Returns false if the user cancelled processing, in which case nothing is changed.
+    pub fn review_data_files(&mut self, data_files: &mut Vec<Data>, file_issues: &mut Vec<FileIssues>, tolerances: &[DiffTolerance], specs: &SpecStore) -> bool {
+        let mut review_window = Window::default()
+            .with_size(DATA_REVIEW_WINDOW_WIDTH, DATA_REVIEW_WINDOW_HEIGHT)
+            .with_label("Review Data Before Writing");
+        review_window.make_modal(true);
+        review_window.make_resizable(true);
+        let mut data_browser = HoldBrowser::default()
+            .with_pos(IO_BOX_PADDING, IO_BOX_PADDING)
+            .with_size(review_window.w() - (2 * IO_BOX_PADDING), review_window.h() - (4 * IO_BOX_PADDING) - IO_BTN_HEIGHT - DATA_REVIEW_DETAILS_HEIGHT);
+        data_browser.set_column_widths(&DATA_REVIEW_COLUMN_WIDTHS);
+        data_browser.set_column_char('\t');
+        data_browser.set_tooltip("Select a file to see its details below, then use the buttons to change it.\nDiffs outside their tolerance are marked with (!).");
+        review_window.resizable(&data_browser);
+
+        let mut details_box = TextDisplay::default()
+            .with_pos(data_browser.x(), data_browser.y() + data_browser.h() + IO_BOX_PADDING)
+            .with_size(data_browser.w(), DATA_REVIEW_DETAILS_HEIGHT);
+        details_box.set_frame(DIALOG_BOX_FRAME);
+        details_box.wrap_mode(DIALOG_BOX_WRAP_MODE, DIALOG_BOX_WRAP_MARGIN);
+        details_box.set_scrollbar_size(DIALOG_BOX_SCROLL_SIZE);
+        details_box.set_buffer(TextBuffer::default());
+
+        let review_btns = Flex::default()
+            .with_pos(details_box.x(), details_box.y() + details_box.h() + IO_BOX_PADDING)
+            .with_size(details_box.w(), IO_BTN_HEIGHT)
+            .with_type(FlexType::Row);
+        let action = Rc::from(RefCell::from(None));
+        let add_review_btn = |label: &str, btn_action: DataReviewAction| {
+            let mut btn = Button::default().with_label(label);
+            btn.set_frame(IO_BTN_FRAME);
+            btn.set_down_frame(IO_BTN_DOWN_FRAME);
+            btn.set_color(IO_BTN_COLOR);
+            btn.set_selection_color(IO_BTN_DOWN_COLOR);
+            btn.set_callback({
+                let action_ref = action.clone();
+                move |_| {*action_ref.borrow_mut() = Some(btn_action);}
+            });
+        };
+        add_review_btn("Include/Leave Out File", DataReviewAction::ToggleFile);
+        add_review_btn("Edit Test Name", DataReviewAction::EditTestName);
+        add_review_btn("Pick Curves", DataReviewAction::PickCurves);
+        add_review_btn("Pick Spec", DataReviewAction::PickSpec);
+        add_review_btn("Write Output", DataReviewAction::Write);
+        add_review_btn("Cancel Processing", DataReviewAction::Cancel);
+        review_btns.end();
+        review_window.end();
+        review_window.show();
+
+        data_browser.set_callback({
+            let action_ref = action.clone();
+            move |_| {*action_ref.borrow_mut() = Some(DataReviewAction::Select);}
+        });
+
+        // work on copies, so nothing changes if the user cancels
+        let mut review_data = data_files.clone();
+        let mut included = vec![true; review_data.len()];
+        // specs picked by hand stay put when the test name is edited
+        let mut spec_picked = vec![false; review_data.len()];
+        GUI::fill_data_review_browser(&mut data_browser, &process::get_review_records(&review_data, file_issues, tolerances), &included);
+        let mut write = false;
+        // wait for the user to finish, treating closing the window as cancelling
+        while review_window.shown() && self.app.wait() {
+            let next_action = action.borrow_mut().take();
+            let next_action = match next_action {
+                Some(next_action) => next_action,
+                None => continue,
+            };
+            if next_action == DataReviewAction::Write {write = true; break;}
+            if next_action == DataReviewAction::Cancel {break;}
+            // the first line of the browser is the column headers
+            let data_idx = match data_browser.value() {
+                line if line > 1 && line as usize - 2 < review_data.len() => line as usize - 2,
+                _ => {
+                    if next_action != DataReviewAction::Select {dialog::message_default("Please select a file in the table first.");}
+                    continue;
+                },
+            };
+            match next_action {
+                DataReviewAction::ToggleFile => included[data_idx] = !included[data_idx],
+                DataReviewAction::EditTestName => {
+                    if let Some(test_name) = dialog::input_default("Test name for this file:", &review_data[data_idx].test_name) {
+                        review_data[data_idx].test_name = test_name.trim().to_string();
+                        if !spec_picked[data_idx] {
+                            review_data[data_idx].spec_name = specs.find_profile_for_test(&review_data[data_idx].test_name)
+                                .map(|profile| profile.name.clone()).unwrap_or_default();
+                        }//end if the spec should follow the new test name
+                    }//end if the user didn't cancel
+                },
+                DataReviewAction::PickCurves => {
+                    let mut picked = vec![review_data[data_idx].clone()];
+                    if self.review_curve_exclusions(&mut picked) {review_data[data_idx] = picked.remove(0);}
+                },
+                DataReviewAction::PickSpec => {
+                    if specs.profiles.is_empty() {dialog::message_default("There aren't any spec profiles to choose from."); continue;}
+                    let test_name = review_data[data_idx].test_name.clone();
+                    if let Some(spec_name) = self.pick_spec_name(&test_name, &review_data[data_idx].spec_name, specs) {
+                        review_data[data_idx].spec_name = spec_name;
+                        spec_picked[data_idx] = true;
+                    }//end if the user didn't cancel
+                },
+                _ => {},
+            }//end matching the action to take on the selected file
+            let records = process::get_review_records(&review_data, file_issues, tolerances);
+            GUI::fill_data_review_browser(&mut data_browser, &records, &included);
+            data_browser.select(data_idx as i32 + 2);
+            let mut details_buf = TextBuffer::default();
+            details_buf.set_text(&GUI::format_data_review_details(&records[data_idx], included[data_idx]));
+            details_box.set_buffer(details_buf);
+        }//end waiting for the user to finish reviewing
+        review_window.hide();
+        if write {
+            process::apply_review_inclusions(&mut review_data, file_issues, &included);
+            *data_files = review_data;
+        }//end if user wants to write output with their changes
+        return write;
+    }//end review_data_files()
+
+    /// Shows a window for choosing the spec profile test_name is graded against, out of
+    /// each profile in specs, or no spec at all. The choice starts on current.  
+    /// Returns the name of the profile chosen, which is empty for no spec, or None if the user cancelled.
+    fn pick_spec_name(&mut self, test_name: &str, current: &str, specs: &SpecStore) -> Option<String> {
+        let mut pick_window = Window::default()
+            .with_size(SPEC_PICK_WINDOW_WIDTH, SPEC_PICK_WINDOW_HEIGHT)
+            .with_label("Choose Spec");
+        pick_window.make_modal(true);
+        let mut spec_choice = Choice::default()
+            .with_pos(IO_BOX_PADDING, IO_BOX_PADDING + CONF_CHOICE_HEIGHT)
+            .with_size(pick_window.w() - (2 * IO_BOX_PADDING), CONF_CHOICE_HEIGHT)
+            .with_align(CONF_CHOICE_ALIGN)
+            .with_label(&format!("Spec profile for {}", test_name).replace('@', "@@"));
+        spec_choice.set_color(CONF_CHOICE_COLOR);
+        spec_choice.set_selection_color(CONF_CHOICE_SELECTION_COLOR);
+        spec_choice.set_text_color(CONF_CHOICE_TEXT_COLOR);
+        // the first choice is no spec, followed by each profile in order
+        let mut spec_names = vec![String::new()];
+        spec_choice.add(SPEC_PICK_NONE_LABEL, Shortcut::None, MenuFlag::Normal, |_| {});
+        for profile in specs.profiles.iter() {
+            // keep fltk from treating these as submenus or shortcuts
+            let label = profile.name.replace('\\', "\\\\").replace('/', "\\/").replace('&', "&&");
+            spec_choice.add(&label, Shortcut::None, MenuFlag::Normal, |_| {});
+            spec_names.push(profile.name.clone());
+        }//end adding each profile name
+        spec_choice.set_value(spec_names.iter().position(|name| name.eq(current)).unwrap_or(0) as i32);
+
+        let pick_btns = Flex::default()
+            .with_pos(spec_choice.x(), spec_choice.y() + spec_choice.h() + (2 * IO_BOX_PADDING))
+            .with_size(spec_choice.w(), IO_BTN_HEIGHT)
+            .with_type(FlexType::Row);
+        let picked = Rc::from(RefCell::from(None));
+        for (label, use_choice) in [("Use Spec", true), ("Cancel", false)] {
+            let mut btn = Button::default().with_label(label);
+            btn.set_frame(IO_BTN_FRAME);
+            btn.set_down_frame(IO_BTN_DOWN_FRAME);
+            btn.set_color(IO_BTN_COLOR);
+            btn.set_selection_color(IO_BTN_DOWN_COLOR);
+            btn.set_callback({
+                let picked_ref = picked.clone();
+                move |_| {*picked_ref.borrow_mut() = Some(use_choice);}
+            });
+        }//end adding each button
+        pick_btns.end();
+        pick_window.end();
+        pick_window.show();
+
+        // wait for a button to be pressed, treating closing the window as cancelling
+        while pick_window.shown() && self.app.wait() {
+            if picked.borrow().is_some() {break;}
+        }//end waiting for user to choose a spec
+        pick_window.hide();
+        let use_choice = picked.borrow().unwrap_or(false);
+        if !use_choice || spec_choice.value() < 0 {return None;}
+        spec_names.get(spec_choice.value() as usize).cloned()
+    }//end pick_spec_name()
+
+    /// Shows records in the table of review_data_files(), with a line of column headers first.
+    fn fill_data_review_browser(data_browser: &mut HoldBrowser, records: &[ReviewRecord], included: &[bool]) {
+        // "@." keeps the browser from reading anything in the text as formatting
+        let format_line = |fields: Vec<String>| fields.iter().map(|field| format!("@.{}", field)).collect::<Vec<String>>().join("\t");
+        data_browser.clear();
+        data_browser.add(&["Include", "File", "Test Name", "Curves", "Averages", "Diffs", "Warnings"].map(|header| format!("@b{}", header)).join("\t"));
+        for (record, included) in records.iter().zip(included.iter()) {
+            data_browser.add(&format_line(vec![
+                if *included {"Yes".to_string()} else {"No".to_string()},
+                record.source_file.clone(),
+                record.test_name.clone(),
+                record.curves.join(", "),
+                record.averages.join(", "),
+                record.diffs.join(", "),
+                record.warnings.len().to_string(),
+            ]));
+        }//end adding a line for each record
+    }//end fill_data_review_browser()
+
+    /// Gets the text describing record in full, for the details shown by review_data_files().
+    fn format_data_review_details(record: &ReviewRecord, included: bool) -> String {
+        let mut details = vec![
+            format!("{} from \"{}\"{}", record.test_name, record.source_file, if included {""} else {" (left out)"}),
+            format!("Curves: {}", record.curves.join(", ")),
+            format!("Averages: {}", record.averages.join(", ")),
+            format!("Diffs: {}", record.diffs.join(", ")),
+            format!("Spec: {}", if record.spec_name.is_empty() {SPEC_PICK_NONE_LABEL} else {&record.spec_name}),
+        ];
+        details.extend(record.warnings.iter().cloned());
+        details.join("\n")
+    }//end format_data_review_details()
+
+    /// Asks the user to choose a folder to watch for new input files, and then
+    /// a folder to write daily workbooks to.  
+    /// Returns None if the user didn't choose both.
+    pub fn get_watch_dirs_from_user(&mut self) -> Option<(PathBuf,PathBuf)> {
+        let mut dirs = Vec::new();
+        for title in ["Please choose a folder to watch for new input files.", "Please choose a folder for the daily workbooks."] {
+            let mut dialog = NativeFileChooser::new(FileDialogType::BrowseDir);
+            dialog.set_title(title);
+            dialog.show();
+            let dialog_error = dialog.error_message().unwrap_or_else(|| "".to_string()).replace("No error", "");
+            if dialog_error != "" {
+                println!("We encountered a dialog error while getting a watch folder:\n{}", dialog_error);
+                return None;
+            }//end if we couldn't get dialog
+            if dialog.filename().as_os_str().is_empty() {return None;}
+            dirs.push(dialog.filename());
+        }//end asking for each folder
+        Some((dirs.remove(0), dirs.remove(0)))
+    }//end get_watch_dirs_from_user()
+
+    /// Sends a WatchTick message right away and then every interval_secs,
+    /// and shows that a folder is being watched.
+    pub fn start_watch_timer(&mut self, interval_secs: f64) {
+        self.stop_watch_timer();
+        let s = self.msg_sender;
+        s.send(InterfaceMessage::WatchTick);
+        self.watch_timer = Some(app::add_timeout3(interval_secs, move |handle| {
+            s.send(InterfaceMessage::WatchTick);
+            app::repeat_timeout3(interval_secs, handle);
+        }));
+        self.ux_watch_btn.set_label("Stop Watch");
+        self.ux_watch_btn.set_color(IO_BTN_DOWN_COLOR);
+        self.ux_watch_btn.redraw();
+    }//end start_watch_timer()
+
+    /// Stops sending WatchTick messages, and shows that no folder is being watched.
+    pub fn stop_watch_timer(&mut self) {
+        if let Some(handle) = self.watch_timer.take() {app::remove_timeout3(handle);}
+        self.ux_watch_btn.set_label("Watch Folder");
+        self.ux_watch_btn.set_color(IO_BTN_COLOR);
+        self.ux_watch_btn.redraw();
+    }//end stop_watch_timer()
+
+    /// Adds lines to the end of the dialog box, without asking the user anything,
+    /// so that watch mode can report what it's doing.
+    pub fn show_watch_log(&mut self, lines: &[String]) {
+        let mut dialog_buf = self.ux_dialog_box.buffer().unwrap_or_else(|| TextBuffer::default());
+        for line in lines {dialog_buf.append(&format!("{}\n", line));}
+        let line_count = self.ux_dialog_box.count_lines(0, dialog_buf.length(), true);
+        self.ux_dialog_box.scroll(line_count, 0);
+    }//end show_watch_log()
+
+    /// Asks the user to choose a database file to save results to.  
+    /// Returns None if the user didn't choose one.
+    pub fn get_database_path_from_user(&mut self) -> Option<PathBuf> {
+        let mut dialog = NativeFileChooser::new(FileDialogType::BrowseSaveFile);
+        dialog.set_filter("*.db");
+        dialog.set_title("Please choose a database file to save results to.");
+        dialog.show();
+        let dialog_error = dialog.error_message().unwrap_or_else(|| "".to_string()).replace("No error", "");
+        if dialog_error != "" {
+            println!("We encountered a dialog error while getting the database path:\n{}", dialog_error);
+            return None;
+        }//end if we couldn't get dialog
+        let mut db_path = dialog.filename();
+        if db_path.as_os_str().is_empty() {return None;}
+        if db_path.extension().is_none() {db_path.set_extension("db");}
+        Some(db_path)
+    }//end get_database_path_from_user()
+
+    /// Asks the user to choose a config file to import from, or to export to if export is true.  
+    /// Returns None if the user didn't choose one.
+    pub fn get_config_path_from_user(&mut self, export: bool) -> Option<PathBuf> {
+        let mut dialog = match export {
+            true => NativeFileChooser::new(FileDialogType::BrowseSaveFile),
+            false => NativeFileChooser::new(FileDialogType::BrowseFile),
+        };
+        dialog.set_filter("*.json");
+        if export {
+            dialog.set_option(FileDialogOptions::SaveAsConfirm);
+            dialog.set_title("Please choose where to export the config settings to.");
+        } else {dialog.set_title("Please choose a config file to import settings from.");}
+        dialog.show();
+        let dialog_error = dialog.error_message().unwrap_or_else(|| "".to_string()).replace("No error", "");
+        if dialog_error != "" {
+            println!("We encountered a dialog error while getting the config path:\n{}", dialog_error);
+            return None;
+        }//end if we couldn't get dialog
+        let mut config_path = dialog.filename();
+        if config_path.as_os_str().is_empty() {return None;}
+        if export && config_path.extension().is_none() {config_path.set_extension("json");}
+        Some(config_path)
+    }//end get_config_path_from_user()
+
     /// Gets the text from the box showing the output path/file.
     pub fn get_output_path_text(&self) -> String {
         let output_box_ref = (&self.ux_output_box).clone();
@@ -381,6 +1150,140 @@ impl GUI {
         return output_buf.text();
     }//end get_output_path_text()
 
+    /// Gets the text showing the filename of each input path, as shown in the input box.
+    fn format_input_names(input_paths: &[PathBuf]) -> String {
+        let mut name_vec = Vec::new();
+        for path in input_paths.iter() {
+            match path.file_name() {
+                None => name_vec.push("FilenameInvalid".to_string()),
+                Some(name) => name_vec.push(name.to_string_lossy().to_string()),
+            }//end matching whether we can get the filename
+        }//end putting filename of each file in the input_box buf
+        name_vec.join(", ")
+    }//end format_input_names()
+
+    /// Replaces the input paths with input_paths, and shows them in the input box.
+    pub fn set_last_input_paths(&mut self, input_paths: Vec<PathBuf>) {
+        let mut input_box = self.ux_input_box.as_ref().borrow_mut();
+        let mut input_buf = input_box.buffer().unwrap_or_else(|| TextBuffer::default());
+        input_buf.set_text(&GUI::format_input_names(&input_paths));
+        input_box.set_buffer(input_buf);
+        *self.last_input_paths.as_ref().borrow_mut() = input_paths;
+    }//end set_last_input_paths()
+
+    /// Asks the user to choose a folder to use input files from.  
+    /// Returns None if the user didn't choose one.
+    pub fn get_input_dir_from_user(&mut self) -> Option<PathBuf> {
+        let mut dialog = NativeFileChooser::new(FileDialogType::BrowseDir);
+        dialog.set_title("Please choose a folder of input files.");
+        dialog.show();
+        let dialog_error = dialog.error_message().unwrap_or_else(|| "".to_string()).replace("No error", "");
+        if dialog_error != "" {
+            println!("We encountered a dialog error while getting the input folder:\n{}", dialog_error);
+            return None;
+        }//end if we couldn't get dialog
+        if dialog.filename().as_os_str().is_empty() {None} else {Some(dialog.filename())}
+    }//end get_input_dir_from_user()
+
+    /// Shows the files found in input_dir with include_patterns and exclude_patterns, along with
+    /// how many there are, and lets the user change the patterns before using the files.  
+    /// If the user uses the files, the patterns are updated to the ones used and the files are returned.
+    /// Returns None if the user cancelled.
+    pub fn review_input_folder(&mut self, input_dir: &Path, include_patterns: &mut Vec<String>, exclude_patterns: &mut Vec<String>) -> Option<Vec<PathBuf>> {
+        let mut review_window = Window::default()
+            .with_size(FOLDER_REVIEW_WINDOW_WIDTH, FOLDER_REVIEW_WINDOW_HEIGHT)
+            .with_label("Choose Files From Folder");
+        review_window.make_modal(true);
+        review_window.make_resizable(true);
+        let label_width = 130;
+        let mut include_input = Input::default()
+            .with_pos(IO_BOX_PADDING + label_width, IO_BOX_PADDING)
+            .with_size(review_window.w() - label_width - (2 * IO_BOX_PADDING), FOLDER_REVIEW_INPUT_HEIGHT)
+            .with_label("Include Patterns");
+        include_input.set_value(&include_patterns.join("; "));
+        include_input.set_tooltip("Files matching any of these patterns are used, separated by \";\". See Help for details.");
+        let mut exclude_input = Input::default()
+            .with_pos(include_input.x(), include_input.y() + include_input.h() + IO_BOX_PADDING)
+            .with_size(include_input.w(), FOLDER_REVIEW_INPUT_HEIGHT)
+            .with_label("Exclude Patterns");
+        exclude_input.set_value(&exclude_patterns.join("; "));
+        exclude_input.set_tooltip("Files matching any of these patterns are left out, separated by \";\".\nA pattern ending in \"/\" leaves out a whole folder.");
+        let mut count_label = Frame::default()
+            .with_pos(IO_BOX_PADDING, exclude_input.y() + exclude_input.h() + IO_BOX_PADDING)
+            .with_size(review_window.w() - (2 * IO_BOX_PADDING), FOLDER_REVIEW_INPUT_HEIGHT)
+            .with_align(Align::Inside.union(Align::Left));
+        let mut file_browser = HoldBrowser::default()
+            .with_pos(IO_BOX_PADDING, count_label.y() + count_label.h() + IO_BOX_PADDING)
+            .with_size(review_window.w() - (2 * IO_BOX_PADDING), review_window.h() - (count_label.y() + count_label.h()) - (3 * IO_BOX_PADDING) - IO_BTN_HEIGHT);
+        review_window.resizable(&file_browser);
+
+        let review_btns = Flex::default()
+            .with_pos(file_browser.x(), file_browser.y() + file_browser.h() + IO_BOX_PADDING)
+            .with_size(file_browser.w(), IO_BTN_HEIGHT)
+            .with_type(FlexType::Row);
+        let action = Rc::from(RefCell::from(None));
+        let add_review_btn = |label: &str, btn_action: FolderReviewAction| {
+            let mut btn = Button::default().with_label(label);
+            btn.set_frame(IO_BTN_FRAME);
+            btn.set_down_frame(IO_BTN_DOWN_FRAME);
+            btn.set_color(IO_BTN_COLOR);
+            btn.set_selection_color(IO_BTN_DOWN_COLOR);
+            btn.set_callback({
+                let action_ref = action.clone();
+                move |_| {*action_ref.borrow_mut() = Some(btn_action);}
+            });
+        };
+        add_review_btn("Refresh", FolderReviewAction::Refresh);
+        add_review_btn("Use These Files", FolderReviewAction::Use);
+        add_review_btn("Cancel", FolderReviewAction::Cancel);
+        review_btns.end();
+        review_window.end();
+        review_window.show();
+
+        let split_patterns = |text: String| text.split(';').map(|pattern| pattern.trim().to_string()).filter(|pattern| !pattern.is_empty()).collect::<Vec<String>>();
+        let mut scan = None;
+        let mut next_action = Some(FolderReviewAction::Refresh);
+        // wait for the user to finish, treating closing the window as cancelling
+        loop {
+            match next_action {
+                Some(FolderReviewAction::Refresh) => {
+                    file_browser.clear();
+                    match input::scan_input_folder(input_dir, &split_patterns(include_input.value()), &split_patterns(exclude_input.value())) {
+                        Ok(new_scan) => {
+                            count_label.set_label(&format!("{} file(s) will be used, {} left out by the exclude patterns, and {} didn't match.", new_scan.included.len(), new_scan.excluded.len() + new_scan.excluded_dirs.len(), new_scan.unmatched));
+                            // "@." keeps the browser from reading anything in the path as formatting
+                            for path in new_scan.included.iter() {file_browser.add(&format!("@.{}", input::get_relative_path_text(input_dir, path)));}
+                            for path in new_scan.excluded.iter() {file_browser.add(&format!("@i@.{}  (left out)", input::get_relative_path_text(input_dir, path)));}
+                            for path in new_scan.excluded_dirs.iter() {file_browser.add(&format!("@i@.{}/  (folder left out)", input::get_relative_path_text(input_dir, path)));}
+                            if !new_scan.unreadable_dirs.is_empty() {dialog::alert_default(&format!("Some folders couldn't be read, so they were skipped:\n{}", new_scan.unreadable_dirs.join("\n")));}
+                            scan = Some(new_scan);
+                        },
+                        Err(msg) => {
+                            count_label.set_label("The folder couldn't be read.");
+                            dialog::alert_default(&msg);
+                            scan = None;
+                        },
+                    }//end matching whether we could scan the folder
+                    review_window.redraw();
+                },
+                Some(FolderReviewAction::Use) => {
+                    if let Some(scan) = scan.take() {
+                        *include_patterns = split_patterns(include_input.value());
+                        *exclude_patterns = split_patterns(exclude_input.value());
+                        review_window.hide();
+                        return Some(scan.included);
+                    }//end if we have files to use
+                },
+                Some(FolderReviewAction::Cancel) => break,
+                None => {},
+            }//end matching the action the user took
+            if !review_window.shown() || !self.app.wait() {break;}
+            next_action = action.borrow_mut().take();
+        }//end waiting for the user to finish choosing files
+        review_window.hide();
+        None
+    }//end review_input_folder()
+
     /// Clears all memory or display of currently stored input paths.
     pub fn clear_last_input_paths(&mut self) {
         let last_input_paths_ref = (&self.last_input_paths).clone();
@@ -584,16 +1487,29 @@ impl GUI {
             .with_label("Select Input File(s)");
         input_btn.set_frame(IO_BTN_FRAME);
         input_btn.set_down_frame(IO_BTN_DOWN_FRAME);
-        input_btn.set_tooltip("Click this button to choose an input file.");
+        input_btn.set_tooltip("Click this button to choose an input file.\nYou can also drop files or folders onto the window to add them.");
         input_btn.clear_visible_focus();
         input_btn.set_color(IO_BTN_COLOR);
         input_btn.set_selection_color(IO_BTN_DOWN_COLOR);
         io_controls_group.add(&input_btn);
 
+        let mut input_dir_btn = Button::default()
+            .with_pos(input_btn.x() + input_btn.w() + IO_BOX_PADDING, input_btn.y())
+            .with_size(IO_DIR_BTN_WIDTH, IO_BTN_HEIGHT)
+            .with_label("Folder");
+        input_dir_btn.emit(s, InterfaceMessage::InputFolder);
+        input_dir_btn.set_frame(IO_BTN_FRAME);
+        input_dir_btn.set_down_frame(IO_BTN_DOWN_FRAME);
+        input_dir_btn.set_tooltip("Click this button to use the files in a folder and the folders inside it,\nchosen by the include and exclude patterns.");
+        input_dir_btn.clear_visible_focus();
+        input_dir_btn.set_color(IO_BTN_COLOR);
+        input_dir_btn.set_selection_color(IO_BTN_DOWN_COLOR);
+        io_controls_group.add(&input_dir_btn);
+
         let input_buf = TextBuffer::default();
         let mut input_box = TextDisplay::default()
-            .with_pos(input_btn.x() + input_btn.w() + IO_BOX_PADDING, input_btn.y())
-            .with_size(io_controls_group.w() - (input_btn.w() + (3 * IO_BOX_PADDING)), IO_BOX_HEIGHT);
+            .with_pos(input_dir_btn.x() + input_dir_btn.w() + IO_BOX_PADDING, input_btn.y())
+            .with_size(io_controls_group.w() - (input_btn.w() + input_dir_btn.w() + (4 * IO_BOX_PADDING)), IO_BOX_HEIGHT);
         input_box.set_frame(IO_BOX_FRAME);
         input_box.set_scrollbar_align(Align::Bottom);
         input_box.set_scrollbar_size(7);
@@ -638,10 +1554,83 @@ impl GUI {
         process_btn.set_tooltip("Once you've selected an input and output, click this to process your files.");
         io_controls_group.add_resizable(&process_btn);
 
+        let mut output_format_choice = Choice::default()
+            .with_pos(output_btn.x(), process_btn.y() + process_btn.h() - IO_FORMAT_CHOICE_HEIGHT)
+            .with_size(IO_FORMAT_CHOICE_WIDTH, IO_FORMAT_CHOICE_HEIGHT)
+            .with_align(CONF_CHOICE_ALIGN)
+            .with_label("Output Format");
+        output_format_choice.add_choice("xlsx|csv|tsv");
+        output_format_choice.set_color(CONF_CHOICE_COLOR);
+        output_format_choice.set_selection_color(CONF_CHOICE_SELECTION_COLOR);
+        output_format_choice.set_text_color(CONF_CHOICE_TEXT_COLOR);
+        output_format_choice.set_frame(CONF_CHOICE_MENU_FRAME);
+        output_format_choice.set_down_frame(CONF_CHOICE_SELECTION_FRAME);
+        output_format_choice.set_label_size(CONF_CHOICE_LABEL_SIZE - 2);
+        output_format_choice.clear_visible_focus();
+        output_format_choice.set_value(0);
+        output_format_choice.set_tooltip("The kind of output file to write. Choose csv or tsv for a flat file that's easy to load into other programs.");
+        io_controls_group.add(&output_format_choice);
+
+        let mut watch_btn = Button::default()
+            .with_pos(output_btn.x(), process_btn.y())
+            .with_size(IO_FORMAT_CHOICE_WIDTH, IO_WATCH_BTN_HEIGHT)
+            .with_label("Watch Folder");
+        watch_btn.emit(s, InterfaceMessage::WatchToggle);
+        watch_btn.set_frame(IO_BTN_FRAME);
+        watch_btn.set_down_frame(IO_BTN_DOWN_FRAME);
+        watch_btn.clear_visible_focus();
+        watch_btn.set_color(IO_BTN_COLOR);
+        watch_btn.set_selection_color(IO_BTN_DOWN_COLOR);
+        watch_btn.set_label_size(CONF_CHOICE_LABEL_SIZE - 2);
+        watch_btn.set_tooltip("Watches a folder for new txt files, and processes each one once it's fully written,\nadding it to a daily workbook, and to the database if \"Database\" is checked.\nClick again to stop watching.");
+        io_controls_group.add(&watch_btn);
+
+        // the checks to the right of the process button each get a fifth of its height
+        let check_height = process_btn.h() / 5;
+        let mut long_output_check = CheckButton::default()
+            .with_pos(process_btn.x() + process_btn.w() + IO_PRC_BTN_PADDING, process_btn.y() + (4 * check_height))
+            .with_size(io_controls_group.x() + io_controls_group.w() - (process_btn.x() + process_btn.w() + (2 * IO_PRC_BTN_PADDING)), process_btn.h() - (4 * check_height))
+            .with_label("Long format");
+        long_output_check.clear_visible_focus();
+        long_output_check.set_tooltip("Also writes every value on its own line, for loading into a database or pivot table.\nFor xlsx this is an extra sheet, and for csv or tsv it is a second file ending in -long.");
+        io_controls_group.add(&long_output_check);
+
+        let mut database_check = CheckButton::default()
+            .with_pos(long_output_check.x(), process_btn.y())
+            .with_size(long_output_check.w(), check_height)
+            .with_label("Database");
+        database_check.clear_visible_focus();
+        database_check.set_tooltip("Also saves results to a SQLite database, so they're kept between runs.\nTests already saved from the same file won't be saved twice.");
+        io_controls_group.add(&database_check);
+
+        let mut issues_check = CheckButton::default()
+            .with_pos(long_output_check.x(), database_check.y() + database_check.h())
+            .with_size(long_output_check.w(), check_height)
+            .with_label("Issues sheet");
+        issues_check.clear_visible_focus();
+        issues_check.set_tooltip("Also writes a sheet listing every issue found while reading the input files,\nand whether each file was included. For csv or tsv, this is a second file ending in -issues.");
+        io_controls_group.add(&issues_check);
+
+        let mut review_data_check = CheckButton::default()
+            .with_pos(long_output_check.x(), issues_check.y() + issues_check.h())
+            .with_size(long_output_check.w(), check_height)
+            .with_label("Review data");
+        review_data_check.clear_visible_focus();
+        review_data_check.set_tooltip("Before writing output, shows a table of every file read, so you can leave out files,\nfix test names, choose specs, or exclude curves, such as a bubble that burst early.");
+        io_controls_group.add(&review_data_check);
+
+        let mut pick_curves_check = CheckButton::default()
+            .with_pos(long_output_check.x(), review_data_check.y() + review_data_check.h())
+            .with_size(long_output_check.w(), check_height)
+            .with_label("Pick curves");
+        pick_curves_check.clear_visible_focus();
+        pick_curves_check.set_tooltip("Before writing output, shows every curve so you can uncheck any you want excluded,\nsuch as a bubble that burst early. Excluded curves aren't used in calcAVG or Diff.");
+        io_controls_group.add(&pick_curves_check);
+
         // set up group with configuration options
         let mut config_group = Group::default()
             .with_pos(io_controls_group.x() + io_controls_group.w(), 0)
-            .with_size(tile_group.width() - io_controls_group.width(), tile_group.height());
+            .with_size(tile_group.width() - io_controls_group.width(), tile_group.height() - PREVIEW_GROUP_HEIGHT);
         config_group.end();
         config_group.set_frame(GROUP_FRAME);
         config_group.set_color(CONFIG_GROUP_COLOR);
@@ -673,6 +1662,7 @@ impl GUI {
         read_start_mode_choice.clear_visible_focus();
         read_start_mode_choice.set_value(0);
         read_start_mode_choice.set_tooltip("The method by which the program finds the header above the rows of data in the file. See Help for details.");
+        read_start_mode_choice.emit(s, InterfaceMessage::ConfigEdited);
         config_group.add(&read_start_mode_choice);
 
         let mut read_row_mode_choice = Choice::default()
@@ -692,6 +1682,7 @@ impl GUI {
         read_row_mode_choice.clear_visible_focus();
         read_row_mode_choice.set_value(0);
         read_row_mode_choice.set_tooltip("The method by which the program finds rows of data under the start header in the file. See Help for details.");
+        read_row_mode_choice.emit(s, InterfaceMessage::ConfigEdited);
         config_group.add(&read_row_mode_choice);
 
         let mut read_start_idx_input = IntInput::default()
@@ -701,6 +1692,8 @@ impl GUI {
             .with_label("Read Start Idx");
         read_start_idx_input.set_frame(CONF_INPUT_FRAME);
         read_start_idx_input.set_tooltip("If using Read Start Mode of Index, sets the 0-based index where the start header is located. See Help for details.");
+        read_start_idx_input.set_trigger(CallbackTrigger::Changed);
+        read_start_idx_input.emit(s, InterfaceMessage::ConfigEdited);
         config_group.add(&read_start_idx_input);
 
         let mut read_rows_max_input = IntInput::default()
@@ -710,6 +1703,8 @@ impl GUI {
             .with_label("Read Rows Max");
         read_rows_max_input.set_frame(CONF_INPUT_FRAME);
         read_rows_max_input.set_tooltip("If using Read Rows Mode of Max, sets the number of rows after the start index to read. See Help for details.");
+        read_rows_max_input.set_trigger(CallbackTrigger::Changed);
+        read_rows_max_input.emit(s, InterfaceMessage::ConfigEdited);
         config_group.add(&read_rows_max_input);
 
         let read_start_header_buf = TextBuffer::default();
@@ -724,6 +1719,8 @@ impl GUI {
         read_start_header_box.set_buffer(read_start_header_buf);
         read_start_header_box.set_cursor_style(fltk::text::Cursor::Simple);
         read_start_header_box.set_tooltip("If using Read Start Mode of Header, sets the exact value of header to look for as the start header. See Help for details.");
+        read_start_header_box.set_trigger(CallbackTrigger::Changed);
+        read_start_header_box.emit(s, InterfaceMessage::ConfigEdited);
         config_group.add(&read_start_header_box);
 
         let mut cf_multiline_flex = Flex::default()
@@ -744,6 +1741,8 @@ impl GUI {
         read_row_headers_box.set_scrollbar_size(CONF_INPUT_SCROLLBAR_SIZE);
         read_row_headers_box.set_cursor_style(fltk::text::Cursor::Simple);
         read_row_headers_box.set_tooltip("If using Read Rows Mode of Header, sets the exact headers (and ordering of those headers) to look for after the start header. Very sensitive, see Help for details.");
+        read_row_headers_box.set_trigger(CallbackTrigger::Changed);
+        read_row_headers_box.emit(s, InterfaceMessage::ConfigEdited);
         cf_multiline_flex.add(&read_row_headers_box);
 
         let row_order_pref_buf = TextBuffer::default();
@@ -758,13 +1757,55 @@ impl GUI {
         row_order_pref_box.set_scrollbar_size(CONF_INPUT_SCROLLBAR_SIZE);
         row_order_pref_box.set_cursor_style(fltk::text::Cursor::Simple);
         row_order_pref_box.set_tooltip("Sets a custom output order for various data headers. This setting will never add or remove data; see Help for details.");
+        row_order_pref_box.set_trigger(CallbackTrigger::Changed);
+        row_order_pref_box.emit(s, InterfaceMessage::ConfigEdited);
         cf_multiline_flex.add(&row_order_pref_box);
 
+        let mut decimal_separator_choice = Choice::default()
+            .with_pos(read_start_mode_choice.x(), cf_multiline_flex.y() + cf_multiline_flex.h() + CONF_CHOICE_VER_PADDING)
+            .with_size(read_start_mode_choice.w(), CONF_CHOICE_HEIGHT)
+            .with_align(CONF_CHOICE_ALIGN)
+            .with_label("Decimal Separator");
+        decimal_separator_choice.add_choice("Point|Comma|Auto");
+        decimal_separator_choice.set_color(CONF_CHOICE_COLOR);
+        decimal_separator_choice.set_selection_color(CONF_CHOICE_SELECTION_COLOR);
+        decimal_separator_choice.set_text_color(CONF_CHOICE_TEXT_COLOR);
+        decimal_separator_choice.set_frame(CONF_CHOICE_MENU_FRAME);
+        decimal_separator_choice.set_down_frame(CONF_CHOICE_SELECTION_FRAME);
+        decimal_separator_choice.set_label_size(CONF_CHOICE_LABEL_SIZE);
+        decimal_separator_choice.set_label_color(CONF_CHOICE_LABEL_COLOR);
+        decimal_separator_choice.set_text_size(CONF_CHOICE_TEXT_SIZE);
+        decimal_separator_choice.clear_visible_focus();
+        decimal_separator_choice.set_value(0);
+        decimal_separator_choice.set_tooltip("The character between the whole and fractional parts of values, such as the comma in 85,3.\nAuto decides it for each file from its values. See Help for details.");
+        decimal_separator_choice.emit(s, InterfaceMessage::ConfigEdited);
+        config_group.add(&decimal_separator_choice);
+
+        let mut thousands_separator_choice = Choice::default()
+            .with_pos(read_row_mode_choice.x(), decimal_separator_choice.y())
+            .with_size(read_row_mode_choice.w(), CONF_CHOICE_HEIGHT)
+            .with_align(CONF_CHOICE_ALIGN)
+            .with_label("Thousands Separator");
+        thousands_separator_choice.add_choice("None|Point|Comma|Space|Apostrophe|Auto");
+        thousands_separator_choice.set_color(CONF_CHOICE_COLOR);
+        thousands_separator_choice.set_selection_color(CONF_CHOICE_SELECTION_COLOR);
+        thousands_separator_choice.set_text_color(CONF_CHOICE_TEXT_COLOR);
+        thousands_separator_choice.set_frame(CONF_CHOICE_MENU_FRAME);
+        thousands_separator_choice.set_down_frame(CONF_CHOICE_SELECTION_FRAME);
+        thousands_separator_choice.set_label_size(CONF_CHOICE_LABEL_SIZE);
+        thousands_separator_choice.set_label_color(CONF_CHOICE_LABEL_COLOR);
+        thousands_separator_choice.set_text_size(CONF_CHOICE_TEXT_SIZE);
+        thousands_separator_choice.clear_visible_focus();
+        thousands_separator_choice.set_value(0);
+        thousands_separator_choice.set_tooltip("The character grouping thousands in values, such as the point in 1.234,5.\nAuto uses whichever of a point or comma isn't the decimal separator. See Help for details.");
+        thousands_separator_choice.emit(s, InterfaceMessage::ConfigEdited);
+        config_group.add(&thousands_separator_choice);
+
         // add box for split character
         // add box for test name prefix
         let split_char_buf = TextBuffer::default();
         let mut split_char_box = TextEditor::default()
-            .with_pos(row_order_pref_box.x(), row_order_pref_box.y() + row_order_pref_box.h() + CONF_CHOICE_HOR_PADDING)
+            .with_pos(row_order_pref_box.x(), decimal_separator_choice.y() + decimal_separator_choice.h() + CONF_CHOICE_HOR_PADDING)
             .with_size(row_order_pref_box.w(), read_start_header_box.h())
             .with_align(Align::LeftTop)
             .with_label("Row Split Character");
@@ -774,6 +1815,8 @@ impl GUI {
         split_char_box.set_frame(CONF_INPUT_FRAME);
         split_char_box.set_cursor_style(fltk::text::Cursor::Simple);
         split_char_box.set_tooltip("Sets the split character between header and value in data rows. See Help for details.");
+        split_char_box.set_trigger(CallbackTrigger::Changed);
+        split_char_box.emit(s, InterfaceMessage::ConfigEdited);
         config_group.add(&split_char_box);
 
         let test_name_prefix_buf = TextBuffer::default();
@@ -788,8 +1831,46 @@ impl GUI {
         test_name_prefix_box.set_scrollbar_align(Align::Bottom);
         test_name_prefix_box.set_scrollbar_size(CONF_INPUT_SCROLLBAR_SIZE);
         test_name_prefix_box.set_tooltip("Sets the prefix to the test name to look for in each file. See Help for details.");
+        test_name_prefix_box.set_trigger(CallbackTrigger::Changed);
+        test_name_prefix_box.emit(s, InterfaceMessage::ConfigEdited);
         config_group.add(&test_name_prefix_box);
 
+        let mut cf_profile_flex = Flex::default()
+            .with_pos(cf_multiline_flex.x(), config_group.y() + config_group.h() - CONF_BUTTON_HEIGHT - CONF_CHOICE_HEIGHT - CONF_CHOICE_HOR_PADDING)
+            .with_size(cf_multiline_flex.w(), CONF_CHOICE_HEIGHT)
+            .with_type(FlexType::Row);
+        cf_profile_flex.set_margins(0,0,0,0);
+        config_group.add(&cf_profile_flex);
+
+        let cf_profile_label = Frame::default()
+            .with_align(Align::Inside.union(Align::Left))
+            .with_label("Profile");
+        cf_profile_flex.fixed(&cf_profile_label, CONF_PROFILE_LABEL_WIDTH);
+
+        let mut profile_choice = Choice::default();
+        profile_choice.set_color(CONF_CHOICE_COLOR);
+        profile_choice.set_selection_color(CONF_CHOICE_SELECTION_COLOR);
+        profile_choice.set_text_color(CONF_CHOICE_TEXT_COLOR);
+        profile_choice.set_frame(CONF_CHOICE_MENU_FRAME);
+        profile_choice.set_down_frame(CONF_CHOICE_SELECTION_FRAME);
+        profile_choice.set_text_size(CONF_CHOICE_TEXT_SIZE);
+        profile_choice.clear_visible_focus();
+        profile_choice.set_tooltip("The config profile in use. Each profile keeps its own settings, and the last one used is remembered.");
+        cf_profile_flex.add(&profile_choice);
+
+        let mut profile_menu = MenuButton::default()
+            .with_label("Edit Profile");
+        profile_menu.set_frame(CONF_BTN_FRAME);
+        profile_menu.set_down_frame(CONF_BTN_DOWN_FRAME);
+        profile_menu.clear_visible_focus();
+        profile_menu.add_emit("New", Shortcut::None, MenuFlag::Normal, s, InterfaceMessage::ProfileNew);
+        profile_menu.add_emit("Duplicate", Shortcut::None, MenuFlag::Normal, s, InterfaceMessage::ProfileDuplicate);
+        profile_menu.add_emit("Rename", Shortcut::None, MenuFlag::Normal, s, InterfaceMessage::ProfileRename);
+        profile_menu.add_emit("Delete", Shortcut::None, MenuFlag::Normal, s, InterfaceMessage::ProfileDelete);
+        profile_menu.set_tooltip("Create, duplicate, rename, or delete config profiles.");
+        cf_profile_flex.fixed(&profile_menu, CONF_PROFILE_MENU_WIDTH);
+        cf_profile_flex.end();
+
         let mut cf_button_flex = Flex::default()
             .with_pos(cf_multiline_flex.x(), config_group.y() + config_group.h() - CONF_BUTTON_HEIGHT)
             .with_size(cf_multiline_flex.w(),CONF_BUTTON_HEIGHT)
@@ -804,7 +1885,34 @@ impl GUI {
         cf_reset_btn.clear_visible_focus();
         cf_reset_btn.emit(s, InterfaceMessage::ConfigReset);
         cf_reset_btn.set_tooltip("Resets all configuration settings to the default values.");
-        cf_button_flex.add(&cf_reset_btn);
+        cf_button_flex.fixed(&cf_reset_btn, CONF_RESET_BTN_WIDTH);
+
+        let mut cf_import_btn = Button::default()
+            .with_label("Import");
+        cf_import_btn.set_frame(CONF_BTN_FRAME);
+        cf_import_btn.set_down_frame(CONF_BTN_DOWN_FRAME);
+        cf_import_btn.clear_visible_focus();
+        cf_import_btn.emit(s, InterfaceMessage::ConfigImport);
+        cf_import_btn.set_tooltip("Replaces the settings of the current profile with settings from a file, such as one exported on another PC.\nYou'll be shown what will change first.");
+        cf_button_flex.add(&cf_import_btn);
+
+        let mut cf_export_btn = Button::default()
+            .with_label("Export");
+        cf_export_btn.set_frame(CONF_BTN_FRAME);
+        cf_export_btn.set_down_frame(CONF_BTN_DOWN_FRAME);
+        cf_export_btn.clear_visible_focus();
+        cf_export_btn.emit(s, InterfaceMessage::ConfigExport);
+        cf_export_btn.set_tooltip("Saves the settings of the current profile to a file, so they can be imported on another PC.");
+        cf_button_flex.add(&cf_export_btn);
+
+        let mut cf_file_btn = Button::default()
+            .with_label("Config File");
+        cf_file_btn.set_frame(CONF_BTN_FRAME);
+        cf_file_btn.set_down_frame(CONF_BTN_DOWN_FRAME);
+        cf_file_btn.clear_visible_focus();
+        cf_file_btn.emit(s, InterfaceMessage::ConfigFileInfo);
+        cf_file_btn.set_tooltip("Shows which config file your settings are saved in.");
+        cf_button_flex.fixed(&cf_file_btn, CONF_FILE_BTN_WIDTH);
 
         let mut cf_help_btn = Button::default()
             .with_label("Help");
@@ -835,7 +1943,7 @@ impl GUI {
         // set up group for integrated dialog
         let mut dialog_group = Group::default()
             .with_pos(io_controls_group.x(), io_controls_group.y() + io_controls_group.h())
-            .with_size(io_controls_group.w(), tile_group.h() - (io_controls_group.y() + io_controls_group.h()));
+            .with_size(io_controls_group.w(), tile_group.h() - PREVIEW_GROUP_HEIGHT - (io_controls_group.y() + io_controls_group.h()));
         dialog_group.end();
         dialog_group.set_frame(GROUP_FRAME);
         dialog_group.set_color(DIALOG_GROUP_COLOR);
@@ -868,6 +1976,46 @@ impl GUI {
         dialog_btns.set_frame(DIALOG_BTNS_BACK_FRAME);
         dialog_group.add(&dialog_btns);
 
+        // set up group for previewing how the first input file will be read
+        let mut preview_group = Group::default()
+            .with_pos(0, tile_group.h() - PREVIEW_GROUP_HEIGHT)
+            .with_size(tile_group.w(), PREVIEW_GROUP_HEIGHT);
+        preview_group.end();
+        preview_group.set_frame(GROUP_FRAME);
+        preview_group.set_color(PREVIEW_GROUP_COLOR);
+        tile_group.add(&preview_group);
+
+        let mut preview_label = Frame::default()
+            .with_pos(preview_group.x() + PREVIEW_BOX_PADDING, preview_group.y() + PREVIEW_BOX_PADDING)
+            .with_size(preview_group.w() - (2 * PREVIEW_BOX_PADDING), PREVIEW_LABEL_HEIGHT)
+            .with_align(Align::Inside.union(Align::Left))
+            .with_label("Parse Preview");
+        preview_label.set_label_size(16);
+        preview_label.set_tooltip("Shows how the first input file will be read with the current config settings.\nThe test name is yellow, headers are blue, average rows are green, and curve rows are purple.");
+        preview_group.add(&preview_label);
+
+        let mut preview_lines_box = TextDisplay::default()
+            .with_pos(preview_label.x(), preview_label.y() + preview_label.h() + PREVIEW_BOX_PADDING)
+            .with_size(preview_group.w() - PREVIEW_VALUES_WIDTH - (3 * PREVIEW_BOX_PADDING), preview_group.h() - PREVIEW_LABEL_HEIGHT - (3 * PREVIEW_BOX_PADDING));
+        preview_lines_box.set_frame(DIALOG_BOX_FRAME);
+        preview_lines_box.set_text_font(Font::Courier);
+        preview_lines_box.set_text_size(PREVIEW_TEXT_SIZE);
+        preview_lines_box.set_linenumber_width(PREVIEW_LINENUMBER_WIDTH);
+        preview_lines_box.set_linenumber_size(PREVIEW_TEXT_SIZE);
+        preview_lines_box.set_scrollbar_size(DIALOG_BOX_SCROLL_SIZE);
+        preview_lines_box.set_buffer(TextBuffer::default());
+        preview_group.add_resizable(&preview_lines_box);
+
+        let mut preview_values_box = TextDisplay::default()
+            .with_pos(preview_lines_box.x() + preview_lines_box.w() + PREVIEW_BOX_PADDING, preview_lines_box.y())
+            .with_size(PREVIEW_VALUES_WIDTH, preview_lines_box.h());
+        preview_values_box.set_frame(DIALOG_BOX_FRAME);
+        preview_values_box.set_text_size(PREVIEW_TEXT_SIZE);
+        preview_values_box.set_scrollbar_size(DIALOG_BOX_SCROLL_SIZE);
+        preview_values_box.set_buffer(TextBuffer::default());
+        preview_values_box.set_tooltip("The values read from the previewed file, followed by any issues found.");
+        preview_group.add(&preview_values_box);
+
         // set up callbacks and reference stuff
         let input_box_ref = Rc::from(RefCell::from(input_box));
         let last_input_path_ref = Rc::from(RefCell::from(Vec::new()));
@@ -891,17 +2039,36 @@ impl GUI {
                 let dialog_error = dialog.error_message().unwrap_or_else(|| "".to_string()).replace("No error","");
                 if dialog_error != "" {println!("We encountered a dialog error while getting input file:\n{}", dialog_error)}
                 *last_input_path = dialog.filenames();
-                let mut name_vec = Vec::new();
-                for path in last_input_path.iter() {
-                    match path.file_name() {
-                        None => name_vec.push("FilenameInvalid".to_string()),
-                        Some(name) => name_vec.push(name.to_string_lossy().to_string()),
-                    }//end matching whether we can get the filename
-                }//end putting filename of each file in the input_box buf
-                input_buf.set_text(&name_vec.join(", "));
+                input_buf.set_text(&GUI::format_input_names(&last_input_path));
                 drop(dialog);
                 // make sure we still have our buffer
                 input_box.set_buffer(input_buf);
+                s.send(InterfaceMessage::InputsChanged);
+            }//end closure
+        });
+
+        main_window.handle({
+            let input_box_ref = (&input_box_ref).clone();
+            let last_input_path_ref = (&last_input_path_ref).clone();
+            move |_, ev| {
+                match ev {
+                    // accept anything dragged over the window, so that it can be dropped
+                    Event::DndEnter | Event::DndDrag | Event::DndLeave | Event::DndRelease => true,
+                    Event::Paste => {
+                        let dropped_paths = input::parse_dropped_paths(&app::event_text());
+                        let mut last_input_path = last_input_path_ref.as_ref().borrow_mut();
+                        let (added, errs) = input::add_dropped_input_paths(&mut last_input_path, &dropped_paths);
+                        if !errs.is_empty() {dialog::alert_default(&errs.join("\n"));}
+                        else if added == 0 {dialog::message_default(&format!("No new .{} files were found in what was dropped.", input::INPUT_EXTENSION));}
+                        let mut input_box = input_box_ref.as_ref().borrow_mut();
+                        let mut input_buf = input_box.buffer().unwrap_or_else(|| TextBuffer::default());
+                        input_buf.set_text(&GUI::format_input_names(&last_input_path));
+                        input_box.set_buffer(input_buf);
+                        if added > 0 {s.send(InterfaceMessage::InputsChanged);}
+                        true
+                    },
+                    _ => false,
+                }//end matching event
             }//end closure
         });
 
@@ -916,7 +2083,7 @@ impl GUI {
                 // create a dialog to show
                 let mut dialog = NativeFileChooser::new(FileDialogType::BrowseSaveFile);
                 dialog.set_option(FileDialogOptions::SaveAsConfirm);
-                dialog.set_filter("*.xlsx");
+                dialog.set_filter("*.{xlsx,csv,tsv}");
                 dialog.set_title("Please select a path for the output file.");
                 dialog.show();
                 let dialog_error = dialog.error_message().unwrap_or_else(|| "".to_string()).replace("No error", "");
@@ -1004,11 +2171,20 @@ impl GUI {
             last_input_paths: last_input_path_ref,
             ux_output_box: output_box_ref,
             last_output_path: last_output_path_ref,
+            ux_output_format_choice: output_format_choice,
+            ux_long_output_check: long_output_check,
+            ux_database_check: database_check,
+            ux_issues_check: issues_check,
+            ux_review_data_check: review_data_check,
+            ux_pick_curves_check: pick_curves_check,
+            ux_watch_btn: watch_btn,
+            watch_timer: None,
             ux_config_group: config_group,
             ux_io_controls_group: io_controls_group,
             ux_dialog_group: dialog_group,
             ux_dialog_box: dialog_box,
             ux_dialog_btns_flx: dialog_btns,
+            ux_cf_label: config_group_label,
             ux_cf_read_start_mode_choice: read_start_mode_choice,
             ux_cf_read_row_mode_choice: read_row_mode_choice,
             ux_cf_read_start_idx_input: read_start_idx_input_ref,
@@ -1018,6 +2194,14 @@ impl GUI {
             ux_cf_row_order_pref_box: row_order_pref_box,
             ux_cf_split_char_box: split_char_box,
             ux_cf_test_name_prefix_box: test_name_prefix_box,
+            ux_cf_decimal_separator_choice: decimal_separator_choice,
+            ux_cf_thousands_separator_choice: thousands_separator_choice,
+            ux_cf_profile_choice: profile_choice,
+            profile_names: Vec::new(),
+            ux_preview_label: preview_label,
+            ux_preview_lines_box: preview_lines_box,
+            ux_preview_values_box: preview_values_box,
+            config_base: ConfigStore::default(),
         }//end struct construction
     }//end initialize()
 }//end impl for GUI
\ No newline at end of file
@@ -0,0 +1,372 @@
+use std::{cell::RefCell, collections::HashSet, fs, path::{Path, PathBuf}};
+
+use sha2::{Digest, Sha256};
+
+use crate::{data::{ParseIssue, ParseIssueKind}, util};
+
+/// The extension of input files to look for when given a directory as input.
+pub const INPUT_EXTENSION: &str = "txt";
+
+/// Gets the filename of a path as a string, for use in messages and output.
+/// If the path doesn't have a filename, "UNKNOWN FILENAME" is returned instead.
+pub fn get_filename(path: &Path) -> String {
+    match path.file_name() {
+        Some(osstr) => osstr.to_string_lossy().into_owned(),
+        None => "UNKNOWN FILENAME".to_string(),
+    }//end matching whether we can get the filename
+}//end get_filename()
+
+/// Expands a list of paths into a list of input files.
+/// Files are kept as they are, and directories are replaced by the files directly
+/// inside them which have an extension of INPUT_EXTENSION, sorted by name.
+/// Duplicate paths are only included once.
+pub fn gather_input_paths(paths: &Vec<PathBuf>) -> Result<Vec<PathBuf>,String> {
+    let mut input_paths: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let dir_entries = match fs::read_dir(path) {
+                Ok(dir_entries) => dir_entries,
+                Err(msg) => return Err(format!("Couldn't read the directory \"{}\":\n{}", path.to_string_lossy(), msg)),
+            };
+            let mut dir_paths: Vec<PathBuf> = dir_entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|entry_path| entry_path.is_file() && has_input_extension(entry_path))
+                .collect();
+            dir_paths.sort();
+            for dir_path in dir_paths {
+                if !input_paths.contains(&dir_path) {input_paths.push(dir_path);}
+            }//end adding each file from the directory
+        }//end if path is a directory
+        else if path.is_file() {
+            if !input_paths.contains(path) {input_paths.push(path.clone());}
+        }//end else if path is a file
+        else {
+            return Err(format!("The input path \"{}\" doesn't exist.", path.to_string_lossy()));
+        }//end else path isn't anything we can read
+    }//end looping over each path given
+    Ok(input_paths)
+}//end gather_input_paths()
+
+/// Finds every file with an extension of INPUT_EXTENSION in dir or any
+/// directory inside it, sorted by path.  
+/// Returns the files, along with a message for each directory inside dir that couldn't be read,
+/// or an error if dir itself couldn't be read.
+pub fn find_input_files_recursive(dir: &Path) -> Result<(Vec<PathBuf>,Vec<String>),String> {
+    let mut unreadable_dirs = Vec::new();
+    let mut input_paths = find_files_recursive(dir, &|_| false, &mut unreadable_dirs)?;
+    input_paths.retain(|input_path| has_input_extension(input_path));
+    Ok((input_paths, unreadable_dirs))
+}//end find_input_files_recursive()
+
+/// Finds every file in dir or any directory inside it, sorted by path.  
+/// Directories for which skip_dir returns true aren't searched. Directories reached more than
+/// once, such as through a symlink back up the tree, are only searched the first time.  
+/// Directories inside dir which can't be read are skipped, with a message added to unreadable_dirs.
+/// Returns an error only if dir itself can't be read.
+fn find_files_recursive(dir: &Path, skip_dir: &dyn Fn(&Path) -> bool, unreadable_dirs: &mut Vec<String>) -> Result<Vec<PathBuf>,String> {
+    let dir_entries = match fs::read_dir(dir) {
+        Ok(dir_entries) => dir_entries,
+        Err(msg) => return Err(format!("Couldn't read the directory \"{}\":\n{}", dir.to_string_lossy(), msg)),
+    };
+    let mut visited_dirs = HashSet::new();
+    if let Ok(canonical_dir) = fs::canonicalize(dir) {visited_dirs.insert(canonical_dir);}
+    let mut file_paths = Vec::new();
+    add_files_recursive(dir_entries, skip_dir, &mut visited_dirs, &mut file_paths, unreadable_dirs);
+    file_paths.sort();
+    Ok(file_paths)
+}//end find_files_recursive()
+
+/// Adds each file in dir_entries to file_paths, and searches each directory in
+/// dir_entries which isn't skipped and isn't in visited_dirs, for find_files_recursive().
+fn add_files_recursive(dir_entries: fs::ReadDir, skip_dir: &dyn Fn(&Path) -> bool, visited_dirs: &mut HashSet<PathBuf>, file_paths: &mut Vec<PathBuf>, unreadable_dirs: &mut Vec<String>) {
+    for entry_path in dir_entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+        if entry_path.is_dir() {
+            if skip_dir(&entry_path) {continue;}
+            // symlinks can lead back to a directory we're already in, which would never end
+            if let Ok(canonical_dir) = fs::canonicalize(&entry_path) {
+                if !visited_dirs.insert(canonical_dir) {continue;}
+            }//end if we know where the directory really is
+            match fs::read_dir(&entry_path) {
+                Ok(sub_entries) => add_files_recursive(sub_entries, skip_dir, visited_dirs, file_paths, unreadable_dirs),
+                Err(msg) => unreadable_dirs.push(format!("Couldn't read the directory \"{}\":\n{}", entry_path.to_string_lossy(), msg)),
+            }//end matching whether we can read the directory
+        }//end if this is a directory
+        else if entry_path.is_file() {file_paths.push(entry_path);}
+    }//end looking at each entry in the directory
+}//end add_files_recursive()
+
+/// The files found by scan_input_folder(), sorted by path.
+#[derive(Clone,PartialEq,Debug,Default)]
+pub struct FolderScan {
+    /// The files matching an include pattern and no exclude pattern.
+    pub included: Vec<PathBuf>,
+    /// The files matching an include pattern, but left out by an exclude pattern.
+    pub excluded: Vec<PathBuf>,
+    /// The folders left out by an exclude pattern ending in "/", which weren't searched.
+    pub excluded_dirs: Vec<PathBuf>,
+    /// The number of files which didn't match any include pattern.
+    pub unmatched: usize,
+    /// A message for each folder which couldn't be read, and so was skipped.
+    pub unreadable_dirs: Vec<String>,
+}//end struct FolderScan
+
+/// Finds every file in dir or any directory inside it which matches one of include_patterns,
+/// and sorts them by whether they also match one of exclude_patterns.  
+/// Patterns are matched against the path relative to dir, with glob_match(). Folders matching
+/// an exclude pattern ending in "/" or "/**" are left out without being searched.
+pub fn scan_input_folder(dir: &Path, include_patterns: &[String], exclude_patterns: &[String]) -> Result<FolderScan,String> {
+    let mut scan = FolderScan::default();
+    let dir_patterns: Vec<&String> = exclude_patterns.iter().filter(|pattern| is_folder_pattern(pattern)).collect();
+    let excluded_dirs = RefCell::new(Vec::new());
+    let skip_dir = |sub_dir: &Path| {
+        let relative_path = get_relative_path_text(dir, sub_dir);
+        let skip = dir_patterns.iter().any(|pattern| glob_match(pattern, &relative_path));
+        if skip {excluded_dirs.borrow_mut().push(sub_dir.to_path_buf());}
+        skip
+    };
+    for file_path in find_files_recursive(dir, &skip_dir, &mut scan.unreadable_dirs)? {
+        let relative_path = get_relative_path_text(dir, &file_path);
+        if !include_patterns.iter().any(|pattern| glob_match(pattern, &relative_path)) {scan.unmatched += 1;}
+        else if exclude_patterns.iter().any(|pattern| glob_match(pattern, &relative_path)) {scan.excluded.push(file_path);}
+        else {scan.included.push(file_path);}
+    }//end sorting out each file in the folder
+    scan.excluded_dirs = excluded_dirs.into_inner();
+    scan.excluded_dirs.sort();
+    Ok(scan)
+}//end scan_input_folder()
+
+/// Returns true if a glob pattern only matches what's inside folders, such as "archive/" or "old/**",
+/// so that a folder it matches can be left out without looking inside.
+fn is_folder_pattern(pattern: &str) -> bool {
+    let pattern = pattern.trim().replace('\\', "/");
+    pattern.ends_with('/') || pattern.ends_with("/**")
+}//end is_folder_pattern()
+
+/// Gets the path of file_path relative to dir, with "/" between folders, for matching with glob_match().
+pub fn get_relative_path_text(dir: &Path, file_path: &Path) -> String {
+    let relative_path = file_path.strip_prefix(dir).unwrap_or(file_path);
+    relative_path.components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<String>>()
+        .join("/")
+}//end get_relative_path_text()
+
+/// Returns true if relative_path, with "/" between folders, matches the glob pattern.  
+/// In a pattern, "*" matches any run of characters within a folder or file name, "?" matches a
+/// single character, and "**" matches any number of folders. Case is ignored.  
+/// A pattern ending in "/" matches everything inside a matching folder, and a pattern
+/// without any other "/" matches at any depth, so "archive/" leaves out every folder
+/// named archive, and "*ALV*.txt" is the same as "**/*ALV*.txt".
+pub fn glob_match(pattern: &str, relative_path: &str) -> bool {
+    let mut pattern = pattern.trim().replace('\\', "/");
+    if pattern.is_empty() {return false;}
+    if !pattern.trim_end_matches('/').contains('/') {pattern = format!("**/{}", pattern);}
+    if pattern.ends_with('/') {pattern.push_str("**");}
+    let pattern_parts: Vec<&str> = pattern.trim_start_matches('/').split('/').collect();
+    let path_parts: Vec<&str> = relative_path.split('/').collect();
+    glob_match_parts(&pattern_parts, &path_parts)
+}//end glob_match()
+
+/// Matches the folders and file name of a path against the parts of a glob pattern, for glob_match().
+fn glob_match_parts(pattern_parts: &[&str], path_parts: &[&str]) -> bool {
+    match pattern_parts.first() {
+        None => path_parts.is_empty(),
+        Some(&"**") => (0..=path_parts.len()).any(|skip| glob_match_parts(&pattern_parts[1..], &path_parts[skip..])),
+        Some(part) => match path_parts.first() {
+            Some(path_part) => util::wildcard_match(part, path_part) && glob_match_parts(&pattern_parts[1..], &path_parts[1..]),
+            None => false,
+        },
+    }//end matching the first part of the pattern
+}//end glob_match_parts()
+
+/// Gets the paths from the text of a drag and drop, which has one path per line.  
+/// Paths given as "file://" urls, as some desktops do, are turned back into plain paths.
+pub fn parse_dropped_paths(dropped_text: &str) -> Vec<PathBuf> {
+    dropped_text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.strip_prefix("file://") {
+            Some(url_path) => PathBuf::from(decode_url_path(url_path)),
+            None => PathBuf::from(line),
+        })
+        .collect()
+}//end parse_dropped_paths()
+
+/// Decodes the %XX escapes in the path part of a url, such as "%20" for a space.  
+/// Anything that isn't a valid escape is left as it is.
+fn decode_url_path(url_path: &str) -> String {
+    let bytes = url_path.as_bytes();
+    let mut decoded = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let escaped = match bytes.get(idx + 1..idx + 3) {
+            Some(hex) if bytes[idx] == b'%' => u8::from_str_radix(&String::from_utf8_lossy(hex), 16).ok(),
+            _ => None,
+        };
+        match escaped {
+            Some(byte) => {decoded.push(byte); idx += 3;},
+            None => {decoded.push(bytes[idx]); idx += 1;},
+        }//end matching whether this is an escaped byte
+    }//end looping over each byte of the path
+    String::from_utf8_lossy(&decoded).into_owned()
+}//end decode_url_path()
+
+/// Expands dropped paths into input files, recursing into directories, and adds
+/// them to input_paths, skipping any already there.  
+/// Files without an extension of INPUT_EXTENSION are ignored.  
+/// Returns the number of paths added, along with a message for any directory that couldn't be read.
+pub fn add_dropped_input_paths(input_paths: &mut Vec<PathBuf>, dropped_paths: &Vec<PathBuf>) -> (usize, Vec<String>) {
+    let mut added = 0;
+    let mut errs = Vec::new();
+    for dropped_path in dropped_paths {
+        let new_paths = if dropped_path.is_dir() {
+            match find_input_files_recursive(dropped_path) {
+                Ok((new_paths, mut unreadable_dirs)) => {errs.append(&mut unreadable_dirs); new_paths},
+                Err(msg) => {errs.push(msg); continue;},
+            }//end matching whether we could read the directory
+        } else if dropped_path.is_file() && has_input_extension(dropped_path) {
+            vec![dropped_path.clone()]
+        } else {Vec::new()};
+        for new_path in new_paths {
+            if !input_paths.contains(&new_path) {
+                input_paths.push(new_path);
+                added += 1;
+            }//end if this path is new
+        }//end adding each new path
+    }//end looping over each dropped path
+    (added, errs)
+}//end add_dropped_input_paths()
+
+/// Returns true if the path has an extension matching INPUT_EXTENSION, ignoring case.
+pub fn has_input_extension(path: &Path) -> bool {
+    match path.extension() {
+        Some(ext) => ext.to_string_lossy().eq_ignore_ascii_case(INPUT_EXTENSION),
+        None => false,
+    }//end matching whether path has an extension
+}//end has_input_extension()
+
+/// Reads the contents of an input file as a string, decoding it with decode_input_bytes().
+pub fn read_input_file(path: &Path) -> Result<String,String> {
+    read_input_file_with_encoding(path).map(|input_file| input_file.contents)
+}//end read_input_file()
+
+/// The decoded contents of an input file, along with how it was read.
+#[derive(Clone,PartialEq,Debug)]
+pub struct InputFile {
+    /// The contents of the file, from decode_input_bytes().
+    pub contents: String,
+    /// The encoding the file was read as.
+    pub encoding: TextEncoding,
+    /// A hash of the bytes of the file, from hash_file_contents(), so that
+    /// the same file is recognized no matter how it was decoded.
+    pub hash: String,
+}//end struct InputFile
+
+/// Reads the contents of an input file as a string, decoding it with decode_input_bytes().  
+/// Returns the contents along with the encoding the file was read as and a hash of its bytes.
+pub fn read_input_file_with_encoding(path: &Path) -> Result<InputFile,String> {
+    match fs::read(path) {
+        Ok(bytes) => {
+            let (contents, encoding) = decode_input_bytes(&bytes);
+            Ok(InputFile{contents, encoding, hash: hash_file_contents(&bytes)})
+        },
+        Err(msg) => Err(msg.to_string()),
+    }//end matching whether we could read the file
+}//end read_input_file_with_encoding()
+
+/// The text encodings input files can be read as.
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum TextEncoding {
+    /// UTF-8 without a byte order mark, which is what most files are.
+    Utf8,
+    /// UTF-8 starting with a byte order mark, which is removed.
+    Utf8Bom,
+    /// UTF-16, little endian, with or without a byte order mark.
+    Utf16Le,
+    /// UTF-16, big endian, with or without a byte order mark.
+    Utf16Be,
+    /// Windows-1252, used when a file isn't valid in any of the others.
+    /// Every byte can be decoded this way, so this is the last resort.
+    Windows1252,
+}//end enum TextEncoding
+
+impl TextEncoding {
+    /// Gets the usual name of the encoding.
+    pub fn to_string(&self) -> String {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Utf8Bom => "UTF-8 with BOM",
+            TextEncoding::Utf16Le => "UTF-16LE",
+            TextEncoding::Utf16Be => "UTF-16BE",
+            TextEncoding::Windows1252 => "Windows-1252",
+        }.to_string()
+    }//end to_string(self)
+
+    /// Gets a note saying filename was read as this encoding, to keep with the file's other issues,
+    /// or None if this is plain UTF-8, which doesn't need mentioning.
+    pub fn get_issue(&self, filename: &str) -> Option<ParseIssue> {
+        match self {
+            TextEncoding::Utf8 => None,
+            _ => Some(ParseIssue::new(filename, None, ParseIssueKind::Encoding{encoding: self.to_string()})),
+        }//end matching whether this encoding is worth mentioning
+    }//end get_issue(self)
+}//end impl for TextEncoding
+
+/// The characters Windows-1252 uses for bytes 0x80 to 0x9F, where it differs from Latin-1.  
+/// Bytes Windows-1252 doesn't define are kept as the control character of the same value.
+const WINDOWS_1252_HIGH_CHARS: [char; 32] = [
+    '\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}', '\u{8F}',
+    '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{2DC}', '\u{2122}', '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}', '\u{178}',
+];
+
+/// Decodes the bytes of an input file into a string, returning the encoding used.  
+/// A byte order mark decides the encoding if there is one, and is removed. Otherwise, files
+/// with a null byte in every other position of their ascii text are read as UTF-16, files
+/// which are valid UTF-8 are read as UTF-8, and anything else is read as Windows-1252.
+pub fn decode_input_bytes(bytes: &[u8]) -> (String, TextEncoding) {
+    if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return (String::from_utf8_lossy(rest).into_owned(), TextEncoding::Utf8Bom);
+    }//end if there's a UTF-8 byte order mark
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {return (decode_utf16(rest, false), TextEncoding::Utf16Le);}
+    if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {return (decode_utf16(rest, true), TextEncoding::Utf16Be);}
+    // without a byte order mark, ascii text in UTF-16 has a null in every other byte
+    if bytes.len() >= 2 && bytes.len() % 2 == 0 {
+        let pairs = bytes.len() / 2;
+        let even_nulls = bytes.iter().step_by(2).filter(|byte| **byte == 0).count();
+        let odd_nulls = bytes.iter().skip(1).step_by(2).filter(|byte| **byte == 0).count();
+        if odd_nulls * 2 > pairs && even_nulls * 4 < odd_nulls {return (decode_utf16(bytes, false), TextEncoding::Utf16Le);}
+        if even_nulls * 2 > pairs && odd_nulls * 4 < even_nulls {return (decode_utf16(bytes, true), TextEncoding::Utf16Be);}
+    }//end if the file could be UTF-16
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), TextEncoding::Utf8),
+        Err(_) => {
+            let text = bytes.iter().map(|byte| match byte {
+                0x80..=0x9F => WINDOWS_1252_HIGH_CHARS[(byte - 0x80) as usize],
+                _ => *byte as char,
+            }).collect();
+            (text, TextEncoding::Windows1252)
+        },
+    }//end matching whether the file is valid UTF-8
+}//end decode_input_bytes()
+
+/// Decodes UTF-16 bytes, replacing anything invalid, including a last odd byte.
+fn decode_utf16(bytes: &[u8], big_endian: bool) -> String {
+    let units: Vec<u16> = bytes.chunks(2)
+        .map(|pair| match pair {
+            [first, second] if big_endian => u16::from_be_bytes([*first, *second]),
+            [first, second] => u16::from_le_bytes([*first, *second]),
+            _ => 0xFFFD,
+        })
+        .collect();
+    String::from_utf16_lossy(&units)
+}//end decode_utf16()
+
+/// Gets a hash of the bytes of a file, as a lowercase hex string.  
+/// This can be used to tell whether two files have the same contents.
+pub fn hash_file_contents(file_bytes: &[u8]) -> String {
+    let digest = Sha256::digest(file_bytes);
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}//end hash_file_contents()
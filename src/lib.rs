@@ -1,17 +1,50 @@
 
+/// This module contains small helpers shared
+/// by several other modules.
+pub mod util;
+
 /// This module contains code for loading
 /// data from a file and storing that data.
 pub mod data;
 
+/// This module contains code for finding and
+/// reading the input files to load data from.
+pub mod input;
+
 /// This module contains code for storing,
 /// loading, and saving configuration information.
 pub mod config_store;
 
+/// This module contains code for storing
+/// specification profiles and grading
+/// data against them.
+pub mod spec;
+
+/// This module contains code for reading and
+/// parsing batches of input files on worker
+/// threads.
+pub mod batch;
+
 /// This module contains code for processing
 /// data from the data module and exporting
 /// that data to a file.
 pub mod process;
 
+/// This module contains code for saving
+/// data to a local SQLite database, so that
+/// results are kept between runs.
+pub mod database;
+
+/// This module contains code for watching a
+/// folder and processing new input files as
+/// they appear.
+pub mod watch;
+
+/// This module contains code for parsing the
+/// arguments used to run without opening a
+/// window.
+pub mod args;
+
 /// This module contains automated testing for
 /// various functions in other modules
 #[cfg(test)]
@@ -1,114 +1,511 @@
 #![cfg_attr(not(debug_assertions),windows_subsystem = "windows")]
-use std::{fs, path::PathBuf, time::{Duration, Instant}};
+use std::{env, path::PathBuf, process, time::{Duration, Instant, SystemTime}};
 
-use alveograph_exporter_s::{config_store::{self, ConfigStore}, data::{self, Data}, process::{close_workbook, get_workbook, write_output_to_sheet}};
+use alveograph_exporter_s::{batch::{FileOutcome, ParseBatch}, config_store::{self, ConfigLocation, ConfigProblem, ConfigProfiles, ConfigStore, ReadRowMode}, data::{self, Data, IssueSeverity}, database, input, process::{apply_review_inclusions, close_workbook, get_issues_output_path, get_long_output_path, get_workbook, write_issues_to_delimited, write_issues_to_sheet, write_long_output_to_delimited, write_long_output_to_sheet, write_output_to_delimited, write_output_to_sheet, FileIssues, OutputFormat, ISSUES_SHEET_NAME, LONG_OUTPUT_SHEET_NAME}, spec::{self, SpecStore}, watch::{self, WatchLedger, WatchPoll, WatchSettings}};
 use gui::GUI;
 
+mod cli;
 mod gui;
 
+/// The process id AttachConsole() takes to mean the console of the parent process.
+#[cfg(windows)]
+const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    /// Attaches to the console of the process with the given id. Returns 0 if it failed.
+    fn AttachConsole(process_id: u32) -> i32;
+}//end extern kernel32
+
+/// Attaches to the console of whatever started the program, so that
+/// output from the command-line interface can be seen in release builds,
+/// which are built without a console of their own.
+fn attach_parent_console() {
+    #[cfg(windows)]
+    unsafe {
+        // if there isn't a parent console, such as from a shortcut, there's nowhere to print anyway
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }//end unsafe call to kernel32
+}//end attach_parent_console()
+
 fn main() {
+    // run headless instead if we were given a command
+    let args: Vec<String> = env::args().collect();
+    if alveograph_exporter_s::args::is_cli_invocation(&args) {
+        attach_parent_console();
+        process::exit(cli::run(&args));
+    }//end if we should use the command-line interface
+
+    // the local offset for watch mode can only be found reliably before the gui starts other threads
+    let local_offset = watch::get_local_offset();
+
     // setup gui
     let mut gui = GUI::initialize();
     let recv = gui.get_receiver();
 
     // get config information
-    let config_name = "config";
+    let config_name = config_store::CONFIG_FILE_NAME;
     let mut config_path: Option<PathBuf> = None;
-    let mut config_store: ConfigStore = ConfigStore::default();
+    let mut config_location: Option<ConfigLocation> = None;
+    let mut config_profiles: ConfigProfiles = ConfigProfiles::default();
+    let mut config_store: ConfigStore;
 
     // make sure we get config information, update gui, walk user through fix if necessary
-    ensure_config_valid(&mut gui, &mut config_store, &mut config_path, config_name);
-    // update gui with given config store
-    let _ = gui.set_config_store(&config_store);
+    ensure_config_valid(&mut gui, &mut config_profiles, &mut config_path, &mut config_location, config_name);
+    // update gui with the profile used last
+    show_config_profiles(&mut gui, &config_profiles);
+    // get spec profiles, if the user has any
+    let spec_store = load_spec_store(&mut gui);
+    // files dropped onto the program are used as input
+    if args.len() > 1 {
+        let mut input_paths = Vec::new();
+        let dropped_paths: Vec<PathBuf> = args[1..].iter().map(PathBuf::from).collect();
+        let (_, errs) = input::add_dropped_input_paths(&mut input_paths, &dropped_paths);
+        if !errs.is_empty() {gui.integrated_dialog_alert(&format!("Some of the files the program was opened with couldn't be read:\n{}", errs.join("\n")));}
+        if !input_paths.is_empty() {
+            gui.set_last_input_paths(input_paths);
+            update_parse_preview(&mut gui);
+        }//end if any of the files can be used
+    }//end if the program was opened with files
+    // the folders and ledger used while watching a folder, if we are
+    let mut watch_state: Option<(WatchSettings,WatchLedger)> = None;
+    // the check of the watched folder running on a worker thread, if there is one
+    let mut watch_poll: Option<WatchPoll> = None;
 
     while gui.wait() {
         match recv.recv() {
             Some(gui::InterfaceMessage::AppClosing) => {
-                if let Some(config_path_v) = config_path {
-                    match gui.get_config_store() {
-                        Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't get save config store because:\n{}", msg)),
-                        Ok(config) => {
-                            if let Err(msg) = config_store::try_write_config(&config_path_v, &config) {
-                                gui.integrated_dialog_alert(&format!("We weren't able to save the config file. Error message is:\n{}", msg));
-                            }//end if writing is not successful
-                        },
-                    }//end matching whether or not we can get the config store
-                    // move this back after we're done with it
-                    config_path = Some(config_path_v);
-                }//end if we have valid config_path
+                store_gui_config(&mut gui, &mut config_profiles);
+                save_config_profiles(&mut gui, &config_profiles, &config_path);
                 GUI::quit();
             },
+            Some(gui::InterfaceMessage::ConfigEdited) => {
+                check_config(&mut gui);
+                update_parse_preview(&mut gui);
+            },
+            Some(gui::InterfaceMessage::WatchToggle) => {
+                if let Some((settings,_)) = watch_state.take() {
+                    gui.stop_watch_timer();
+                    gui.show_watch_log(&[format!("Stopped watching \"{}\".", settings.watch_dir.to_string_lossy())]);
+                    continue;
+                }//end if we were already watching
+                let config_problems = check_config(&mut gui);
+                if config_problems.iter().any(|problem| problem.severity() == IssueSeverity::Error) {
+                    gui.integrated_dialog_alert("The highlighted config settings need to be fixed before watching a folder.");
+                    continue;
+                }//end if the config can't be used
+                let (watch_dir, output_dir) = match gui.get_watch_dirs_from_user() {
+                    Some(dirs) => dirs,
+                    None => continue,
+                };
+                let mut settings = WatchSettings::new(watch_dir, output_dir);
+                if gui.get_database_output() {
+                    let mut config = gui.get_config_store().unwrap_or_default();
+                    if config.database_path.is_empty() {
+                        if let Some(db_path) = gui.get_database_path_from_user() {
+                            config.database_path = db_path.to_string_lossy().to_string();
+                            let _ = gui.set_config_store(&config);
+                        }//end if user chose a database path
+                    }//end if we don't know where the database is yet
+                    if !config.database_path.is_empty() {settings.database_path = Some(PathBuf::from(&config.database_path));}
+                }//end if user wants results saved to a database
+                match watch::try_read_ledger(&settings.ledger_path()) {
+                    Ok(ledger) => {
+                        gui.show_watch_log(&[format!("Watching \"{}\", writing daily workbooks to \"{}\".", settings.watch_dir.to_string_lossy(), settings.output_dir.to_string_lossy())]);
+                        watch_state = Some((settings, ledger));
+                        gui.start_watch_timer(watch::DEFAULT_POLL_SECS as f64);
+                    },
+                    Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't read the watch ledger \"{}\":\n{}", settings.ledger_path().to_string_lossy(), msg)),
+                }//end matching whether we could read the ledger
+            },
+            Some(gui::InterfaceMessage::WatchTick) => {
+                // wait for the last check to finish before starting another
+                if watch_poll.is_some() {continue;}
+                let (settings, ledger) = match watch_state.as_ref() {
+                    Some(watch_state) => (&watch_state.0, &watch_state.1),
+                    None => continue,
+                };
+                // use the settings shown in the gui, so changes apply to the next files
+                let config = match gui.get_config_store() {
+                    Ok(config) => config,
+                    Err(_) => continue,
+                };
+                let s = gui.get_sender();
+                watch_poll = Some(WatchPoll::start(settings, ledger, &config, &spec_store, SystemTime::now(), local_offset, move || s.send(gui::InterfaceMessage::WatchDone)));
+            },
+            Some(gui::InterfaceMessage::WatchDone) => {
+                let poll = match watch_poll.take() {
+                    Some(poll) => poll,
+                    None => continue,
+                };
+                let settings = poll.get_settings().clone();
+                let (ledger, report) = match poll.take_result() {
+                    Ok(result) => result,
+                    Err(msg) => {gui.show_watch_log(&[msg]); continue;},
+                };
+                gui.show_watch_log(&report.get_lines());
+                // the ledger is saved even if watching stopped during the check, since the output was still written
+                if let Err(msg) = watch::try_write_ledger(&settings.ledger_path(), &ledger) {
+                    gui.show_watch_log(&[format!("Couldn't save the watch ledger \"{}\":\n{}", settings.ledger_path().to_string_lossy(), msg)]);
+                }//end if we couldn't save the ledger
+                if let Some((watch_settings, watch_ledger)) = watch_state.as_mut() {
+                    if *watch_settings == settings {*watch_ledger = ledger;}
+                }//end if we're still watching with the same settings
+            },
+            Some(gui::InterfaceMessage::InputFolder) => {
+                let input_dir = match gui.get_input_dir_from_user() {
+                    Some(input_dir) => input_dir,
+                    None => continue,
+                };
+                let mut config = match gui.get_config_store() {
+                    Ok(config) => config,
+                    Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't get the config settings:\n{}", msg)); continue;},
+                };
+                if let Some(input_paths) = gui.review_input_folder(&input_dir, &mut config.input_include_patterns, &mut config.input_exclude_patterns) {
+                    // keep the patterns used for next time
+                    let _ = gui.set_config_store(&config);
+                    gui.set_last_input_paths(input_paths);
+                    check_config(&mut gui);
+                    update_parse_preview(&mut gui);
+                }//end if the user chose files from the folder
+            },
+            Some(gui::InterfaceMessage::InputsChanged) => {
+                check_config(&mut gui);
+                update_parse_preview(&mut gui);
+            },
+            Some(gui::InterfaceMessage::ConfigImport) => {
+                let import_path = match gui.get_config_path_from_user(false) {
+                    Some(import_path) => import_path,
+                    None => continue,
+                };
+                let (imported_config, defaulted_fields) = match config_store::try_import_config(&import_path) {
+                    Ok(imported) => imported,
+                    Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't import the config file \"{}\":\n{}", import_path.to_string_lossy(), msg)); continue;},
+                };
+                let current_config = match gui.get_config_store() {
+                    Ok(current_config) => current_config,
+                    Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't get the current config settings:\n{}", msg)); continue;},
+                };
+                let differences = config_store::get_config_differences(&current_config, &imported_config);
+                if differences.is_empty() {
+                    gui.integrated_dialog_message(&format!("The settings in \"{}\" are the same as the profile \"{}\", so nothing needs to change.", import_path.to_string_lossy(), config_profiles.current_name()));
+                    continue;
+                }//end if there's nothing to import
+                let mut difference_msg = differences.iter().map(|difference| difference.to_string()).collect::<Vec<String>>().join("\n");
+                if !defaulted_fields.is_empty() {
+                    difference_msg.push_str(&format!("\nThese settings aren't in the file, so they'll use their default values: {}", defaulted_fields.join(", ")));
+                }//end if the file is from before some settings were added
+                if gui.integrated_dialog_yes_no(&format!("Importing \"{}\" will make these changes to the profile \"{}\":\n{}\nWould you like to import it?", import_path.to_string_lossy(), config_profiles.current_name(), difference_msg)) {
+                    config_profiles.set_current(imported_config);
+                    show_config_profiles(&mut gui, &config_profiles);
+                    save_config_profiles(&mut gui, &config_profiles, &config_path);
+                }//end if the user wants to import the changes
+            },
+            Some(gui::InterfaceMessage::ConfigExport) => {
+                let export_path = match gui.get_config_path_from_user(true) {
+                    Some(export_path) => export_path,
+                    None => continue,
+                };
+                match gui.get_config_store() {
+                    Ok(config) => match config_store::try_write_config(&export_path, &config) {
+                        Ok(_) => gui.integrated_dialog_message(&format!("The settings of the profile \"{}\" were exported to \"{}\".", config_profiles.current_name(), export_path.to_string_lossy())),
+                        Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't export the config to \"{}\":\n{}", export_path.to_string_lossy(), msg)),
+                    },
+                    Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't get the current config settings:\n{}", msg)),
+                }//end matching whether we can get the config to export
+            },
+            Some(gui::InterfaceMessage::ConfigFileInfo) => {
+                match (&config_path, config_location) {
+                    (Some(config_path_v), Some(location)) => {
+                        let info = format!("Your settings are saved in the config file \"{}\", {}.\nTo keep them somewhere else, set the {} environment variable to a folder.", config_path_v.to_string_lossy(), location.to_string(), config_store::CONFIG_DIR_ENV_VAR);
+                        if gui.integrated_dialog_message_choice(&info, vec!["Show File", "Ok"]) == Some(0) {
+                            opener::reveal(config_path_v).unwrap_or_else(|e| eprintln!("Couldn't reveal config file due to {}", e));
+                        }//end if user wants to see the file
+                    },
+                    _ => gui.integrated_dialog_message("There isn't a config file in use, so your settings won't be saved when you close the program."),
+                }//end matching whether we have a config file
+            },
+            Some(gui::InterfaceMessage::ProfileSwitch) => {
+                if let Some(profile_name) = gui.get_selected_profile() {
+                    if profile_name.eq(&config_profiles.current_name()) {continue;}
+                    store_gui_config(&mut gui, &mut config_profiles);
+                    if let Err(msg) = config_profiles.switch(&profile_name) {gui.integrated_dialog_alert(&msg);}
+                    show_config_profiles(&mut gui, &config_profiles);
+                    save_config_profiles(&mut gui, &config_profiles, &config_path);
+                }//end if the user selected a profile
+            },
+            Some(gui::InterfaceMessage::ProfileNew) => {
+                if let Some(profile_name) = gui.get_profile_name_from_user("Name for the new profile, which will start with the default settings:", "") {
+                    store_gui_config(&mut gui, &mut config_profiles);
+                    if let Err(msg) = config_profiles.create(&profile_name, ConfigStore::default()) {gui.integrated_dialog_alert(&msg);}
+                    show_config_profiles(&mut gui, &config_profiles);
+                    save_config_profiles(&mut gui, &config_profiles, &config_path);
+                }//end if the user gave a name
+            },
+            Some(gui::InterfaceMessage::ProfileDuplicate) => {
+                let current_name = config_profiles.current_name();
+                if let Some(profile_name) = gui.get_profile_name_from_user(&format!("Name for the copy of \"{}\":", current_name), &format!("{} copy", current_name)) {
+                    store_gui_config(&mut gui, &mut config_profiles);
+                    if let Err(msg) = config_profiles.duplicate(&current_name, &profile_name) {gui.integrated_dialog_alert(&msg);}
+                    show_config_profiles(&mut gui, &config_profiles);
+                    save_config_profiles(&mut gui, &config_profiles, &config_path);
+                }//end if the user gave a name
+            },
+            Some(gui::InterfaceMessage::ProfileRename) => {
+                let current_name = config_profiles.current_name();
+                if let Some(profile_name) = gui.get_profile_name_from_user(&format!("New name for \"{}\":", current_name), &current_name) {
+                    if profile_name.eq(&current_name) {continue;}
+                    store_gui_config(&mut gui, &mut config_profiles);
+                    if let Err(msg) = config_profiles.rename(&current_name, &profile_name) {gui.integrated_dialog_alert(&msg);}
+                    show_config_profiles(&mut gui, &config_profiles);
+                    save_config_profiles(&mut gui, &config_profiles, &config_path);
+                }//end if the user gave a name
+            },
+            Some(gui::InterfaceMessage::ProfileDelete) => {
+                let current_name = config_profiles.current_name();
+                if gui.integrated_dialog_yes_no(&format!("Are you sure you want to delete the profile \"{}\" and all of its settings?", current_name)) {
+                    if let Err(msg) = config_profiles.delete(&current_name) {gui.integrated_dialog_alert(&msg);}
+                    show_config_profiles(&mut gui, &config_profiles);
+                    save_config_profiles(&mut gui, &config_profiles, &config_path);
+                }//end if the user is sure
+            },
             Some(gui::InterfaceMessage::ConfigReset) => {
                 if let Err(msg) = gui.set_config_store(&ConfigStore::default()) {
                     gui.integrated_dialog_alert(&format!("There was an issue resetting the config!:\n{}", msg));
                 }//end if we had an error while trying to reset config store
+                check_config(&mut gui);
+                update_parse_preview(&mut gui);
             },
             Some(gui::InterfaceMessage::Process) => {
                 // get input and output paths from gui/user
                 let input_paths = gui.get_last_input_paths();
                 let output_path = gui.get_last_output_paths();
+                let output_format = gui.get_output_format();
+                let long_output = gui.get_long_output();
+                let issues_output = gui.get_issues_output();
                 // make sure we have valid input and output paths
                 let input_valid = validate_input_paths(&input_paths, &mut gui);
-                let output_path = validate_output_path(output_path, &mut gui);
+                let output_path = validate_output_path(output_path, output_format, &mut gui);
                 if !input_valid || output_path.is_err() {continue;}
                 let output_path = output_path.expect("We already checked it wasn't an error.");
                 // grab configuration details from the gui
                 config_store = gui.get_config_store().unwrap();
+                // make sure the config makes sense before we start, including with the input files and spec profiles
+                let mut config_problems = check_config(&mut gui);
+                // only Max mode needs the files read ahead of time, so other modes don't read every file twice
+                let input_files: Vec<(String,String)> = match config_store.read_row_mode {
+                    ReadRowMode::Max => input_paths.iter()
+                        .filter_map(|input_path| input::read_input_file(input_path).ok().map(|file_contents| (input::get_filename(input_path), file_contents)))
+                        .collect(),
+                    ReadRowMode::Header => Vec::new(),
+                };
+                let other_problems: Vec<ConfigProblem> = config_store.validate_files(&input_files).into_iter()
+                    .chain(config_store.validate_specs(&spec_store))
+                    .collect();
+                if !other_problems.is_empty() {
+                    config_problems.extend(other_problems);
+                    gui.highlight_config_problems(&config_problems);
+                }//end if the files or specs don't fit the config
+                if !config_problems.is_empty() {
+                    let problem_msg = config_problems.iter().map(|problem| format!("{}: {}", problem.severity(), problem)).collect::<Vec<String>>().join("\n");
+                    if config_problems.iter().any(|problem| problem.severity() == IssueSeverity::Error) {
+                        gui.integrated_dialog_alert(&format!("The highlighted config settings need to be fixed before processing:\n{}", problem_msg));
+                        continue;
+                    } else if !gui.integrated_dialog_yes_no(&format!("The highlighted config settings might not work as expected:\n{}\nWould you like to process anyway?", problem_msg)) {
+                        continue;
+                    }//end else if the user doesn't want to continue despite the warnings
+                }//end if there are problems with the config
                 // proceed with processing calls
                 gui.start_wait();
                 let start = Instant::now();
+                // read and parse files on worker threads, so the window keeps responding
+                let mut parse_batch = ParseBatch::start(&input_paths, &config_store);
+                if !gui.show_parse_progress(&mut parse_batch) {
+                    let _ = parse_batch.take_results();
+                    gui.integrated_dialog_message("Processing was cancelled, so no output was written.");
+                    gui.end_wait();
+                    continue;
+                }//end if user cancelled while files were being read
+                let file_results = parse_batch.take_results();
                 let mut data_files: Vec<Data> = Vec::new();
-                for (i,input_path) in input_paths.iter().enumerate() {
-                    match fs::read_to_string(input_path) {
-                        Err(msg) => gui.integrated_dialog_alert(&format!("There was an error reading from path \"{}\":\n{}",input_path.to_string_lossy(),msg)),
-                        Ok(file_contents) => {
-                            let filename = match input_path.file_name() {
-                                Some(osstr) => osstr.to_string_lossy().into_owned(),
-                                None => "UNKNOWN FILENAME".to_string(),
-                            };
-                            match data::read_data_from_file(&filename, &file_contents, &config_store) {
-                                Err(msg) => {
-                                    if i >= input_paths.len() - 1 {
-                                        gui.integrated_dialog_alert(&format!("There was an issue reading from path \"{}\". The issue was:\n{}",input_path.to_string_lossy(),msg));
-                                    }//end if this is last file
-                                    else {
-                                        if !gui.integrated_dialog_yes_no(&format!("There was an issue reading from path \"{}\". The issue will be displayed below.\n\tDo you want to continue processing?\n\n{}",input_path.to_string_lossy(),msg)) {
-                                            break;} else {continue;}
-                                    }//end else there are a bunch more files
-                                },
-                                Ok((data,errs)) => {
-                                    if errs.len() > 0 {
-                                        if !gui.integrated_dialog_yes_no(&format!("There were issue(s) parsing data from path {}. The issues will be displayed below.\n\tDo you still want to use output from this file?\n\n{}",input_path.to_string_lossy(),errs.join("\n"))) {
-                                            if i >= input_paths.len() - 1 && !gui.integrated_dialog_yes_no(&format!("Since you said you don't want to use the current file, do you want to continue processing?")) {
-                                                break;} else {continue;}
-                                        }//end if user said they don't want to include current, potentially broken file
-                                    }//end if there is at least one error
-                                    data_files.push(data);
-                                },
-                            }//end matching whether we can read data from this file
+                let mut file_issues: Vec<FileIssues> = Vec::new();
+                // problems are collected and asked about all at once, rather than one file at a time
+                for file_result in file_results {
+                    let encoding_issue = file_result.get_encoding_issue();
+                    let input_path = file_result.path;
+                    let filename = file_result.filename;
+                    match file_result.outcome {
+                        FileOutcome::Unreadable(msg) => {
+                            file_issues.push(FileIssues::new(&filename, &input_path, vec![data::ParseIssue::new(&filename, None, data::ParseIssueKind::FileUnreadable{msg})], false));
+                        },
+                        FileOutcome::Failed(issue) => {
+                            file_issues.push(FileIssues::new(&filename, &input_path, encoding_issue.into_iter().chain([issue]).collect(), false));
                         },
-                    }//end matching whether or not we can get a string from the input file
-                }//end looping over each input file to read from
+                        FileOutcome::Parsed(data,errs) => {
+                            if !errs.is_empty() {file_issues.push(FileIssues::new(&filename, &input_path, errs, true));}
+                            data_files.push(data);
+                        },
+                    }//end matching what happened when reading and parsing this file
+                }//end looping over each input file's results, in the order they were given
+                if data_files.is_empty() {
+                    gui.integrated_dialog_alert(&format!("None of the input files could be read, so no output was written:\n{}", format_file_issues(&file_issues)));
+                    gui.end_wait();
+                    continue;
+                }//end if there's nothing to write
+
+                // ask once about every file with problems, unless the review table is about to show them
+                let warned_files: Vec<FileIssues> = file_issues.iter().filter(|file| file.included && !data::get_problems(&file.issues).is_empty()).cloned().collect();
+                if !warned_files.is_empty() && !gui.get_review_data() {
+                    match gui.integrated_dialog_message_choice(&format!("There were issues reading data from {} of {} file(s):\n{}\nDo you still want to use output from these files?", warned_files.len(), data_files.len(), format_file_issues(&warned_files)), vec!["Use All", "Skip These", "Cancel"]) {
+                        Some(0) => {},
+                        Some(1) => {
+                            let warned_paths: Vec<PathBuf> = warned_files.iter().map(|file| file.source_path.clone()).collect();
+                            let included: Vec<bool> = data_files.iter().map(|data| !warned_paths.contains(&data.source_path)).collect();
+                            apply_review_inclusions(&mut data_files, &mut file_issues, &included);
+                            if data_files.is_empty() {
+                                gui.integrated_dialog_message("Every file was skipped, so no output was written.");
+                                gui.end_wait();
+                                continue;
+                            }//end if there's nothing left to write
+                        },
+                        _ => {
+                            gui.integrated_dialog_message("Processing was cancelled, so no output was written.");
+                            gui.end_wait();
+                            continue;
+                        },
+                    }//end matching what the user wants to do with the files that had issues
+                }//end if any files used had issues
+
+                // assign specs first, so the user can see and change them while reviewing
+                spec::assign_spec_names(&mut data_files, &spec_store);
+
+                // let the user pick curves to exclude on their own, without the rest of the review
+                if gui.get_pick_curves() && !data_files.is_empty() && !gui.review_curve_exclusions(&mut data_files) {
+                    gui.end_wait();
+                    continue;
+                }//end if user cancelled processing while picking curves
+
+                // let the user review what was read before writing, unless they cancel
+                if gui.get_review_data() && !data_files.is_empty() && !gui.review_data_files(&mut data_files, &mut file_issues, &config_store.diff_tolerances, &spec_store) {
+                    gui.end_wait();
+                    continue;
+                }//end if user cancelled processing while reviewing data
 
-                let mut wb = get_workbook();
                 let mut wrote_to_output = false;
                 let mut closed_output = false;
-                if let Err(err) = write_output_to_sheet(&mut wb, &data_files, "alveograph-exporter-output") {
-                    gui.integrated_dialog_alert(&format!("There was an issue writing output data to the sheet:\n{}",err));
-                }//end if there was an error writing to the sheet
-                else {wrote_to_output = true;}
-                if let Err(err) = close_workbook(&mut wb, &output_path) {
-                    gui.integrated_dialog_alert(&format!("There was an issue closing the workbook \"{}\". \nIs it open? \n{}", output_path.to_string_lossy(),err));
-                }//end if there was an error closing the workbook
-                else {closed_output = true;}
+                match output_format.delimiter() {
+                    Some(delimiter) => {
+                        if let Err(err) = write_output_to_delimited(&data_files, &output_path, delimiter, &config_store.row_order_preference) {
+                            gui.integrated_dialog_alert(&format!("There was an issue writing output to \"{}\". \nIs it open? \n{}", output_path.to_string_lossy(), err));
+                        }//end if there was an error writing the delimited file
+                        else {wrote_to_output = true; closed_output = true;}
+                        if long_output {
+                            let long_path = get_long_output_path(&output_path);
+                            if let Err(err) = write_long_output_to_delimited(&data_files, &long_path, delimiter) {
+                                gui.integrated_dialog_alert(&format!("There was an issue writing long-format output to \"{}\". \nIs it open? \n{}", long_path.to_string_lossy(), err));
+                                wrote_to_output = false;
+                            }//end if there was an error writing the long delimited file
+                        }//end if we should write long-format output too
+                        if issues_output {
+                            let issues_path = get_issues_output_path(&output_path);
+                            if let Err(err) = write_issues_to_delimited(&file_issues, &issues_path, delimiter) {
+                                gui.integrated_dialog_alert(&format!("There was an issue writing issues to \"{}\". \nIs it open? \n{}", issues_path.to_string_lossy(), err));
+                                wrote_to_output = false;
+                            }//end if there was an error writing the issues delimited file
+                        }//end if we should write issues too
+                    },
+                    None => {
+                        let mut wb = get_workbook();
+                        if let Err(err) = write_output_to_sheet(&mut wb, &data_files, "alveograph-exporter-output", &config_store.row_order_preference, &config_store.diff_tolerances, &spec_store) {
+                            gui.integrated_dialog_alert(&format!("There was an issue writing output data to the sheet:\n{}",err));
+                        }//end if there was an error writing to the sheet
+                        else {wrote_to_output = true;}
+                        if long_output {
+                            if let Err(err) = write_long_output_to_sheet(&mut wb, &data_files, LONG_OUTPUT_SHEET_NAME) {
+                                gui.integrated_dialog_alert(&format!("There was an issue writing long-format output to the sheet:\n{}",err));
+                                wrote_to_output = false;
+                            }//end if there was an error writing to the long sheet
+                        }//end if we should write long-format output too
+                        if issues_output {
+                            if let Err(err) = write_issues_to_sheet(&mut wb, &file_issues, ISSUES_SHEET_NAME) {
+                                gui.integrated_dialog_alert(&format!("There was an issue writing the issues sheet:\n{}",err));
+                                wrote_to_output = false;
+                            }//end if there was an error writing to the issues sheet
+                        }//end if we should write the issues sheet too
+                        if let Err(err) = close_workbook(&mut wb, &output_path) {
+                            gui.integrated_dialog_alert(&format!("There was an issue closing the workbook \"{}\". \nIs it open? \n{}", output_path.to_string_lossy(),err));
+                        }//end if there was an error closing the workbook
+                        else {closed_output = true;}
+                    },
+                }//end matching whether we're writing a delimited file or a workbook
+
+                // save results to the database as well, if the user wants
+                let mut database_msg = String::new();
+                let mut saved_to_database = true;
+                if gui.get_database_output() {
+                    if config_store.database_path.is_empty() {
+                        if let Some(db_path) = gui.get_database_path_from_user() {
+                            config_store.database_path = db_path.to_string_lossy().to_string();
+                            let _ = gui.set_config_store(&config_store);
+                        }//end if user chose a database path
+                    }//end if we don't know where the database is yet
+                    if config_store.database_path.is_empty() {
+                        gui.integrated_dialog_alert("No database file was chosen, so results weren't saved to a database.");
+                    } else {
+                        match database::write_output_to_database(&PathBuf::from(&config_store.database_path), &data_files, &config_store) {
+                            Ok((inserted, skipped)) => database_msg = format!(" Saved {} test(s) to the database, skipping {} already saved.", inserted, skipped),
+                            Err(msg) => {
+                                // told to the user with the rest of the results, so it isn't missed
+                                database_msg = format!("\nHowever, there was an issue saving results to the database \"{}\":\n{}\n", config_store.database_path, msg);
+                                saved_to_database = false;
+                            },
+                        }//end matching whether we could save to the database
+                    }//end else we have a database to save to
+                }//end if user wants results saved to a database
+
+                // count how many tests had a diff outside tolerance
+                let mut tolerance_msg = String::new();
+                if !config_store.diff_tolerances.is_empty() {
+                    let flagged_tests = data_files.iter().filter(|data| !data.get_flagged_diffs(&config_store.diff_tolerances).is_empty()).count();
+                    tolerance_msg = format!(" {} of {} test(s) had a Diff outside tolerance.", flagged_tests, data_files.len());
+                }//end if there are tolerances to check
+
+                // count how many graded tests passed their spec
+                let mut spec_msg = String::new();
+                let graded_results: Vec<bool> = data_files.iter()
+                    .filter_map(|data| spec_store.get_profile(&data.spec_name).map(|profile| profile.get_failed_headers(&spec::get_graded_rows(data)).is_empty()))
+                    .collect();
+                if !graded_results.is_empty() {
+                    let passed = graded_results.iter().filter(|passed| **passed).count();
+                    spec_msg = format!(" {} of {} graded test(s) passed their spec.", passed, graded_results.len());
+                }//end if any tests were graded
+
+                // mention any files with problems, which weren't asked about while reading
+                let mut issues_msg = String::new();
+                let problem_files: Vec<&FileIssues> = file_issues.iter().filter(|file| !data::get_problems(&file.issues).is_empty()).collect();
+                if !problem_files.is_empty() {
+                    let left_out = problem_files.iter().filter(|file| !file.included).count();
+                    let where_listed = if issues_output {"They're listed in the issues output."} else {"Check \"Issues sheet\" to have them written with the output."};
+                    issues_msg = format!("\n{} file(s) had issues, and {} of them were left out. {}\n{}\n", problem_files.len(), left_out, where_listed, format_file_issues(&file_issues));
+                }//end if any files had issues
+
+                // mention any files that needed a different encoding to be read
+                let mut encoding_msg = String::new();
+                let encoding_notes: Vec<String> = file_issues.iter().flat_map(|file| file.issues.iter().filter(|issue| issue.is_note()).map(|issue| issue.to_string())).collect();
+                if !encoding_notes.is_empty() {
+                    encoding_msg = format!("\n{}\n", encoding_notes.join("\n"));
+                }//end if any files weren't plain UTF-8
 
                 // perform cleanup after finishing processing
                 gui.clear_last_input_paths();
                 gui.clear_last_output_path();
+                update_parse_preview(&mut gui);
                 if wrote_to_output && closed_output {
                     eprintln!("Finished processing file(s).");
                     let total_duration = start.elapsed();
-                    if gui.integrated_dialog_yes_no(&format!("Processing has completed successfully in {} miliseconds. Would you like to open the folder where the output file is located?", format_milliseconds(total_duration))) {
+                    let outcome = if saved_to_database {"completed successfully"} else {"written the output file"};
+                    if gui.integrated_dialog_yes_no(&format!("Processing has {} in {} miliseconds.{}{}{}{}{} Would you like to open the folder where the output file is located?", outcome, format_milliseconds(total_duration), database_msg, tolerance_msg, spec_msg, issues_msg, encoding_msg)) {
                         opener::reveal(output_path).unwrap_or_else(|e| eprintln!("Couldn't reveal output due to {}", e));
                     }//end if user want to open folder
                 }//end if output file seems to be created ok
@@ -119,6 +516,39 @@ fn main() {
     }//end main application loop
 }//end main function
 
+/// Formats a line for each file in file_issues with a problem, for telling the user about every file at once.
+/// Files which were left out show why, while files which were used show a summary of their issues.
+/// Notes are left out, since they aren't problems.
+fn format_file_issues(file_issues: &Vec<FileIssues>) -> String {
+    file_issues.iter()
+        .map(|file| (file, data::get_problems(&file.issues)))
+        .filter(|(_, problems)| !problems.is_empty())
+        .map(|(file, problems)| match file.included {
+            true => format!("{}: {}", file.source_file, data::summarize_issues(&problems)),
+            false => format!("{} (left out): {}", file.source_file, data::format_issues(&problems).replace('\n', "; ")),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}//end format_file_issues()
+
+/// Reads the spec profiles saved next to the config file.  
+/// If there isn't a specs file, an empty SpecStore is returned. If there is one
+/// but it can't be read, the user is told why, and an empty SpecStore is returned.
+fn load_spec_store(gui: &mut GUI) -> SpecStore {
+    match config_store::try_read_config_path(spec::SPEC_FILE_NAME, false) {
+        Ok(spec_path) if spec_path.exists() => {
+            match spec::try_read_specs(&spec_path) {
+                Ok(spec_store) => spec_store,
+                Err(msg) => {
+                    gui.integrated_dialog_alert(&format!("I found a specs file at \"{}\", but I couldn't read it, so no tests will be graded against a spec. Here's the error message:\n{}", spec_path.to_string_lossy(), msg));
+                    SpecStore::default()
+                },
+            }//end matching whether we could read the specs file
+        },
+        _ => SpecStore::default(),
+    }//end matching whether there's a specs file
+}//end load_spec_store()
+
 /// Given a duration, gives a string of a float representation of the number
 /// of milliseconds. If the parse fails, it will return the whole
 /// number of milliseconds as a string.
@@ -140,15 +570,24 @@ fn validate_input_paths(input_paths: &Vec<PathBuf>, gui: &mut GUI) -> bool {
 }//end validate_input_paths()
 
 /// Returns true if the output_path given is valid for processing.  
+/// The extension of the path is changed to match output_format if needed.  
 /// If invalid, shows dialog message about issue.
-fn validate_output_path(output_path: Option<PathBuf>, gui: &mut GUI) -> Result<PathBuf,()> {
+fn validate_output_path(output_path: Option<PathBuf>, output_format: OutputFormat, gui: &mut GUI) -> Result<PathBuf,()> {
     let output_txt = gui.get_output_path_text();
     if output_txt.len() == 0 {
         gui.integrated_dialog_alert("No output path selected. Please select one before processing.");
         return Err(());
     }//end if no selected file OR user deleted selection
     else if output_path.is_some() {
-        return Ok(output_path.expect("Already checked that output_path is_some()"));
+        let mut output_path = output_path.expect("Already checked that output_path is_some()");
+        if OutputFormat::from_path(&output_path) != Some(output_format) {
+            output_path.set_extension(output_format.extension());
+            // the file chooser only confirmed overwriting the path with the old extension
+            if output_path.exists() && !gui.integrated_dialog_yes_no(&format!("The output file \"{}\" already exists. Are you sure you want to overwrite it?", output_path.to_string_lossy())) {
+                return Err(());
+            }//end if the user doesn't want to overwrite the file with the new extension
+        }//end if the extension doesn't match the output format
+        return Ok(output_path);
     }//end else case that both txt and path are valid, all seems good
     else {
         let input_paths = gui.get_last_input_paths();
@@ -163,7 +602,7 @@ fn validate_output_path(output_path: Option<PathBuf>, gui: &mut GUI) -> Result<P
             let mut output_pathbuf = PathBuf::new();
             output_pathbuf.push(input_dir);
             output_pathbuf.push(output_txt);
-            output_pathbuf.set_extension("xlsx");
+            output_pathbuf.set_extension(output_format.extension());
             if !output_pathbuf.exists() || gui.integrated_dialog_yes_no("The output file you specified already exists. Are you sure you want to overwrite it?") {
                 return Ok(output_pathbuf);
             } else {return Err(());}
@@ -174,21 +613,24 @@ fn validate_output_path(output_path: Option<PathBuf>, gui: &mut GUI) -> Result<P
     }//end else case that txt is valid, but path is not, must generate path
 }//end validate_output_path()
 
-/// Gets the config information from the config file.
+/// Gets the config profiles from the config file.
 /// If we encounter issues with that, lets the user know through the gui.
 fn ensure_config_valid(
     gui: &mut GUI,
-    config_store: &mut ConfigStore,
+    config_profiles: &mut ConfigProfiles,
     config_path: &mut Option<PathBuf>,
+    config_location: &mut Option<ConfigLocation>,
     config_name: &str
 ) {
-    *config_store = ConfigStore::default();
+    *config_profiles = ConfigProfiles::default();
     *config_path = None;
+    *config_location = None;
 
-    match config_store::try_read_config_path(config_name, false) {
-        Ok(config_path_tmp) => {
+    match config_store::find_config_path(config_name, None) {
+        Ok((config_path_tmp, location)) => {
+            *config_location = Some(location);
             if !config_path_tmp.exists() {
-                match config_store::try_write_config(&config_path_tmp, &config_store) {
+                match config_store::try_write_profiles(&config_path_tmp, &config_profiles) {
                     Ok(_) => {
                         *config_path = Some(config_path_tmp);
                     },
@@ -196,19 +638,112 @@ fn ensure_config_valid(
                 }//end matching whether we can write the default config
             }//end if the config file does not already exist
             else {
-                match config_store::try_read_config(&config_path_tmp) {
-                    Ok(config_store_tmp) => *config_store = config_store_tmp,
+                let file_version = config_store::try_read_config_version(&config_path_tmp);
+                if file_version.as_ref().is_ok_and(|version| *version > config_store::CONFIG_VERSION) {
+                    // writing over a file from a newer version would lose whatever that version added
+                    if let Ok(config_profiles_tmp) = config_store::try_read_profiles(&config_path_tmp) {
+                        *config_profiles = config_profiles_tmp;
+                        config_profiles.version = config_store::CONFIG_VERSION;
+                    }//end if we can read what we understand of the newer file
+                    gui.integrated_dialog_alert(&format!("Your config file at \"{}\" is from a newer version of the program. I'll use what settings I can, but I won't save any changes to them, so that the newer file isn't overwritten.", config_path_tmp.to_string_lossy()));
+                    return;
+                }//end if the config file is from a newer version
+                match config_store::try_read_profiles(&config_path_tmp) {
+                    Ok(config_profiles_tmp) => {
+                        *config_profiles = config_profiles_tmp;
+                        if file_version == Ok(config_store::CONFIG_VERSION) {*config_path = Some(config_path_tmp);}
+                        else {
+                            // keep a copy of the file from the other version before saving over it
+                            match config_store::try_backup_config(&config_path_tmp) {
+                                Ok(_) => {
+                                    if let Err(msg) = config_store::try_write_profiles(&config_path_tmp, config_profiles) {
+                                        gui.integrated_dialog_alert(&format!("I updated your config file from an older version, but I couldn't save the update. Error message below:\n{}", msg));
+                                    }//end if we couldn't write the updated config
+                                    *config_path = Some(config_path_tmp);
+                                },
+                                Err(msg) => gui.integrated_dialog_alert(&format!("Your config file is from a different version of the program, and I couldn't make a backup copy of it before updating it. You can still use your settings, but I won't save any changes to them, so that your file isn't lost. Error message below:\n{}", msg)),
+                            }//end matching whether we could back up the config file
+                        }//end else the config file came from a different version
+                    },
                     Err(msg) => {
-                        gui.integrated_dialog_alert(&format!("I found a config file, but I couldn't read it. Things like this can happen during version changes or if the file is edited incorrectly. I'm going to go ahead and create a new file with the default settings for you. Here's the error message:\n{}",msg));
-                        match config_store::try_write_config(&config_path_tmp, config_store) {
-                            Ok(_) => {},
-                            Err(msg) => gui.integrated_dialog_alert(&format!("Ok, so I tried writing a new config file, but I wasn't able to. Was it open? Either way, if you keep seeing messages like this, please contact the developer. You can still use the program with the default config and even edit the settings while you use it, but I can't keep track of those changes after you close the program. Error message below:\n{}", msg)),
-                        }
+                        match config_store::try_backup_config(&config_path_tmp) {
+                            Ok(backup_path) => {
+                                gui.integrated_dialog_alert(&format!("I found a config file, but I couldn't read it. Things like this can happen if the file is edited incorrectly. I've saved a copy of it at \"{}\", and I'm going to go ahead and create a new file with the default settings for you. Here's the error message:\n{}", backup_path.to_string_lossy(), msg));
+                                match config_store::try_write_profiles(&config_path_tmp, config_profiles) {
+                                    Ok(_) => {},
+                                    Err(msg) => gui.integrated_dialog_alert(&format!("Ok, so I tried writing a new config file, but I wasn't able to. Was it open? Either way, if you keep seeing messages like this, please contact the developer. You can still use the program with the default config and even edit the settings while you use it, but I can't keep track of those changes after you close the program. Error message below:\n{}", msg)),
+                                }
+                                *config_path = Some(config_path_tmp);
+                            },
+                            Err(backup_msg) => gui.integrated_dialog_alert(&format!("I found a config file, but I couldn't read it, and I couldn't make a backup copy of it either. You can use the default config, but I won't save any changes to it, so that your file isn't lost. Here are the error messages:\n{}\n{}", msg, backup_msg)),
+                        }//end matching whether we could back up the unreadable config file
                     },
                 }//end matching whether or not we can read from the config file we have
-                *config_path = Some(config_path_tmp);
             }//end else we do have a config file to read
         },
         Err(msg) => gui.integrated_dialog_alert(&format!("Could not determine the path to a config file:\n{}", msg)),
     }//end matching whether or not we can get config path
 }//end ensure_config_valid()
+
+/// Updates the gui to show the profile names and the settings of the profile in use.
+fn show_config_profiles(gui: &mut GUI, config_profiles: &ConfigProfiles) {
+    if let Err(msg) = gui.set_config_store(&config_profiles.current()) {
+        gui.integrated_dialog_alert(&format!("There was an issue showing the config profile!:\n{}", msg));
+    }//end if we couldn't show the config
+    gui.set_profile_names(&config_profiles.names(), &config_profiles.current_name());
+    check_config(gui);
+    update_parse_preview(gui);
+}//end show_config_profiles()
+
+/// Validates the settings of the config shown in the gui, and highlights any problems in the gui.  
+/// The input files aren't read, so this can run on every edit.  
+/// Returns the problems found, which is empty if the config can't be read from the gui.
+fn check_config(gui: &mut GUI) -> Vec<ConfigProblem> {
+    let config = match gui.get_config_store() {
+        Ok(config) => config,
+        Err(_) => return Vec::new(),
+    };
+    let problems = config.validate();
+    gui.highlight_config_problems(&problems);
+    problems
+}//end check_config()
+
+/// Shows how the first input file chosen so far will be read with the config shown in the gui.
+fn update_parse_preview(gui: &mut GUI) {
+    let input_path = match gui.get_last_input_paths().first() {
+        Some(input_path) => input_path.clone(),
+        None => {gui.clear_parse_preview("Select an input file to preview how it will be read."); return;},
+    };
+    let config = match gui.get_config_store() {
+        Ok(config) => config,
+        Err(msg) => {gui.clear_parse_preview(&format!("Couldn't get the config settings:\n{}", msg)); return;},
+    };
+    let filename = input::get_filename(&input_path);
+    match input::read_input_file_with_encoding(&input_path) {
+        Ok(input_file) => {
+            let shown_name = match input_file.encoding {
+                input::TextEncoding::Utf8 => filename.clone(),
+                _ => format!("{} (read as {})", filename, input_file.encoding.to_string()),
+            };
+            gui.show_parse_preview(&shown_name, &data::preview_file(&filename, &input_file.contents, &config));
+        },
+        Err(msg) => gui.clear_parse_preview(&format!("Couldn't read \"{}\":\n{}", input_path.to_string_lossy(), msg)),
+    }//end matching whether we could read the input file
+}//end update_parse_preview()
+
+/// Stores the settings shown in the gui into the profile in use.
+fn store_gui_config(gui: &mut GUI, config_profiles: &mut ConfigProfiles) {
+    match gui.get_config_store() {
+        Ok(config) => config_profiles.set_current(config),
+        Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't get the config settings, so changes to the profile \"{}\" won't be saved:\n{}", config_profiles.current_name(), msg)),
+    }//end matching whether or not we can get the config store
+}//end store_gui_config()
+
+/// Saves every config profile to the config file, if we have a path for it.
+fn save_config_profiles(gui: &mut GUI, config_profiles: &ConfigProfiles, config_path: &Option<PathBuf>) {
+    if let Some(config_path) = config_path {
+        if let Err(msg) = config_store::try_write_profiles(config_path, config_profiles) {
+            gui.integrated_dialog_alert(&format!("We weren't able to save the config file. Error message is:\n{}", msg));
+        }//end if writing is not successful
+    }//end if we have valid config_path
+}//end save_config_profiles()
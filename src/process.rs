@@ -1,6 +1,6 @@
-use std::path::PathBuf;
-use rust_xlsxwriter::{Format, FormatAlign, FormatBorder, Workbook, XlsxError};
-use crate::data::Data;
+use std::{fs, path::{Path, PathBuf}};
+use rust_xlsxwriter::{utility::row_col_to_cell, Color, ConditionalFormatFormula, Format, FormatAlign, FormatBorder, Workbook, Worksheet, XlsxError};
+use crate::{config_store::{DiffTolerance, ToleranceMode}, data::{Curve, Data, IssueSeverity, ParseIssue, Row}, spec::{self, SpecProfile, SpecStore}};
 
 /// The border style to use for all the cells we write to.
 const BORDER_FORMAT: FormatBorder = FormatBorder::Thin;
@@ -14,6 +14,153 @@ const FONT_SIZE_DATA: i32 = 11;
 /// This acts as a vertical offset for the whole output.
 const HEADER_START_ROW: u32 = 2;
 
+/// The block label used for the average rows read from a file in long-format output.
+pub const AVERAGE_BLOCK_LABEL: &str = "Average";
+/// The text added to the end of the filename when writing long-format output
+/// to a separate delimited file.
+pub const LONG_OUTPUT_SUFFIX: &str = "-long";
+
+/// The text added to the end of the label of an excluded curve in output.
+pub const EXCLUDED_CURVE_SUFFIX: &str = " (excluded)";
+
+/// The name of the sheet that long-format output is written to in a workbook.
+pub const LONG_OUTPUT_SHEET_NAME: &str = "long-format";
+
+/// Represents a single value in long-format output, along with
+/// everything needed to tell where it came from.
+#[derive(Clone,PartialEq,PartialOrd,Debug,Default)]
+pub struct LongRecord {
+    pub test_name: String,
+    pub source_file: String,
+    /// The block the value was read from, such as "Curve1" or "Average".
+    pub block: String,
+    pub header: String,
+    pub value: f64,
+    /// The value exactly as it was written in the file, from Row::raw_text.
+    pub raw_text: String,
+}//end struct LongRecord
+
+/// The column headers used in long-format output.
+const LONG_OUTPUT_HEADERS: [&str; 6] = ["Test Name", "Source File", "Block", "Parameter", "Value", "Raw Text"];
+
+/// The name of the sheet that issues are written to in a workbook.
+pub const ISSUES_SHEET_NAME: &str = "Issues";
+/// The text added to the end of the filename when writing issues
+/// to a separate delimited file.
+pub const ISSUES_OUTPUT_SUFFIX: &str = "-issues";
+
+/// The column headers used in the issues sheet.
+const ISSUES_OUTPUT_HEADERS: [&str; 6] = ["File", "Line", "Severity", "Kind", "Message", "Included"];
+
+/// The issues found while reading a single input file, along with
+/// whether the file ended up being used in the output.
+#[derive(Clone,PartialEq,Debug)]
+pub struct FileIssues {
+    pub source_file: String,
+    /// The full path of the file, which tells apart files with the same name in different folders.
+    pub source_path: PathBuf,
+    pub issues: Vec<ParseIssue>,
+    /// Whether the data from this file was included in the output.
+    pub included: bool,
+}//end struct FileIssues
+
+impl FileIssues {
+    /// Creates a new FileIssues for the given file.
+    pub fn new(source_file: &str, source_path: &Path, issues: Vec<ParseIssue>, included: bool) -> FileIssues {
+        FileIssues{source_file: source_file.to_string(), source_path: source_path.to_path_buf(), issues, included}
+    }
+}//end impl FileIssues
+
+/// Represents a single line of the issues sheet.
+#[derive(Clone,PartialEq,Debug)]
+pub struct IssueRecord {
+    pub source_file: String,
+    /// The 1-based line number the issue was on, if it was about a particular line.
+    pub line_number: Option<usize>,
+    pub severity: IssueSeverity,
+    /// The category of the issue, from ParseIssueKind::category().
+    pub kind: String,
+    pub message: String,
+    pub included: bool,
+}//end struct IssueRecord
+
+/// Represents a single line of the review table shown before output is written,
+/// summarizing one data file.
+#[derive(Clone,PartialEq,Debug)]
+pub struct ReviewRecord {
+    pub source_file: String,
+    pub test_name: String,
+    /// The label of each curve, as written in output.
+    pub curves: Vec<String>,
+    /// The average rows read from the file, such as "P 85".
+    pub averages: Vec<String>,
+    /// The rows from Data::calc_diff(), with any outside tolerance marked by DIFF_FLAG.
+    pub diffs: Vec<String>,
+    /// The message of each issue found while reading the file, leaving out notes.
+    pub warnings: Vec<String>,
+    /// The name of the spec profile the file is graded against, or empty if there isn't one.
+    pub spec_name: String,
+}//end struct ReviewRecord
+
+/// The text added to the end of a diff in a ReviewRecord when it's outside its tolerance.
+pub const DIFF_FLAG: &str = " (!)";
+
+/// An enum to represent the different kinds of output file we can write.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+pub enum OutputFormat {
+    /// An excel workbook, written with write_output_to_sheet().
+    Xlsx,
+    /// A comma-separated file, written with write_output_to_delimited().
+    Csv,
+    /// A tab-separated file, written with write_output_to_delimited().
+    Tsv,
+}//end enum OutputFormat
+
+impl OutputFormat {
+    /// Returns string representation of variant.
+    pub fn to_string(&self) -> String {
+        match self {
+            OutputFormat::Xlsx => "xlsx".to_string(),
+            OutputFormat::Csv => "csv".to_string(),
+            OutputFormat::Tsv => "tsv".to_string(),
+        }//end matching self
+    }//end to_string()
+
+    /// Attempts to match label to variant, ignoring case.
+    pub fn from_str(str: &str) -> Option<OutputFormat> {
+        match str.to_lowercase().as_str() {
+            "xlsx" => Some(OutputFormat::Xlsx),
+            "csv" => Some(OutputFormat::Csv),
+            "tsv" => Some(OutputFormat::Tsv),
+            _ => None,
+        }//end matching str
+    }//end from_str()
+
+    /// Attempts to match the extension of a path to a variant.
+    pub fn from_path(path: &PathBuf) -> Option<OutputFormat> {
+        path.extension().and_then(|ext| OutputFormat::from_str(&ext.to_string_lossy()))
+    }//end from_path()
+
+    /// Returns the file extension to use for this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Xlsx => "xlsx",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Tsv => "tsv",
+        }//end matching self
+    }//end extension()
+
+    /// Returns the delimiter between fields for delimited formats,
+    /// or None for formats which aren't delimited.
+    pub fn delimiter(&self) -> Option<char> {
+        match self {
+            OutputFormat::Xlsx => None,
+            OutputFormat::Csv => Some(','),
+            OutputFormat::Tsv => Some('\t'),
+        }//end matching self
+    }//end delimiter()
+}//end impl for OutputFormat
+
 /// Creates an excel workbook, which can then be used in
 /// further funtions.
 pub fn get_workbook() -> Workbook {
@@ -26,10 +173,120 @@ pub fn close_workbook(workbook: &mut Workbook, output_path: &PathBuf) -> Result<
     Ok(())
 }//end close_workbook(workbook)
 
+/// Gets the label to write for a curve in output, which is marked
+/// with EXCLUDED_CURVE_SUFFIX if the curve is excluded.
+pub fn get_curve_output_label(curve: &Curve) -> String {
+    if curve.excluded {format!("{}{}", curve.label, EXCLUDED_CURVE_SUFFIX)}
+    else {curve.label.clone()}
+}//end get_curve_output_label()
+
+/// Gets the rows of statistics calculated across the curves of data_file,
+/// each with the label to write next to it in output.
+pub fn get_curve_stat_rows(data_file: &Data) -> Vec<(&'static str, Vec<Row>)> {
+    vec![
+        ("SD", data_file.calc_std_dev()),
+        ("CV%", data_file.calc_cv()),
+        ("Min", data_file.calc_min()),
+        ("Max", data_file.calc_max()),
+        ("Range", data_file.calc_range()),
+    ]
+}//end get_curve_stat_rows()
+
+/// Gets the list of row headers to use for columns in output, in order.  
+/// This is every header found in the average or curve rows of each Data.
+/// Headers in row_order_preference come first, in that order, followed by
+/// any others in the order they were first found.
+pub fn get_column_headers(data: &Vec<Data>, row_order_preference: &[String]) -> Vec<String> {
+    let mut found_headers: Vec<String> = Vec::new();
+    for data_file in data {
+        let curve_rows = data_file.curves.iter().flat_map(|curve| curve.row_data.iter());
+        for row in data_file.row_data.iter().chain(curve_rows) {
+            if !found_headers.contains(&row.header) {found_headers.push(row.header.clone());}
+        }//end looking at each row header in this data file
+    }//end looping over each data file
+    let mut headers: Vec<String> = Vec::new();
+    for header in row_order_preference {
+        if found_headers.contains(header) && !headers.contains(header) {headers.push(header.clone());}
+    }//end adding each preferred header that was found
+    for header in found_headers {
+        if !headers.contains(&header) {headers.push(header);}
+    }//end adding the rest of the headers
+    return headers;
+}//end get_column_headers()
+
+/// Writes the values of each row to sheet in the given row_num.  
+/// Each value is placed in the column matching its header in column_headers,
+/// offset by col_start. Rows with headers not in column_headers are skipped.
+fn write_rows_to_sheet(sheet: &mut Worksheet, row_num: u32, col_start: u16, rows: &Vec<Row>, column_headers: &Vec<String>, format: &Format) -> Result<(),XlsxError> {
+    for row in rows {
+        if let Some(col_offset) = column_headers.iter().position(|header| header.eq(&row.header)) {
+            sheet.write_number_with_format(row_num, col_start + col_offset as u16, row.value, format)?;
+        }//end if we know which column to put this row in
+    }//end looping over each row of data to place in a column
+    Ok(())
+}//end write_rows_to_sheet()
+
+/// Highlights each Diff cell in diff_row that is outside its tolerance, using conditional formatting.  
+/// The rule compares against the AVG cell in file_avg_row, so it stays correct if
+/// values are changed in the workbook later.
+fn add_tolerance_formats_to_sheet(sheet: &mut Worksheet, diff_row: u32, file_avg_row: u32, col_start: u16, column_headers: &Vec<String>, tolerances: &[DiffTolerance], format: &Format) -> Result<(),XlsxError> {
+    for (col_offset, header) in column_headers.iter().enumerate() {
+        let tolerance = match tolerances.iter().find(|tolerance| tolerance.header.eq(header)) {
+            Some(tolerance) => tolerance,
+            None => continue,
+        };
+        let col = col_start + col_offset as u16;
+        let diff_cell = row_col_to_cell(diff_row, col);
+        let rule = match tolerance.mode {
+            ToleranceMode::Absolute => format!("=ABS({})>{}", diff_cell, tolerance.limit.abs()),
+            ToleranceMode::Relative => format!("=ABS({})>ABS({})*{}/100", diff_cell, row_col_to_cell(file_avg_row, col), tolerance.limit.abs()),
+        };
+        let conditional_format = ConditionalFormatFormula::new()
+            .set_rule(rule.as_str())
+            .set_format(format);
+        sheet.add_conditional_format(diff_row, col, diff_row, col, &conditional_format)?;
+    }//end adding a rule for each column with a tolerance
+    Ok(())
+}//end add_tolerance_formats_to_sheet()
+
+/// Gets the result of grading rows against a spec profile, for use in output.  
+/// This is "Pass" if every row is within its limits, or "Fail" followed by the failed headers.
+pub fn get_spec_result_text(profile: &SpecProfile, rows: &Vec<Row>) -> String {
+    let failed_headers = profile.get_failed_headers(rows);
+    if failed_headers.is_empty() {"Pass".to_string()}
+    else {format!("Fail: {}", failed_headers.join(", "))}
+}//end get_spec_result_text()
+
+/// A spec profile to grade values against, along with the formats
+/// for values which pass or fail it.
+struct SpecColors<'a> {
+    profile: &'a SpecProfile,
+    pass_format: &'a Format,
+    fail_format: &'a Format,
+}//end struct SpecColors
+
+/// Re-writes each value in rows which has a limit in the profile of spec_colors, colored by
+/// whether it passed or failed that limit. Values without a limit are left as they are.
+fn write_spec_colors_to_sheet(sheet: &mut Worksheet, row_num: u32, col_start: u16, rows: &Vec<Row>, column_headers: &Vec<String>, spec_colors: &SpecColors) -> Result<(),XlsxError> {
+    for row in rows {
+        let limit = spec_colors.profile.limits.iter().find(|limit| limit.header.eq(&row.header));
+        let col_offset = column_headers.iter().position(|header| header.eq(&row.header));
+        if let (Some(limit), Some(col_offset)) = (limit, col_offset) {
+            let format = if limit.passes(row.value) {spec_colors.pass_format} else {spec_colors.fail_format};
+            sheet.write_number_with_format(row_num, col_start + col_offset as u16, row.value, format)?;
+        }//end if this row has a limit and a column
+    }//end looping over each row to color
+    Ok(())
+}//end write_spec_colors_to_sheet()
+
 /// Writes output from another function to a workbook that has already
 /// been created. After you're done calling this function (however many times),  
-/// make sure to call process::close_workbook().
-pub fn write_output_to_sheet(workbook: &mut Workbook, data: &Vec<Data>, sheet_name: &str) -> Result<(),XlsxError> {
+/// make sure to call process::close_workbook().  
+/// Diff cells outside their tolerance in tolerances are highlighted.  
+/// If any test has a spec_name, Spec and Spec Result columns are added, and the
+/// graded values of those tests are colored by whether they pass the spec in specs.  
+/// The columns are ordered by get_column_headers() with row_order_preference.
+pub fn write_output_to_sheet(workbook: &mut Workbook, data: &Vec<Data>, sheet_name: &str, row_order_preference: &[String], tolerances: &[DiffTolerance], specs: &SpecStore) -> Result<(),XlsxError> {
     let sheet = workbook.add_worksheet();//workbook.create_sheet(sheet_name);
     sheet.set_name(sheet_name)?;
     if data.len() < 1 {return Ok(());}
@@ -42,10 +299,18 @@ pub fn write_output_to_sheet(workbook: &mut Workbook, data: &Vec<Data>, sheet_na
         .set_font_size(FONT_SIZE_HEADER);
     sheet.write_with_format(HEADER_START_ROW,0,"Test Name", &bold)?;
     sheet.write_with_format(HEADER_START_ROW,1,"Curve", &bold)?;
-    for (index, row) in data.first().expect("already checked").row_data.iter().enumerate() {
+    let column_headers = get_column_headers(data, row_order_preference);
+    for (index, header) in column_headers.iter().enumerate() {
         let index = index as u16;
-        sheet.write_with_format(HEADER_START_ROW,index + 2, row.header.clone(),&bold)?;
+        sheet.write_with_format(HEADER_START_ROW,index + 2, header.clone(),&bold)?;
     }//end writing each row header
+    // spec columns go after the row header columns
+    let spec_col = column_headers.len() as u16 + 2;
+    let write_specs = data.iter().any(|data_file| !data_file.spec_name.is_empty());
+    if write_specs {
+        sheet.write_with_format(HEADER_START_ROW,spec_col,"Spec", &bold)?;
+        sheet.write_with_format(HEADER_START_ROW,spec_col + 1,"Spec Result", &bold)?;
+    }//end if we need spec columns
 
     let test_name_format = Format::new()
         .set_align(FormatAlign::Center)
@@ -55,63 +320,357 @@ pub fn write_output_to_sheet(workbook: &mut Workbook, data: &Vec<Data>, sheet_na
         .set_align(FormatAlign::Center)
         .set_border(BORDER_FORMAT)
         .set_font_size(FONT_SIZE_DATA);
+    // excluded curves are struck through and grayed out, so it's clear they weren't used
+    let excluded_label_format = test_name_format.clone()
+        .set_font_strikethrough()
+        .set_font_color(Color::Gray);
+    let excluded_format = default_format.clone()
+        .set_font_strikethrough()
+        .set_font_color(Color::Gray);
+    let out_of_tolerance_format = Format::new()
+        .set_font_color(Color::RGB(0x9C0006))
+        .set_background_color(Color::RGB(0xFFC7CE));
+    let spec_pass_format = default_format.clone()
+        .set_font_color(Color::RGB(0x006100))
+        .set_background_color(Color::RGB(0xC6EFCE));
+    let spec_fail_format = default_format.clone()
+        .set_font_color(Color::RGB(0x9C0006))
+        .set_background_color(Color::RGB(0xFFC7CE));
     let mut row_num = HEADER_START_ROW + 1;
     for data_file in data {
         sheet.write_with_format(row_num,0,data_file.test_name.clone(), &test_name_format)?;
-        sheet.write_with_format(row_num,1,"1".to_string(), &test_name_format)?;
-        sheet.write_with_format(row_num+1,1,"2".to_string(), &test_name_format)?;
-        sheet.write_with_format(row_num+2,1,"3".to_string(), &test_name_format)?;
-        sheet.write_with_format(row_num+3,1,"4".to_string(), &test_name_format)?;
-        sheet.write_with_format(row_num+4,1,"5".to_string(), &test_name_format)?;
-		sheet.write_with_format(row_num+5,1,"calcAVG".to_string(), &test_name_format)?;
-		sheet.write_with_format(row_num+7,1,"AVG".to_string(), &test_name_format)?;
-		sheet.write_with_format(row_num+8,1,"Diff".to_string(), &test_name_format)?;
-
-
-		for (col_offset,row) in data_file.curve_data1.iter().enumerate() {
-            let col_offset = col_offset as u16;
-            sheet.write_number_with_format(row_num,2+col_offset,row.value, &default_format)?;
-        }//end looping over each row of data to place in a column
-		for (col_offset,row) in data_file.curve_data2.iter().enumerate() {
-            let col_offset = col_offset as u16;
-            sheet.write_number_with_format(row_num+1,2+col_offset,row.value, &default_format)?;
-        }//end looping over each row of data to place in a column
-		for (col_offset,row) in data_file.curve_data3.iter().enumerate() {
-            let col_offset = col_offset as u16;
-            sheet.write_number_with_format(row_num+2,2+col_offset,row.value, &default_format)?;
-        }//end looping over each row of data to place in a column
-		for (col_offset,row) in data_file.curve_data4.iter().enumerate() {
-            let col_offset = col_offset as u16;
-            sheet.write_number_with_format(row_num+3,2+col_offset,row.value, &default_format)?;
-        }//end looping over each row of data to place in a column
-		for (col_offset,row) in data_file.curve_data5.iter().enumerate() {
-            let col_offset = col_offset as u16;
-            sheet.write_number_with_format(row_num+4,2+col_offset,row.value, &default_format)?;
-        }//end looping over each row of data to place in a column
-		for (col_offset,row) in data_file.row_data.iter().enumerate() {
-            let col_offset = col_offset as u16;
-            sheet.write_number_with_format(row_num+7,2+col_offset,row.value, &default_format)?;
-        }//end looping over each row of data to place in a column
-        for (index, _) in data_file.row_data.iter().enumerate() {
-            let curve1 = data_file.curve_data1.get(index).unwrap();
-            let curve2 = data_file.curve_data2.get(index).unwrap();
-            let curve3 = data_file.curve_data3.get(index).unwrap();
-            let curve4 = data_file.curve_data4.get(index).unwrap();
-            let curve5 = data_file.curve_data5.get(index).unwrap();
-            let avg = (curve1.value+curve2.value+curve3.value+curve4.value+curve5.value)/5.0;
-            let fileavg = data_file.row_data.get(index).unwrap();
-            let diff = avg-fileavg.value;
-            let index = index as u16;
-            sheet.write_number_with_format(row_num+5, 2+index, avg, &default_format)?;
-            sheet.write_number_with_format(row_num+8, 2+index, diff, &default_format)?;
-
-
-        }
-
-        row_num += 10;
+        for (curve_offset, curve) in data_file.curves.iter().enumerate() {
+            let curve_row = row_num + curve_offset as u32;
+            let (label_format, row_format) = match curve.excluded {
+                true => (&excluded_label_format, &excluded_format),
+                false => (&test_name_format, &default_format),
+            };
+            sheet.write_with_format(curve_row,1,get_curve_output_label(curve), label_format)?;
+            write_rows_to_sheet(sheet, curve_row, 2, &curve.row_data, &column_headers, row_format)?;
+        }//end writing each curve to its own row
+        // rows for calculated and file averages go under the curves
+        let calc_avg_row = row_num + data_file.curves.len() as u32;
+        let file_avg_row = calc_avg_row + 2;
+        let diff_row = calc_avg_row + 3;
+        sheet.write_with_format(calc_avg_row,1,"calcAVG".to_string(), &test_name_format)?;
+        sheet.write_with_format(file_avg_row,1,"AVG".to_string(), &test_name_format)?;
+        sheet.write_with_format(diff_row,1,"Diff".to_string(), &test_name_format)?;
+        write_rows_to_sheet(sheet, calc_avg_row, 2, &data_file.calc_avg(), &column_headers, &default_format)?;
+        write_rows_to_sheet(sheet, file_avg_row, 2, &data_file.row_data, &column_headers, &default_format)?;
+        write_rows_to_sheet(sheet, diff_row, 2, &data_file.calc_diff(), &column_headers, &default_format)?;
+        add_tolerance_formats_to_sheet(sheet, diff_row, file_avg_row, 2, &column_headers, tolerances, &out_of_tolerance_format)?;
+        // the spec result goes next to the row it grades
+        if !data_file.spec_name.is_empty() {
+            let graded_rows = spec::get_graded_rows(data_file);
+            let graded_row = if data_file.calc_avg().is_empty() {file_avg_row} else {calc_avg_row};
+            sheet.write_with_format(graded_row,spec_col,data_file.spec_name.clone(), &test_name_format)?;
+            match specs.get_profile(&data_file.spec_name) {
+                Some(profile) => {
+                    let result = get_spec_result_text(profile, &graded_rows);
+                    let result_format = if profile.get_failed_headers(&graded_rows).is_empty() {&spec_pass_format} else {&spec_fail_format};
+                    sheet.write_with_format(graded_row,spec_col + 1,result, result_format)?;
+                    let spec_colors = SpecColors{profile, pass_format: &spec_pass_format, fail_format: &spec_fail_format};
+                    write_spec_colors_to_sheet(sheet, graded_row, 2, &graded_rows, &column_headers, &spec_colors)?;
+                },
+                None => {sheet.write_with_format(graded_row,spec_col + 1,"Unknown spec", &test_name_format)?;},
+            }//end matching whether we can find the spec
+        }//end if this test has a spec
+        // statistics across the curves go under the averages
+        let mut stat_row = diff_row + 1;
+        for (label, rows) in get_curve_stat_rows(data_file) {
+            stat_row += 1;
+            sheet.write_with_format(stat_row,1,label.to_string(), &test_name_format)?;
+            write_rows_to_sheet(sheet, stat_row, 2, &rows, &column_headers, &default_format)?;
+        }//end writing each row of statistics
+
+        row_num = stat_row + 2;
     }//end looping over each data file
 
     sheet.set_column_width(0, 14.5)?;
+    if write_specs {sheet.set_column_width(spec_col + 1, 16)?;}
 
     Ok(())
 }//end write_output_to_sheet()
+
+/// Gets one IssueRecord for every issue in file_issues, in the order the files were read.
+pub fn get_issue_records(file_issues: &Vec<FileIssues>) -> Vec<IssueRecord> {
+    let mut records = Vec::new();
+    for file in file_issues {
+        for issue in file.issues.iter() {
+            records.push(IssueRecord {
+                source_file: file.source_file.clone(),
+                line_number: issue.line_idx.map(|line_idx| line_idx + 1),
+                severity: issue.severity(),
+                kind: issue.kind.category().to_string(),
+                message: issue.kind.to_string(),
+                included: file.included,
+            });
+        }//end adding a record for each issue in the file
+    }//end looping over each file's issues
+    return records;
+}//end get_issue_records()
+
+/// Gets one ReviewRecord for each file in data, in the same order,
+/// taking the warnings for each file from file_issues, matched by source_path.
+pub fn get_review_records(data: &Vec<Data>, file_issues: &Vec<FileIssues>, tolerances: &[DiffTolerance]) -> Vec<ReviewRecord> {
+    let format_rows = |rows: &Vec<Row>| rows.iter().map(|row| format!("{} {}", row.header, row.value)).collect::<Vec<String>>();
+    let mut records = Vec::new();
+    for data_file in data {
+        let flagged = data_file.get_flagged_diffs(tolerances);
+        let diffs = data_file.calc_diff().iter()
+            .map(|row| format!("{} {:.2}{}", row.header, row.value, if flagged.contains(&row.header) {DIFF_FLAG} else {""}))
+            .collect();
+        let warnings = file_issues.iter()
+            .filter(|file| file.source_path.eq(&data_file.source_path))
+            .flat_map(|file| file.issues.iter().filter(|issue| !issue.is_note()).map(|issue| issue.to_string()))
+            .collect();
+        records.push(ReviewRecord {
+            source_file: data_file.source_file.clone(),
+            test_name: data_file.test_name.clone(),
+            curves: data_file.curves.iter().map(get_curve_output_label).collect(),
+            averages: format_rows(&data_file.row_data),
+            diffs,
+            warnings,
+            spec_name: data_file.spec_name.clone(),
+        });
+    }//end adding a record for each data file
+    return records;
+}//end get_review_records()
+
+/// Removes each file from data which isn't included, going by the index in data,
+/// and marks its issues in file_issues as not included, matched by source_path.
+pub fn apply_review_inclusions(data: &mut Vec<Data>, file_issues: &mut [FileIssues], included: &[bool]) {
+    for (data_file, _) in data.iter().zip(included.iter()).filter(|(_, included)| !**included) {
+        for file in file_issues.iter_mut().filter(|file| file.source_path.eq(&data_file.source_path)) {
+            file.included = false;
+        }//end marking each of the file's issues as not included
+    }//end looping over each excluded file
+    let mut included_iter = included.iter();
+    data.retain(|_| *included_iter.next().unwrap_or(&true));
+}//end apply_review_inclusions()
+
+/// Writes every issue found while reading input files to a new sheet in
+/// a workbook that has already been created, one issue per row.  
+/// Make sure to call process::close_workbook() when finished with the workbook.
+pub fn write_issues_to_sheet(workbook: &mut Workbook, file_issues: &Vec<FileIssues>, sheet_name: &str) -> Result<(),XlsxError> {
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(sheet_name)?;
+    let bold = Format::new()
+        .set_bold()
+        .set_border(BORDER_FORMAT);
+    let error_format = Format::new()
+        .set_font_color(Color::RGB(0x9C0006))
+        .set_background_color(Color::RGB(0xFFC7CE));
+    for (col, header) in ISSUES_OUTPUT_HEADERS.iter().enumerate() {
+        sheet.write_with_format(0, col as u16, *header, &bold)?;
+    }//end writing each column header
+    for (offset, record) in get_issue_records(file_issues).iter().enumerate() {
+        let row_num = offset as u32 + 1;
+        sheet.write(row_num, 0, record.source_file.clone())?;
+        if let Some(line_number) = record.line_number {sheet.write_number(row_num, 1, line_number as f64)?;}
+        match record.severity {
+            IssueSeverity::Error => sheet.write_with_format(row_num, 2, record.severity.to_string(), &error_format)?,
+            IssueSeverity::Warning | IssueSeverity::Note => sheet.write(row_num, 2, record.severity.to_string())?,
+        };
+        sheet.write(row_num, 3, record.kind.clone())?;
+        sheet.write(row_num, 4, record.message.clone())?;
+        sheet.write(row_num, 5, if record.included {"Yes"} else {"No"})?;
+    }//end writing each record on its own row
+    sheet.set_column_width(0, 20)?;
+    sheet.set_column_width(3, 18)?;
+    sheet.set_column_width(4, 80)?;
+    Ok(())
+}//end write_issues_to_sheet()
+
+/// Creates the contents of a delimited file listing every issue in file_issues,
+/// with the same columns as write_issues_to_sheet().
+pub fn format_issues_as_delimited(file_issues: &Vec<FileIssues>, delimiter: char) -> String {
+    let mut lines = vec![ISSUES_OUTPUT_HEADERS.join(&delimiter.to_string())];
+    for record in get_issue_records(file_issues) {
+        let fields = [
+            escape_delimited_field(&record.source_file, delimiter),
+            record.line_number.map(|line_number| line_number.to_string()).unwrap_or_default(),
+            record.severity.to_string(),
+            escape_delimited_field(&record.kind, delimiter),
+            escape_delimited_field(&record.message, delimiter),
+            if record.included {"Yes"} else {"No"}.to_string(),
+        ];
+        lines.push(fields.join(&delimiter.to_string()));
+    }//end adding a line for each record
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    return contents;
+}//end format_issues_as_delimited()
+
+/// Writes every issue in file_issues to a delimited file at output_path,
+/// overwriting anything already there.
+pub fn write_issues_to_delimited(file_issues: &Vec<FileIssues>, output_path: &PathBuf, delimiter: char) -> Result<(),String> {
+    match fs::write(output_path, format_issues_as_delimited(file_issues, delimiter)) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we could write the file
+}//end write_issues_to_delimited()
+
+/// Formats a single field for a delimited file.  
+/// If the field contains the delimiter, a quote, or a line break, it will
+/// be wrapped in quotes, with any quotes inside it doubled.
+fn escape_delimited_field(field: &str, delimiter: char) -> String {
+    if field.contains(delimiter) || field.contains(['"','\n','\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {field.to_string()}
+}//end escape_delimited_field()
+
+/// Formats a single line of a delimited file, with the test name and label
+/// followed by the value of each row in the column matching its header.
+/// Columns without a value are left empty.
+fn format_delimited_line(test_name: &str, label: &str, rows: &Vec<Row>, column_headers: &Vec<String>, delimiter: char) -> String {
+    let mut fields = vec![escape_delimited_field(test_name, delimiter), escape_delimited_field(label, delimiter)];
+    for header in column_headers {
+        match rows.iter().find(|row| row.header.eq(header)) {
+            Some(row) => fields.push(row.value.to_string()),
+            None => fields.push("".to_string()),
+        }//end matching whether we have a value for this column
+    }//end adding a field for each column
+    fields.join(&delimiter.to_string())
+}//end format_delimited_line()
+
+/// Creates the contents of a delimited file (such as csv or tsv) from the data.  
+/// There is one line for each curve of each test, followed by lines for
+/// the calculated average, the average from the file, the difference between them,
+/// and the statistics from get_curve_stat_rows().
+/// The columns use the same order as write_output_to_sheet(), from get_column_headers() with row_order_preference.
+pub fn format_output_as_delimited(data: &Vec<Data>, delimiter: char, row_order_preference: &[String]) -> String {
+    let column_headers = get_column_headers(data, row_order_preference);
+    let mut lines = Vec::new();
+    let header_fields: Vec<String> = ["Test Name","Curve"].iter().map(|s| s.to_string())
+        .chain(column_headers.iter().cloned())
+        .map(|field| escape_delimited_field(&field, delimiter))
+        .collect();
+    lines.push(header_fields.join(&delimiter.to_string()));
+    for data_file in data {
+        for curve in data_file.curves.iter() {
+            lines.push(format_delimited_line(&data_file.test_name, &get_curve_output_label(curve), &curve.row_data, &column_headers, delimiter));
+        }//end adding a line for each curve
+        lines.push(format_delimited_line(&data_file.test_name, "calcAVG", &data_file.calc_avg(), &column_headers, delimiter));
+        lines.push(format_delimited_line(&data_file.test_name, "AVG", &data_file.row_data, &column_headers, delimiter));
+        lines.push(format_delimited_line(&data_file.test_name, "Diff", &data_file.calc_diff(), &column_headers, delimiter));
+        for (label, rows) in get_curve_stat_rows(data_file) {
+            lines.push(format_delimited_line(&data_file.test_name, label, &rows, &column_headers, delimiter));
+        }//end adding a line for each row of statistics
+    }//end looping over each data file
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    return contents;
+}//end format_output_as_delimited()
+
+/// Writes output to a delimited file (such as csv or tsv) at output_path,
+/// overwriting anything already there.  
+/// The columns are ordered by get_column_headers() with row_order_preference.
+pub fn write_output_to_delimited(data: &Vec<Data>, output_path: &PathBuf, delimiter: char, row_order_preference: &[String]) -> Result<(),String> {
+    match fs::write(output_path, format_output_as_delimited(data, delimiter, row_order_preference)) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we could write the file
+}//end write_output_to_delimited()
+
+/// Gets one LongRecord for every value read from each file, with the
+/// curves first, in the order they were found, followed by the average block.  
+/// Excluded curves are left out, since they weren't used.
+pub fn get_long_records(data: &Vec<Data>) -> Vec<LongRecord> {
+    let mut records = Vec::new();
+    for data_file in data {
+        let curve_blocks = data_file.curves.iter().filter(|curve| !curve.excluded).map(|curve| (curve.label.as_str(), &curve.row_data));
+        let blocks = curve_blocks.chain(std::iter::once((AVERAGE_BLOCK_LABEL, &data_file.row_data)));
+        for (block, rows) in blocks {
+            for row in rows {
+                records.push(LongRecord {
+                    test_name: data_file.test_name.clone(),
+                    source_file: data_file.source_file.clone(),
+                    block: block.to_string(),
+                    header: row.header.clone(),
+                    value: row.value,
+                    raw_text: row.raw_text.clone(),
+                });
+            }//end adding a record for each row in the block
+        }//end looping over each block of rows
+    }//end looping over each data file
+    return records;
+}//end get_long_records()
+
+/// Writes long-format output, with one line per value, to a new sheet in a
+/// workbook that has already been created.  
+/// Make sure to call process::close_workbook() when finished with the workbook.
+pub fn write_long_output_to_sheet(workbook: &mut Workbook, data: &Vec<Data>, sheet_name: &str) -> Result<(),XlsxError> {
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(sheet_name)?;
+    let bold = Format::new()
+        .set_bold()
+        .set_border(BORDER_FORMAT);
+    for (col, header) in LONG_OUTPUT_HEADERS.iter().enumerate() {
+        sheet.write_with_format(0, col as u16, *header, &bold)?;
+    }//end writing each column header
+    for (offset, record) in get_long_records(data).iter().enumerate() {
+        let row_num = offset as u32 + 1;
+        sheet.write(row_num, 0, record.test_name.clone())?;
+        sheet.write(row_num, 1, record.source_file.clone())?;
+        sheet.write(row_num, 2, record.block.clone())?;
+        sheet.write(row_num, 3, record.header.clone())?;
+        sheet.write_number(row_num, 4, record.value)?;
+        sheet.write(row_num, 5, record.raw_text.clone())?;
+    }//end writing each record on its own row
+    sheet.set_column_width(0, 14.5)?;
+    sheet.set_column_width(1, 20)?;
+    Ok(())
+}//end write_long_output_to_sheet()
+
+/// Creates the contents of a long-format delimited file, with one line per value.
+pub fn format_long_output_as_delimited(data: &Vec<Data>, delimiter: char) -> String {
+    let mut lines = vec![LONG_OUTPUT_HEADERS.join(&delimiter.to_string())];
+    for record in get_long_records(data) {
+        let fields = [
+            escape_delimited_field(&record.test_name, delimiter),
+            escape_delimited_field(&record.source_file, delimiter),
+            escape_delimited_field(&record.block, delimiter),
+            escape_delimited_field(&record.header, delimiter),
+            record.value.to_string(),
+            escape_delimited_field(&record.raw_text, delimiter),
+        ];
+        lines.push(fields.join(&delimiter.to_string()));
+    }//end adding a line for each record
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    return contents;
+}//end format_long_output_as_delimited()
+
+/// Writes long-format output to a delimited file at output_path,
+/// overwriting anything already there.
+pub fn write_long_output_to_delimited(data: &Vec<Data>, output_path: &PathBuf, delimiter: char) -> Result<(),String> {
+    match fs::write(output_path, format_long_output_as_delimited(data, delimiter)) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we could write the file
+}//end write_long_output_to_delimited()
+
+/// Gets output_path with suffix added to the end of the filename, before the extension.
+fn add_suffix_to_path(output_path: &PathBuf, suffix: &str) -> PathBuf {
+    let mut suffixed_path = output_path.clone();
+    let stem = output_path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    // set_extension() would replace anything after a dot in the stem, so the name is built all at once
+    match output_path.extension() {
+        Some(ext) => suffixed_path.set_file_name(format!("{}{}.{}", stem, suffix, ext.to_string_lossy())),
+        None => suffixed_path.set_file_name(format!("{}{}", stem, suffix)),
+    }//end matching whether the output path has an extension
+    return suffixed_path;
+}//end add_suffix_to_path()
+
+/// Gets the path to write long-format delimited output to, which is
+/// output_path with LONG_OUTPUT_SUFFIX added to the end of the filename.
+pub fn get_long_output_path(output_path: &PathBuf) -> PathBuf {
+    add_suffix_to_path(output_path, LONG_OUTPUT_SUFFIX)
+}//end get_long_output_path()
+
+/// Gets the path to write issues to alongside delimited output, which is
+/// output_path with ISSUES_OUTPUT_SUFFIX added to the end of the filename.
+pub fn get_issues_output_path(output_path: &PathBuf) -> PathBuf {
+    add_suffix_to_path(output_path, ISSUES_OUTPUT_SUFFIX)
+}//end get_issues_output_path()
@@ -0,0 +1,129 @@
+use std::{fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{data::{Data, Row}, util::wildcard_match};
+
+/// The name of the file that spec profiles are stored in, next to the config file.
+pub const SPEC_FILE_NAME: &str = "specs";
+
+/// The allowed range for a single row header, such as W between 250 and 320.
+#[derive(Clone, PartialEq, PartialOrd, Debug, Deserialize, Serialize)]
+pub struct SpecLimit {
+    pub header: String,
+    /// The lowest allowed value, if there is one.
+    pub min: Option<f64>,
+    /// The highest allowed value, if there is one.
+    pub max: Option<f64>,
+}//end struct SpecLimit
+
+impl SpecLimit {
+    /// Creates a new SpecLimit with given header, min, and max.
+    pub fn new(header: &str, min: Option<f64>, max: Option<f64>) -> SpecLimit {
+        SpecLimit{header: header.to_string(), min, max}
+    }
+
+    /// Returns true if value is within the min and max of this limit.
+    pub fn passes(&self, value: f64) -> bool {
+        self.min.map_or(true, |min| value >= min) && self.max.map_or(true, |max| value <= max)
+    }//end passes()
+}//end impl SpecLimit
+
+/// A named set of limits, such as a customer's specification for a flour.
+#[derive(Clone, PartialEq, PartialOrd, Debug, Deserialize, Serialize)]
+pub struct SpecProfile {
+    pub name: String,
+    /// Test names matching any of these patterns use this spec.
+    /// A "*" matches any run of characters and a "?" matches a single character, ignoring case.
+    pub test_name_patterns: Vec<String>,
+    /// Exact test names which use this spec. These take priority over test_name_patterns
+    /// in every profile, so they can be used to assign a spec to particular tests by hand.
+    pub test_names: Vec<String>,
+    pub limits: Vec<SpecLimit>,
+}//end struct SpecProfile
+
+impl SpecProfile {
+    /// Creates a new SpecProfile with the given name and no limits.
+    pub fn new(name: &str) -> SpecProfile {
+        SpecProfile{name: name.to_string(), test_name_patterns: Vec::new(), test_names: Vec::new(), limits: Vec::new()}
+    }
+
+    /// Returns true if test_name matches one of test_name_patterns.
+    pub fn matches_test_name(&self, test_name: &str) -> bool {
+        self.test_name_patterns.iter().any(|pattern| wildcard_match(pattern, test_name))
+    }//end matches_test_name()
+
+    /// Checks each row against the limit with the same header.
+    /// Returns the headers of any rows outside their limits, or an empty Vec if all passed.
+    /// Rows without a limit are ignored.
+    pub fn get_failed_headers(&self, rows: &Vec<Row>) -> Vec<String> {
+        rows.iter()
+            .filter(|row| self.limits.iter().any(|limit| limit.header.eq(&row.header) && !limit.passes(row.value)))
+            .map(|row| row.header.clone())
+            .collect()
+    }//end get_failed_headers()
+}//end impl SpecProfile
+
+/// Holds every spec profile, and is saved to its own file next to the config file.
+#[derive(Clone, PartialEq, PartialOrd, Debug, Default, Deserialize, Serialize)]
+pub struct SpecStore {
+    pub profiles: Vec<SpecProfile>,
+}//end struct SpecStore
+
+impl SpecStore {
+    /// Gets the profile with the given name, if there is one.
+    pub fn get_profile(&self, name: &str) -> Option<&SpecProfile> {
+        self.profiles.iter().find(|profile| profile.name.eq(name))
+    }//end get_profile()
+
+    /// Finds the profile to use for a test.
+    /// Profiles listing the exact test name are checked first, then profiles with
+    /// a matching test name pattern, in the order they are stored.
+    pub fn find_profile_for_test(&self, test_name: &str) -> Option<&SpecProfile> {
+        self.profiles.iter().find(|profile| profile.test_names.iter().any(|name| name.eq(test_name)))
+            .or_else(|| self.profiles.iter().find(|profile| profile.matches_test_name(test_name)))
+    }//end find_profile_for_test()
+}//end impl SpecStore
+
+/// Gets the rows a test is graded on, which are the averages of the curves
+/// that weren't excluded, or the averages from the file if there are no curves.
+pub fn get_graded_rows(data: &Data) -> Vec<Row> {
+    let calc_avg = data.calc_avg();
+    if calc_avg.is_empty() {data.row_data.clone()} else {calc_avg}
+}//end get_graded_rows()
+
+/// Sets the spec_name of each Data which doesn't have one yet,
+/// using SpecStore::find_profile_for_test().
+pub fn assign_spec_names(data_files: &mut Vec<Data>, specs: &SpecStore) {
+    for data in data_files.iter_mut().filter(|data| data.spec_name.is_empty()) {
+        if let Some(profile) = specs.find_profile_for_test(&data.test_name) {
+            data.spec_name = profile.name.clone();
+        }//end if we found a profile for this test
+    }//end looping over each data without a spec
+}//end assign_spec_names()
+
+/// Attempts to read contents of file at path and deserialize into SpecStore object.
+pub fn try_read_specs(spec_path: &PathBuf) -> Result<SpecStore,String> {
+    match fs::read_to_string(spec_path) {
+        Ok(file_contents) => {
+            match serde_json::from_str(&file_contents) {
+                Ok(spec_store) => Ok(spec_store),
+                Err(error) => Err(error.to_string()),
+            }//end matching whether we can deserialize specs
+        },
+        Err(error) => Err(error.to_string())
+    }//end matching whether we could read string from file
+}//end try_read_specs()
+
+/// Attempts to write given spec_store to the given path.
+pub fn try_write_specs(spec_path: &PathBuf, spec_store: &SpecStore) -> Result<(),String> {
+    match serde_json::to_string_pretty(spec_store) {
+        Ok(spec_serial) => {
+            match fs::write(spec_path, spec_serial) {
+                Ok(_) => Ok(()),
+                Err(error) => Err(error.to_string()),
+            }//end matching whether or not write succeeded
+        },
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we could serialize specs
+}//end try_write_specs()
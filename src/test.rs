@@ -33,6 +33,15 @@ pub fn sample_config() -> ConfigStore {
         row_order_preference: vec!["G","L","P"].iter().map(|s| s.to_string()).collect(),
         read_row_split_char: "\t".to_string(),
         read_test_name_prefix: "Test name\t:\t".to_string(),
+        read_curve_header_prefix: "Standard\t : \tCurve".to_string(),
+        database_path: "".to_string(),
+        curve_exclusion_header: "L".to_string(),
+        curve_exclusion_max_deviation: 0,
+        diff_tolerances: Vec::new(),
+        input_include_patterns: vec!["**/*.txt".to_string()],
+        input_exclude_patterns: Vec::new(),
+        decimal_separator: crate::config_store::DecimalSeparator::Point,
+        thousands_separator: crate::config_store::ThousandsSeparator::None,
     }//end struct construction
 }//end sample_config()
 
@@ -71,7 +80,7 @@ pub fn data_get_header_idx_from_lines1() {
 //         &file_lines.join("\n"),
 //         &config
 //     ).unwrap();
-//     assert!(errs.len() == 0);
+//     assert!(errs.is_empty());
 //     let correct_data = crate::data::Data::new1(
 //         "Sample001-1234567".to_string(),
 //         vec![
@@ -96,11 +105,1234 @@ pub fn data_sort_row_data1() {
         crate::data::Row::new("G".to_string(),3.),
         crate::data::Row::new("L".to_string(),2.),
         crate::data::Row::new("P".to_string(),1.),
-        crate::data::Row::new("H2O".to_string(),4.),
     ];
-    let sorted_row_data = crate::data::sort_row_data(
+    let (sorted_row_data, errs) = crate::data::sort_row_data(
+        "sample-filename",
         row_data,
         &sample_config()
     );
     assert_eq!(correct_sorted_row_data,sorted_row_data);
+    assert!(errs.is_empty());
 }//end data_sort_row_data1
+
+/// Sample lines from a made-up file with a few curves, to be used for testing.  
+/// Curve1 shows up twice, and there is no Curve4.
+pub fn sample_curve_file_lines() -> Vec<String> {
+    vec![
+        "wigejewg ignore eikgubeg",
+        "Test name\t:\tSample002-7654321",
+        "",
+        "Standard\t : \tCurve1",
+        "P\t11",
+        "L\t12",
+        "G\t13",
+        "",
+        "Standard\t : \tCurve2",
+        "P\t21",
+        "L\t22",
+        "G\t23",
+        "",
+        "Standard\t : \tCurve1",
+        "P\t31",
+        "L\t32",
+        "G\t33",
+        "",
+        "Standard\t : \tAverage",
+        "P\t1",
+        "L\t2",
+        "G\t3",
+    ].iter().map(|s| s.to_string()).collect()
+}//end sample_curve_file_lines()
+
+/// A folder inside the system temp folder for a single test to write files in.  
+/// The folder is removed when this is dropped, so it's cleaned up even if the test fails part way through.
+pub struct TempDir {
+    path: std::path::PathBuf,
+}//end struct TempDir
+
+impl TempDir {
+    /// Creates an empty folder named after test_name, removing anything left from an earlier run.
+    pub fn new(test_name: &str) -> TempDir {
+        let path = std::env::temp_dir().join(format!("alveograph-exporter-s-test-{}-{}", test_name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        TempDir{path}
+    }//end new()
+
+    /// The path of the folder.
+    pub fn path(&self) -> &std::path::PathBuf {&self.path}
+}//end impl for TempDir
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }//end drop()
+}//end impl Drop for TempDir
+
+/// Test 1 for crate::data::get_curve_header_idxs_from_lines()
+#[test]
+pub fn data_get_curve_header_idxs_from_lines1() {
+    let file_lines = sample_curve_file_lines();
+    let config = sample_config();
+    let mut errs = Vec::new();
+    let curve_idxs = crate::data::get_curve_header_idxs_from_lines(
+        "sample-filename",
+        &file_lines,
+        &config,
+        &mut errs
+    );
+    assert_eq!(curve_idxs, vec![("Curve1".to_string(),3), ("Curve2".to_string(),8)]);
+    // the second Curve1 should be reported as a duplicate
+    assert_eq!(errs.len(), 1);
+    assert_eq!(errs[0].line_idx, Some(13));
+    assert_eq!(errs[0].kind, crate::data::ParseIssueKind::DuplicateCurve{label: "Curve1".to_string(), first_line_idx: 3});
+}//end data_get_curve_header_idxs_from_lines1()
+
+/// Test 2 for crate::data::get_curve_header_idxs_from_lines()
+#[test]
+pub fn data_get_curve_header_idxs_from_lines2() {
+    let file_lines = sample_file_lines();
+    let config = sample_config();
+    let mut errs = Vec::new();
+    let curve_idxs = crate::data::get_curve_header_idxs_from_lines(
+        "sample-filename",
+        &file_lines,
+        &config,
+        &mut errs
+    );
+    assert!(curve_idxs.is_empty());
+    assert_eq!(errs.len(), 1);
+    assert_eq!(errs[0].kind.category(), "missing-curves");
+}//end data_get_curve_header_idxs_from_lines2()
+
+/// Test 1 for crate::data::read_data_from_file()
+#[test]
+pub fn data_read_data_from_file1() {
+    let file_lines = sample_curve_file_lines();
+    let config = sample_config();
+    let (data, errs) = crate::data::read_data_from_file(
+        "sample-filename",
+        &file_lines.join("\n"),
+        &config
+    ).unwrap();
+    assert_eq!(data.test_name, "Sample002-7654321".to_string());
+    assert_eq!(data.row_data, vec![
+        crate::data::Row::new("G".to_string(),3.),
+        crate::data::Row::new("L".to_string(),2.),
+        crate::data::Row::new("P".to_string(),1.),
+    ]);
+    assert_eq!(data.curves.len(), 2);
+    assert_eq!(data.curves[0], crate::data::Curve::new("Curve1".to_string(), vec![
+        crate::data::Row::new("G".to_string(),13.),
+        crate::data::Row::new("L".to_string(),12.),
+        crate::data::Row::new("P".to_string(),11.),
+    ]));
+    assert_eq!(data.curves[1].label, "Curve2".to_string());
+    assert_eq!(data.curves[1].row_data[0], crate::data::Row::new("G".to_string(),23.));
+    // just the duplicate Curve1
+    assert_eq!(errs.len(), 1);
+    assert_eq!(errs[0].severity(), crate::data::IssueSeverity::Warning);
+}//end data_read_data_from_file1()
+
+/// Test 1 for crate::data::read_rows_from_lines()
+#[test]
+pub fn data_read_rows_from_lines1() {
+    let mut file_lines = sample_file_lines();
+    file_lines[9] = "L\t12,5".to_string();
+    let config = sample_config();
+    let mut errs = Vec::new();
+    let rows = crate::data::read_rows_from_lines(
+        "sample-filename",
+        &file_lines,
+        7,
+        &config,
+        &mut errs
+    );
+    assert_eq!(rows.len(), 2);
+    assert_eq!(errs.len(), 1);
+    assert_eq!(errs[0].line_idx, Some(9));
+    match &errs[0].kind {
+        crate::data::ParseIssueKind::BadNumber{text, column, ..} => {
+            assert_eq!(text, "12,5");
+            assert_eq!(*column, 2);
+        },
+        other => panic!("Expected a bad-number issue, found {:?}", other),
+    }//end matching the kind of issue
+    assert!(errs[0].to_string().starts_with("Warning in \"sample-filename\" on line 10: "));
+}//end data_read_rows_from_lines1()
+
+/// Test 1 for crate::data::NumberFormat
+#[test]
+pub fn data_number_format1() {
+    // existing configs keep reading numbers with a decimal point, the same as before separators could be set
+    let default_format = crate::data::NumberFormat::detect(&["85,3"], &ConfigStore::default());
+    assert_eq!(default_format, crate::data::NumberFormat{decimal: '.', thousands: None});
+    assert!(default_format.parse("12,5").is_err());
+    let mut config = sample_config();
+    config.decimal_separator = crate::config_store::DecimalSeparator::Auto;
+    config.thousands_separator = crate::config_store::ThousandsSeparator::Auto;
+    let decimal_comma = crate::data::NumberFormat::detect(&["85,3", "1.234,5", "0,62", "250"], &config);
+    assert_eq!(decimal_comma, crate::data::NumberFormat{decimal: ',', thousands: Some('.')});
+    assert_eq!(decimal_comma.parse(" 85,3 "), Ok(85.3));
+    assert_eq!(decimal_comma.parse("1.234,5"), Ok(1234.5));
+    assert_eq!(decimal_comma.parse("-0,62"), Ok(-0.62));
+    assert!(decimal_comma.parse("85.3").is_err());
+    // three digits after a single separator could be either, so the other values decide
+    let decimal_point = crate::data::NumberFormat::detect(&["1,234", "85.3"], &config);
+    assert_eq!(decimal_point, crate::data::NumberFormat{decimal: '.', thousands: Some(',')});
+    assert_eq!(decimal_point.parse("1,234"), Ok(1234.));
+    assert!(decimal_point.parse("2,3").is_err());
+    assert_eq!(crate::data::NumberFormat::detect(&["1,234"], &config).decimal, '.');
+    let mut fixed_config = sample_config();
+    fixed_config.decimal_separator = crate::config_store::DecimalSeparator::Comma;
+    fixed_config.thousands_separator = crate::config_store::ThousandsSeparator::Space;
+    let fixed = crate::data::NumberFormat::detect(&["1.5"], &fixed_config);
+    assert_eq!(fixed, crate::data::NumberFormat{decimal: ',', thousands: Some(' ')});
+    assert_eq!(fixed.parse("1 234,5"), Ok(1234.5));
+    fixed_config.thousands_separator = crate::config_store::ThousandsSeparator::Comma;
+    assert!(fixed_config.validate().contains(&crate::config_store::ConfigProblem::SameSeparators{separator: "Comma".to_string()}));
+
+    // every block in a file is read with the same separators, keeping the text from the file
+    let file_lines = sample_curve_file_lines().join("\n").replace("P\t1\n", "P\t1,5\n").replace("P\t21", "P\t21,25");
+    let (data, errs) = crate::data::read_data_from_file("sample-filename", &file_lines, &config).unwrap();
+    assert_eq!(errs.len(), 1);
+    assert_eq!(data.row_data[2], crate::data::Row::new("P".to_string(), 1.5));
+    assert_eq!(data.row_data[2].raw_text, "1,5");
+    assert_eq!(data.curves[1].row_data[2], crate::data::Row::new("P".to_string(), 21.25));
+    assert_eq!(data.curves[1].row_data[2].raw_text, "21,25");
+    // rows are compared by header and value, whatever text they were read from
+    assert_eq!(crate::data::Row::new1("P".to_string(), 1.5, "1,5".to_string()), crate::data::Row::new1("P".to_string(), 1.5, "1.5".to_string()));
+}//end data_number_format1()
+
+/// Test 1 for crate::data::preview_file()
+#[test]
+pub fn data_preview_file1() {
+    let file_lines = sample_curve_file_lines();
+    let config = sample_config();
+    let preview = crate::data::preview_file(
+        "sample-filename",
+        &file_lines.join("\n"),
+        &config
+    );
+    assert_eq!(preview.lines.len(), file_lines.len());
+    assert_eq!(preview.test_name_idx, Some(1));
+    assert_eq!(preview.header_idx, Some(18));
+    assert_eq!(preview.row_idxs, vec![19,20,21]);
+    assert_eq!(preview.curve_header_idxs, vec![3,8]);
+    assert_eq!(preview.curve_row_idxs, vec![4,5,6,9,10,11]);
+    assert_eq!(preview.get_line_kind(0), crate::data::PreviewLineKind::Unused);
+    assert_eq!(preview.get_line_kind(1), crate::data::PreviewLineKind::TestName);
+    assert_eq!(preview.get_line_kind(5), crate::data::PreviewLineKind::CurveRow);
+    // the duplicate Curve1 isn't read
+    assert_eq!(preview.get_line_kind(13), crate::data::PreviewLineKind::Unused);
+    assert_eq!(preview.get_line_kind(20), crate::data::PreviewLineKind::AverageRow);
+    let value_lines = preview.get_value_lines();
+    assert_eq!(value_lines[0], "Test name: Sample002-7654321");
+    assert_eq!(value_lines[2], "  G: 3");
+    assert!(value_lines.last().unwrap().starts_with("Warning"));
+}//end data_preview_file1()
+
+/// Test 1 for crate::data::Data::calc_avg() and crate::data::Data::calc_diff()
+#[test]
+pub fn data_calc_avg_diff1() {
+    let rows = |p: f64, l: f64| vec![
+        crate::data::Row::new("P".to_string(),p),
+        crate::data::Row::new("L".to_string(),l),
+    ];
+    let data = crate::data::Data::new1(
+        "Sample003".to_string(),
+        vec![crate::data::Row::new("P".to_string(),2.)],
+        vec![
+            crate::data::Curve::new("Curve1".to_string(), rows(1.,10.)),
+            crate::data::Curve::new("Curve2".to_string(), rows(2.,20.)),
+            crate::data::Curve::new("Curve3".to_string(), rows(6.,30.)),
+        ],
+    );
+    assert_eq!(data.calc_avg(), rows(3.,20.));
+    // L isn't in the file average, so there's no diff for it
+    assert_eq!(data.calc_diff(), vec![crate::data::Row::new("P".to_string(),1.)]);
+}//end data_calc_avg_diff1()
+
+/// Test 1 for crate::data::Data::calc_std_dev() and the other curve statistics
+#[test]
+pub fn data_calc_stats1() {
+    let rows = |p: f64, l: f64| vec![
+        crate::data::Row::new("P".to_string(),p),
+        crate::data::Row::new("L".to_string(),l),
+    ];
+    let mut data = crate::data::Data::new1(
+        "Sample006".to_string(),
+        Vec::new(),
+        vec![
+            crate::data::Curve::new("Curve1".to_string(), rows(2.,10.)),
+            crate::data::Curve::new("Curve2".to_string(), rows(4.,10.)),
+            crate::data::Curve::new("Curve3".to_string(), rows(6.,10.)),
+        ],
+    );
+    assert_eq!(data.calc_std_dev(), rows(2.,0.));
+    assert_eq!(data.calc_cv(), rows(50.,0.));
+    assert_eq!(data.calc_min(), rows(2.,10.));
+    assert_eq!(data.calc_max(), rows(6.,10.));
+    assert_eq!(data.calc_range(), rows(4.,0.));
+    // a single curve has no standard deviation
+    data.curves.truncate(1);
+    assert!(data.calc_std_dev().is_empty());
+    assert!(data.calc_cv().is_empty());
+    assert_eq!(data.calc_range(), rows(0.,0.));
+}//end data_calc_stats1()
+
+/// Test 1 for crate::data::Data::exclude_curves_by_median()
+#[test]
+pub fn data_exclude_curves_by_median1() {
+    let rows = |p: f64, l: f64| vec![
+        crate::data::Row::new("P".to_string(),p),
+        crate::data::Row::new("L".to_string(),l),
+    ];
+    let mut data = crate::data::Data::new1(
+        "Sample007".to_string(),
+        vec![crate::data::Row::new("L".to_string(),100.)],
+        vec![
+            crate::data::Curve::new("Curve1".to_string(), rows(1.,100.)),
+            crate::data::Curve::new("Curve2".to_string(), rows(2.,104.)),
+            crate::data::Curve::new("Curve3".to_string(), rows(3.,40.)),
+            crate::data::Curve::new("Curve4".to_string(), rows(4.,96.)),
+        ],
+    );
+    // median of L is 98, so only Curve3 is more than 10% away
+    assert_eq!(data.exclude_curves_by_median("L", 10.), vec!["Curve3".to_string()]);
+    assert!(data.curves[2].excluded);
+    assert_eq!(data.calc_avg(), rows(7./3.,100.));
+    assert_eq!(data.calc_diff(), vec![crate::data::Row::new("L".to_string(),0.)]);
+    let csv = crate::process::format_output_as_delimited(&vec![data.clone()], ',', &[]);
+    assert!(csv.contains("\nSample007,Curve3 (excluded),40,3\n"));
+    // the median now leaves out Curve3, so it's 100 and both Curve2 and Curve4 are more than 3% away
+    assert_eq!(data.exclude_curves_by_median("L", 3.), vec!["Curve2".to_string(), "Curve4".to_string()]);
+}//end data_exclude_curves_by_median1()
+
+/// Test 1 for crate::data::Data::get_flagged_diffs()
+#[test]
+pub fn data_get_flagged_diffs1() {
+    let rows = |p: f64, l: f64, w: f64| vec![
+        crate::data::Row::new("P".to_string(),p),
+        crate::data::Row::new("L".to_string(),l),
+        crate::data::Row::new("W".to_string(),w),
+    ];
+    let data = crate::data::Data::new1(
+        "Sample008".to_string(),
+        rows(10.,100.,300.),
+        vec![
+            crate::data::Curve::new("Curve1".to_string(), rows(11.,104.,290.)),
+            crate::data::Curve::new("Curve2".to_string(), rows(11.,104.,290.)),
+        ],
+    );
+    let tolerances = vec![
+        crate::config_store::DiffTolerance::new("P", crate::config_store::ToleranceMode::Absolute, 0.5),
+        crate::config_store::DiffTolerance::new("L", crate::config_store::ToleranceMode::Relative, 5.),
+        crate::config_store::DiffTolerance::new("W", crate::config_store::ToleranceMode::Relative, 3.),
+    ];
+    // P is off by 1, L by 4%, and W by 3.33%
+    assert_eq!(data.get_flagged_diffs(&tolerances), vec!["P".to_string(), "W".to_string()]);
+    assert!(data.get_flagged_diffs(&Vec::new()).is_empty());
+}//end data_get_flagged_diffs1()
+
+/// Test 1 for crate::util::wildcard_match()
+#[test]
+pub fn util_wildcard_match1() {
+    assert!(crate::util::wildcard_match("Sample*", "sample001-1234567"));
+    assert!(crate::util::wildcard_match("*-123*7", "Sample001-1234567"));
+    assert!(crate::util::wildcard_match("Sample00?-*", "Sample002-7654321"));
+    assert!(crate::util::wildcard_match("*", ""));
+    assert!(!crate::util::wildcard_match("Sample?", "Sample"));
+    assert!(!crate::util::wildcard_match("*ALV*", "Sample001"));
+}//end util_wildcard_match1()
+
+/// Test 1 for crate::spec::SpecStore::find_profile_for_test()
+#[test]
+pub fn spec_find_profile_for_test1() {
+    let mut bread = crate::spec::SpecProfile::new("Bread");
+    bread.test_name_patterns = vec!["B*".to_string()];
+    bread.limits = vec![
+        crate::spec::SpecLimit::new("W", Some(250.), Some(320.)),
+        crate::spec::SpecLimit::new("P/L", Some(0.5), Some(0.8)),
+    ];
+    let mut biscuit = crate::spec::SpecProfile::new("Biscuit");
+    biscuit.test_names = vec!["B100".to_string()];
+    let specs = crate::spec::SpecStore{profiles: vec![bread.clone(), biscuit]};
+    // an exact test name wins over an earlier pattern
+    assert_eq!(specs.find_profile_for_test("B100").unwrap().name, "Biscuit");
+    assert_eq!(specs.find_profile_for_test("B200").unwrap().name, "Bread");
+    assert!(specs.find_profile_for_test("C300").is_none());
+    let rows = vec![
+        crate::data::Row::new("W".to_string(),330.),
+        crate::data::Row::new("P/L".to_string(),0.6),
+        crate::data::Row::new("L".to_string(),90.),
+    ];
+    assert_eq!(bread.get_failed_headers(&rows), vec!["W".to_string()]);
+    assert_eq!(crate::process::get_spec_result_text(&bread, &rows), "Fail: W");
+}//end spec_find_profile_for_test1()
+
+/// Test 1 for crate::config_store::ConfigProfiles
+#[test]
+pub fn config_store_config_profiles1() {
+    let mut profiles = crate::config_store::ConfigProfiles::default();
+    let mut other_config = sample_config();
+    other_config.read_max_rows = 3;
+    assert!(profiles.create("Other", other_config.clone()).is_ok());
+    assert_eq!(profiles.current(), other_config);
+    assert!(profiles.create("Other", ConfigStore::default()).is_err());
+    assert!(profiles.create("  ", ConfigStore::default()).is_err());
+    assert!(profiles.duplicate("Other", "Other copy").is_ok());
+    assert_eq!(profiles.current_name(), "Other copy");
+    assert!(profiles.rename("Other copy", "Renamed").is_ok());
+    assert_eq!(profiles.last_used, "Renamed");
+    assert_eq!(profiles.names(), vec!["Default", "Other", "Renamed"]);
+    assert!(profiles.delete("Renamed").is_ok());
+    assert_eq!(profiles.current_name(), "Default");
+    assert!(profiles.delete("Default").is_ok());
+    assert!(profiles.delete("Other").is_err());
+    assert_eq!(profiles.current(), other_config);
+
+    // config files from before profiles are read as a single profile
+    let temp_dir = TempDir::new("profiles");
+    let config_path = temp_dir.path().join("config.json");
+    crate::config_store::try_write_config(&config_path, &other_config).unwrap();
+    let read_profiles = crate::config_store::try_read_profiles(&config_path).unwrap();
+    assert_eq!(read_profiles.names(), vec![crate::config_store::DEFAULT_PROFILE_NAME]);
+    assert_eq!(read_profiles.current(), other_config);
+}//end config_store_config_profiles1()
+
+/// Test 1 for crate::config_store::migrate_config_json()
+#[test]
+pub fn config_store_migrate_config_json1() {
+    // a config from before profiles, missing fields added since then
+    let old_json = serde_json::json!({
+        "read_start_header": "Standard\t : \tAverage",
+        "read_start_idx": 105,
+        "read_row_headers": ["P","L"],
+        "read_max_rows": 2,
+        "read_start_mode": "Header",
+        "read_row_mode": "Max",
+        "row_order_preference": ["L"],
+        "read_row_split_char": "\t",
+        "read_test_name_prefix": "Test name\t:\t",
+    });
+    assert_eq!(crate::config_store::get_config_version(&old_json), 0);
+    let new_json = crate::config_store::migrate_config_json(old_json);
+    assert_eq!(crate::config_store::get_config_version(&new_json), crate::config_store::CONFIG_VERSION);
+    let profiles: crate::config_store::ConfigProfiles = serde_json::from_value(new_json).unwrap();
+    let config = profiles.current();
+    assert_eq!(config.read_max_rows, 2);
+    assert_eq!(config.read_row_mode, crate::config_store::ReadRowMode::Max);
+    assert_eq!(config.read_curve_header_prefix, ConfigStore::default().read_curve_header_prefix);
+    assert_eq!(config.curve_exclusion_header, "L");
+    assert!(config.diff_tolerances.is_empty());
+
+    // profiles written before the version field was added are version 1
+    let unversioned_json = serde_json::json!({"last_used": "Default", "profiles": []});
+    assert_eq!(crate::config_store::get_config_version(&unversioned_json), 1);
+
+    // profiles without a last used profile fall back to the first one
+    let no_last_used_json = serde_json::json!({"version": 1, "profiles": [{"name": "Only", "config": ConfigStore::default()}]});
+    let profiles: crate::config_store::ConfigProfiles = serde_json::from_value(crate::config_store::migrate_config_json(no_last_used_json)).unwrap();
+    assert_eq!(profiles.last_used, "");
+    assert_eq!(profiles.current_name(), "Only");
+}//end config_store_migrate_config_json1()
+
+/// Test 2 for crate::config_store::migrate_config_json()
+#[test]
+pub fn config_store_migrate_config_json2() {
+    // a config.json from before any fields were added, with only the original fields
+    let old_text = r#"{
+        "read_start_header": "Standard\t : \tAverage",
+        "read_start_idx": 105,
+        "read_row_headers": ["P","L","W"],
+        "read_max_rows": 3,
+        "read_start_mode": "Header",
+        "read_row_mode": "Header",
+        "row_order_preference": [],
+        "read_row_split_char": "\t",
+        "read_test_name_prefix": "Test name\t:\t"
+    }"#;
+    let old_json: serde_json::Value = serde_json::from_str(old_text).unwrap();
+    let config: ConfigStore = serde_json::from_value(old_json.clone()).unwrap();
+    let default_config = ConfigStore::default();
+    let mut expected_config = default_config.clone();
+    expected_config.read_row_headers = vec!["P".to_string(), "L".to_string(), "W".to_string()];
+    expected_config.read_max_rows = 3;
+    expected_config.read_row_mode = crate::config_store::ReadRowMode::Header;
+    expected_config.row_order_preference = Vec::new();
+    assert_eq!(config, expected_config);
+    let profiles: crate::config_store::ConfigProfiles = serde_json::from_value(crate::config_store::migrate_config_json(old_json)).unwrap();
+    assert_eq!(profiles.current(), expected_config);
+
+    // configs can still be kept in sets, even with diff tolerances
+    let mut tolerance_config = expected_config.clone();
+    tolerance_config.diff_tolerances.push(crate::config_store::DiffTolerance::new("W", crate::config_store::ToleranceMode::Relative, 2.5));
+    let configs: std::collections::HashSet<ConfigStore> = [expected_config.clone(), tolerance_config.clone(), tolerance_config.clone()].into_iter().collect();
+    assert_eq!(configs.len(), 2);
+    assert!(expected_config < tolerance_config);
+}//end config_store_migrate_config_json2()
+
+/// Test 1 for crate::config_store::try_backup_config()
+#[test]
+pub fn config_store_try_backup_config1() {
+    let temp_dir = TempDir::new("backup");
+    let dir = temp_dir.path();
+    let config_path = dir.join("config.json");
+    std::fs::write(&config_path, "first").unwrap();
+    let first_backup = crate::config_store::try_backup_config(&config_path);
+    std::fs::write(&config_path, "second").unwrap();
+    let second_backup = crate::config_store::try_backup_config(&config_path);
+    let third_backup = crate::config_store::try_backup_config(&config_path);
+    let first_contents = first_backup.as_ref().ok().and_then(|path| std::fs::read_to_string(path).ok());
+    let second_contents = second_backup.as_ref().ok().and_then(|path| std::fs::read_to_string(path).ok());
+
+    // backups made within the same second get a counter instead of overwriting each other
+    let (first_backup, second_backup, third_backup) = (first_backup.unwrap(), second_backup.unwrap(), third_backup.unwrap());
+    assert_ne!(first_backup, second_backup);
+    assert_ne!(second_backup, third_backup);
+    assert_ne!(first_backup, third_backup);
+    assert!(first_backup.file_name().unwrap().to_string_lossy().starts_with("config-backup-"));
+    assert_eq!(first_backup.extension().unwrap(), "json");
+    assert_eq!(first_contents, Some("first".to_string()));
+    assert_eq!(second_contents, Some("second".to_string()));
+}//end config_store_try_backup_config1()
+
+/// Test 1 for crate::config_store::find_config_path()
+#[test]
+pub fn config_store_find_config_path1() {
+    use crate::config_store::{find_config_dir, find_config_path, get_config_file_path, ConfigLocation, CONFIG_FILE_NAME};
+    let override_path = std::path::PathBuf::from("somewhere").join("else.json");
+    assert_eq!(find_config_path(CONFIG_FILE_NAME, Some(&override_path)), Ok((override_path.clone(), ConfigLocation::Override)));
+
+    // the environment variable's directory is used whether anything is in it or not
+    let env_dir = std::env::temp_dir().join("alveograph-exporter-env-test");
+    assert_eq!(find_config_dir(Some(env_dir.clone().into_os_string())), Ok((env_dir.clone(), ConfigLocation::EnvVar)));
+    assert_eq!(get_config_file_path(&env_dir, CONFIG_FILE_NAME), env_dir.join("config.json"));
+    assert_eq!(get_config_file_path(&env_dir, crate::spec::SPEC_FILE_NAME), env_dir.join("specs.json"));
+
+    // an empty variable counts as not being set
+    let (default_dir, location) = find_config_dir(Some(std::ffi::OsString::new())).unwrap();
+    assert_eq!(find_config_dir(None), Ok((default_dir.clone(), location)));
+    assert!(location == ConfigLocation::UserDir || location == ConfigLocation::ExeDir);
+
+    // config.json and specs.json are always found in the same directory
+    let (config_path, config_location) = find_config_path(CONFIG_FILE_NAME, None).unwrap();
+    let (specs_path, specs_location) = find_config_path(crate::spec::SPEC_FILE_NAME, None).unwrap();
+    assert_eq!(config_path.parent(), specs_path.parent());
+    assert_eq!(config_location, specs_location);
+}//end config_store_find_config_path1()
+
+/// Test 1 for crate::config_store::ConfigStore::validate()
+#[test]
+pub fn config_store_validate1() {
+    use crate::config_store::{ConfigProblem, ReadRowMode};
+    let mut config = sample_config();
+    assert!(config.validate().is_empty());
+
+    config.read_start_header = " ".to_string();
+    config.read_row_split_char = "".to_string();
+    config.row_order_preference.push("W".to_string());
+    let problems = config.validate();
+    assert_eq!(problems, vec![
+        ConfigProblem::BlankStartHeader,
+        ConfigProblem::EmptySplitChar,
+        ConfigProblem::UnknownOrderHeader{header: "W".to_string()},
+    ]);
+    assert_eq!(problems[0].severity(), crate::data::IssueSeverity::Error);
+    assert_eq!(problems[2].field(), "row_order_preference");
+
+    // in Max mode, the headers come from the file, so the row order preference can't be checked
+    let mut config = sample_config();
+    config.read_row_mode = ReadRowMode::Max;
+    config.read_max_rows = 6;
+    config.row_order_preference.push("W".to_string());
+    assert!(config.validate().is_empty());
+    config.read_max_rows = 0;
+    assert_eq!(config.validate(), vec![ConfigProblem::ZeroMaxRows]);
+}//end config_store_validate1()
+
+/// Test 1 for crate::config_store::ConfigStore::validate_files()
+#[test]
+pub fn config_store_validate_files1() {
+    use crate::config_store::{ConfigProblem, ReadRowMode, ReadStartMode};
+    let sample_file = ("sample-filename".to_string(), sample_file_lines().join("\n"));
+    let mut config = sample_config();
+    config.read_max_rows = 10;
+    // files are only checked in Max mode
+    assert!(config.validate_files(std::slice::from_ref(&sample_file)).is_empty());
+
+    // the sample file has 6 lines after the start header at index 7
+    config.read_start_mode = ReadStartMode::Index;
+    config.read_row_mode = ReadRowMode::Max;
+    config.read_max_rows = 6;
+    assert!(config.validate_files(std::slice::from_ref(&sample_file)).is_empty());
+    config.read_max_rows = 10;
+    assert_eq!(config.validate_files(&[sample_file]), vec![
+        ConfigProblem::MaxRowsExceedsFile{filename: "sample-filename".to_string(), read_max_rows: 10, rows_available: 6},
+    ]);
+}//end config_store_validate_files1()
+
+/// Test 1 for crate::config_store::get_config_differences()
+#[test]
+pub fn config_store_get_config_differences1() {
+    let old_config = sample_config();
+    let mut new_config = sample_config();
+    assert!(crate::config_store::get_config_differences(&old_config, &new_config).is_empty());
+    new_config.read_max_rows = 11;
+    new_config.read_row_split_char = ",".to_string();
+    let differences = crate::config_store::get_config_differences(&old_config, &new_config);
+    assert_eq!(differences.len(), 2);
+    assert_eq!(differences[0].to_string(), "read_max_rows: 3 -> 11");
+    assert_eq!(differences[1].to_string(), "read_row_split_char: \"\\t\" -> \",\"");
+
+    // a whole config.json can be imported, giving the profile used last
+    let mut profiles = crate::config_store::ConfigProfiles::default();
+    profiles.create("Other", new_config.clone()).unwrap();
+    let temp_dir = TempDir::new("differences");
+    let config_path = temp_dir.path().join("config.json");
+    crate::config_store::try_write_profiles(&config_path, &profiles).unwrap();
+    let imported_config = crate::config_store::try_import_config(&config_path);
+    assert_eq!(imported_config, Ok((new_config, Vec::new())));
+}//end config_store_get_config_differences1()
+
+/// Test 1 for crate::config_store::try_import_config()
+#[test]
+pub fn config_store_try_import_config1() {
+    use crate::config_store::{try_import_config, CONFIG_VERSION};
+    let temp_dir = TempDir::new("import");
+    let dir = temp_dir.path();
+    let import = |file_name: &str, file_json: serde_json::Value| {
+        let import_path = dir.join(file_name);
+        std::fs::write(&import_path, file_json.to_string()).unwrap();
+        try_import_config(&import_path)
+    };
+    let old_config_json = serde_json::json!({
+        "read_start_header": "Standard\t : \tAverage",
+        "read_start_idx": 105,
+        "read_row_headers": ["P","L"],
+        "read_max_rows": 2,
+        "read_start_mode": "Header",
+        "read_row_mode": "Header",
+        "row_order_preference": [],
+        "read_row_split_char": "\t",
+        "read_test_name_prefix": "Test name\t:\t",
+    });
+    // a config from before profiles is imported, listing the settings added since then
+    let old_result = import("old.json", old_config_json.clone());
+    // json which was never a config, or is missing settings, is rejected
+    let empty_result = import("empty.json", serde_json::json!({}));
+    let mut partial_json = old_config_json.clone();
+    partial_json.as_object_mut().unwrap().remove("read_row_headers");
+    let partial_result = import("partial.json", partial_json);
+    let no_profiles_result = import("no-profiles.json", serde_json::json!({"version": 1, "last_used": "Default", "profiles": []}));
+    // profiles from a newer version are rejected
+    let newer_result = import("newer.json", serde_json::json!({"version": CONFIG_VERSION + 1, "last_used": "Default", "profiles": [{"name": "Default", "config": old_config_json}]}));
+
+    let (old_config, defaulted_fields) = old_result.unwrap();
+    assert_eq!(old_config.read_row_headers, vec!["P".to_string(), "L".to_string()]);
+    assert_eq!(old_config.read_curve_header_prefix, ConfigStore::default().read_curve_header_prefix);
+    assert!(defaulted_fields.contains(&"read_curve_header_prefix".to_string()));
+    assert!(defaulted_fields.contains(&"diff_tolerances".to_string()));
+    assert!(!defaulted_fields.contains(&"read_row_headers".to_string()));
+    let mut sorted_fields = defaulted_fields.clone();
+    sorted_fields.sort();
+    assert_eq!(defaulted_fields, sorted_fields);
+
+    assert!(empty_result.unwrap_err().contains("read_start_header"));
+    let partial_msg = partial_result.unwrap_err();
+    assert!(partial_msg.contains("read_row_headers"));
+    assert!(!partial_msg.contains("read_start_header"));
+    assert!(no_profiles_result.is_err());
+    assert!(newer_result.unwrap_err().contains("newer version"));
+}//end config_store_try_import_config1()
+
+/// Test 1 for crate::process::format_output_as_delimited()
+#[test]
+pub fn process_format_output_as_delimited1() {
+    let data = crate::data::Data::new1(
+        "Sample,004".to_string(),
+        vec![
+            crate::data::Row::new("P".to_string(),2.),
+            crate::data::Row::new("L".to_string(),20.),
+        ],
+        vec![
+            crate::data::Curve::new("Curve1".to_string(), vec![
+                crate::data::Row::new("P".to_string(),1.),
+                crate::data::Row::new("L".to_string(),10.),
+            ]),
+            crate::data::Curve::new("Curve2".to_string(), vec![
+                crate::data::Row::new("P".to_string(),2.),
+            ]),
+        ],
+    );
+    let csv = crate::process::format_output_as_delimited(&vec![data], ',', &[]);
+    let correct_csv = vec![
+        "Test Name,Curve,P,L",
+        "\"Sample,004\",Curve1,1,10",
+        "\"Sample,004\",Curve2,2,",
+        "\"Sample,004\",calcAVG,1.5,10",
+        "\"Sample,004\",AVG,2,20",
+        "\"Sample,004\",Diff,-0.5,-10",
+        "\"Sample,004\",SD,0.7071067811865476,",
+        "\"Sample,004\",CV%,47.14045207910317,",
+        "\"Sample,004\",Min,1,10",
+        "\"Sample,004\",Max,2,10",
+        "\"Sample,004\",Range,1,0",
+        "",
+    ].join("\n");
+    assert_eq!(csv, correct_csv);
+}//end process_format_output_as_delimited1()
+
+/// Test 1 for crate::process::get_column_headers()
+#[test]
+pub fn process_get_column_headers1() {
+    let first = crate::data::Data::new1(
+        "Sample008".to_string(),
+        vec![
+            crate::data::Row::new("W".to_string(),250.),
+            crate::data::Row::new("L".to_string(),20.),
+        ],
+        Vec::new(),
+    );
+    let second = crate::data::Data::new1(
+        "Sample009".to_string(),
+        vec![
+            crate::data::Row::new("P".to_string(),80.),
+            crate::data::Row::new("L".to_string(),22.),
+            crate::data::Row::new("G".to_string(),10.),
+        ],
+        Vec::new(),
+    );
+    let data = vec![first, second];
+    let row_order_preference = vec!["P".to_string(), "L".to_string(), "Ie".to_string()];
+    // preferred headers come first even when the first file is missing them, then the rest as found
+    assert_eq!(crate::process::get_column_headers(&data, &row_order_preference), vec!["P", "L", "W", "G"]);
+    assert_eq!(crate::process::get_column_headers(&data, &[]), vec!["W", "L", "P", "G"]);
+    let csv = crate::process::format_output_as_delimited(&data, ',', &row_order_preference);
+    assert!(csv.starts_with("Test Name,Curve,P,L,W,G\n"));
+    assert!(csv.contains("\nSample008,AVG,,20,250,\n"));
+}//end process_get_column_headers1()
+
+/// Test 1 for crate::process::format_long_output_as_delimited()
+#[test]
+pub fn process_format_long_output_as_delimited1() {
+    let mut data = crate::data::Data::new1(
+        "Sample005".to_string(),
+        vec![crate::data::Row::new("P".to_string(),2.)],
+        vec![
+            crate::data::Curve::new("Curve1".to_string(), vec![
+                crate::data::Row::new1("P".to_string(),1.,"1,0".to_string()),
+                crate::data::Row::new("L".to_string(),10.),
+            ]),
+            crate::data::Curve::new("Curve2".to_string(), vec![
+                crate::data::Row::new("P".to_string(),7.),
+            ]),
+        ],
+    );
+    data.source_file = "sample\tfile.txt".to_string();
+    // excluded curves weren't used, so they're left out
+    data.curves[1].excluded = true;
+    let tsv = crate::process::format_long_output_as_delimited(&vec![data], '\t');
+    let correct_tsv = vec![
+        "Test Name\tSource File\tBlock\tParameter\tValue\tRaw Text",
+        "Sample005\t\"sample\tfile.txt\"\tCurve1\tP\t1\t1,0",
+        "Sample005\t\"sample\tfile.txt\"\tCurve1\tL\t10\t",
+        "Sample005\t\"sample\tfile.txt\"\tAverage\tP\t2\t",
+        "",
+    ].join("\n");
+    assert_eq!(tsv, correct_tsv);
+}//end process_format_long_output_as_delimited1()
+
+/// Test 1 for crate::process::get_long_output_path()
+#[test]
+pub fn process_get_long_output_path1() {
+    use crate::process::get_long_output_path;
+    use std::path::PathBuf;
+    let dir = PathBuf::from("output");
+    assert_eq!(get_long_output_path(&dir.join("today.csv")), dir.join("today-long.csv"));
+    // dots in the stem aren't mistaken for the extension
+    assert_eq!(get_long_output_path(&dir.join("results.2024.01.31.tsv")), dir.join("results.2024.01.31-long.tsv"));
+    assert_eq!(get_long_output_path(&dir.join("today")), dir.join("today-long"));
+}//end process_get_long_output_path1()
+
+/// Test 1 for crate::database::insert_data()
+#[test]
+pub fn database_insert_data1() {
+    let file_contents = sample_curve_file_lines().join("\n");
+    let (mut data, _) = crate::data::read_data_from_file(
+        "sample-filename",
+        &file_contents,
+        &sample_config()
+    ).unwrap();
+    data.source_hash = crate::input::hash_file_contents(file_contents.as_bytes());
+    let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+    conn.execute_batch(crate::database::CREATE_TABLES).unwrap();
+    let inserted = crate::database::insert_data(&mut conn, &data, &sample_config(), "2024-10-01T00:00:00Z").unwrap();
+    assert!(inserted);
+    // the same test from the same file shouldn't be inserted twice
+    let inserted_again = crate::database::insert_data(&mut conn, &data, &sample_config(), "2024-10-02T00:00:00Z").unwrap();
+    assert!(!inserted_again);
+    let count = |sql: &str| conn.query_row(sql, [], |row| row.get::<_,i64>(0)).unwrap();
+    assert_eq!(count("SELECT COUNT(*) FROM tests"), 1);
+    assert_eq!(count("SELECT COUNT(*) FROM curves"), 2);
+    // 3 rows for each of 2 curves, plus 3 average rows
+    assert_eq!(count("SELECT COUNT(*) FROM row_values"), 9);
+    // the text each value was read from is kept, so values can be checked against their files
+    let raw_text: String = conn.query_row("SELECT raw_text FROM row_values WHERE block = 'Average' AND header = 'P'", [], |row| row.get(0)).unwrap();
+    assert_eq!(raw_text, "1");
+
+    // the same text saved with a byte order mark is a different file, and excluded curves aren't stored
+    let mut bom_bytes = vec![0xEF, 0xBB, 0xBF];
+    bom_bytes.extend_from_slice(file_contents.as_bytes());
+    let (bom_contents, _) = crate::input::decode_input_bytes(&bom_bytes);
+    assert_eq!(bom_contents, file_contents);
+    data.source_hash = crate::input::hash_file_contents(&bom_bytes);
+    data.curves[0].excluded = true;
+    let inserted = crate::database::insert_data(&mut conn, &data, &sample_config(), "2024-10-03T00:00:00Z").unwrap();
+    assert!(inserted);
+    let count = |sql: &str| conn.query_row(sql, [], |row| row.get::<_,i64>(0)).unwrap();
+    assert_eq!(count("SELECT COUNT(*) FROM tests"), 2);
+    assert_eq!(count("SELECT COUNT(*) FROM curves"), 3);
+    assert_eq!(count("SELECT COUNT(*) FROM row_values"), 15);
+    assert_eq!(count("SELECT MIN(position) FROM curves WHERE test_id = 2"), 1);
+
+    // databases made before raw_text was saved get the column added when opened
+    let old_conn = rusqlite::Connection::open_in_memory().unwrap();
+    old_conn.execute_batch(&crate::database::CREATE_TABLES.replace(",\n    raw_text TEXT NOT NULL DEFAULT ''", "")).unwrap();
+    old_conn.execute_batch("
+        INSERT INTO tests (id, test_name, source_file, source_path, file_hash, processed_at, config) VALUES (1, 'Sample001', 'a.txt', 'a.txt', '', '', '');
+        INSERT INTO row_values (test_id, block, position, header, value) VALUES (1, 'Average', 0, 'P', 85.3);
+    ").unwrap();
+    crate::database::add_missing_columns(&old_conn).unwrap();
+    crate::database::add_missing_columns(&old_conn).unwrap();
+    let raw_text: String = old_conn.query_row("SELECT raw_text FROM row_values", [], |row| row.get(0)).unwrap();
+    assert_eq!(raw_text, "");
+}//end database_insert_data1()
+
+/// Test 1 for crate::process::get_issue_records()
+#[test]
+pub fn process_get_issue_records1() {
+    let file_lines = sample_curve_file_lines();
+    let config = sample_config();
+    let (_, errs) = crate::data::read_data_from_file(
+        "sample-filename",
+        &file_lines.join("\n"),
+        &config
+    ).unwrap();
+    let unreadable = crate::data::ParseIssue::new("other-file", None, crate::data::ParseIssueKind::FileUnreadable{msg: "access denied".to_string()});
+    let note = crate::input::TextEncoding::Windows1252.get_issue("old-file").unwrap();
+    let file_issues = vec![
+        crate::process::FileIssues::new("sample-filename", std::path::Path::new("sample-filename"), errs, true),
+        crate::process::FileIssues::new("other-file", std::path::Path::new("other-file"), vec![unreadable], false),
+        crate::process::FileIssues::new("old-file", std::path::Path::new("old-file"), vec![note], true),
+    ];
+    let records = crate::process::get_issue_records(&file_issues);
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].source_file, "sample-filename");
+    assert_eq!(records[0].line_number, Some(14));
+    assert_eq!(records[0].kind, "duplicate-curve");
+    assert!(records[0].included);
+    assert_eq!(records[1].line_number, None);
+    assert_eq!(records[1].severity, crate::data::IssueSeverity::Error);
+    assert!(!records[1].included);
+    // encoding notes reach the issues output along with everything else
+    assert_eq!(records[2].severity, crate::data::IssueSeverity::Note);
+    assert_eq!(records[2].kind, "encoding");
+    // csv and tsv output get the same columns in a second file
+    let csv = crate::process::format_issues_as_delimited(&file_issues, ',');
+    let csv_lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(csv_lines.len(), 4);
+    assert_eq!(csv_lines[0], "File,Line,Severity,Kind,Message,Included");
+    assert!(csv_lines[1].starts_with("sample-filename,14,Warning,duplicate-curve,"));
+    assert!(csv_lines[2].starts_with("other-file,,Error,file-unreadable,"));
+    assert!(csv_lines[2].ends_with(",No"));
+    assert!(csv_lines[3].starts_with("old-file,,Note,encoding,"));
+    let output_path = std::path::PathBuf::from("today.csv");
+    assert_eq!(crate::process::get_issues_output_path(&output_path), std::path::PathBuf::from("today-issues.csv"));
+}//end process_get_issue_records1()
+
+/// Test 1 for crate::process::get_review_records() and crate::process::apply_review_inclusions()
+#[test]
+pub fn process_get_review_records1() {
+    let file_lines = sample_curve_file_lines();
+    let config = sample_config();
+    let (mut data, mut errs) = crate::data::read_data_from_file(
+        "sample-filename",
+        &file_lines.join("\n"),
+        &config
+    ).unwrap();
+    // notes aren't counted as warnings
+    errs.push(crate::input::TextEncoding::Utf16Le.get_issue("sample-filename").unwrap());
+    data.curves[1].excluded = true;
+    data.source_path = std::path::PathBuf::from("first").join("sample-filename");
+    // a file with the same name in another folder is kept apart by its path
+    let mut other = data.clone();
+    other.source_path = std::path::PathBuf::from("second").join("sample-filename");
+    let mut data_files = vec![data, other];
+    let mut file_issues = vec![crate::process::FileIssues::new("sample-filename", &data_files[0].source_path, errs, true)];
+    let tolerances = vec![crate::config_store::DiffTolerance::new("G", crate::config_store::ToleranceMode::Absolute, 1.)];
+    let records = crate::process::get_review_records(&data_files, &file_issues, &tolerances);
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].test_name, "Sample002-7654321");
+    assert_eq!(records[0].curves, vec!["Curve1".to_string(), format!("Curve2{}", crate::process::EXCLUDED_CURVE_SUFFIX)]);
+    assert_eq!(records[0].averages, vec!["G 3", "L 2", "P 1"]);
+    // only Curve1 is used, so G is 13 - 3 = 10 away from the file average
+    assert_eq!(records[0].diffs[0], format!("G 10.00{}", crate::process::DIFF_FLAG));
+    assert_eq!(records[0].diffs[1], "L 10.00");
+    assert_eq!(records[0].warnings.len(), 1);
+    assert!(records[1].warnings.is_empty());
+    // leaving out the second file doesn't touch the issues of the first, even though they have the same name
+    crate::process::apply_review_inclusions(&mut data_files, &mut file_issues, &[true, false]);
+    assert!(file_issues[0].included);
+    assert_eq!(data_files.len(), 1);
+    crate::process::apply_review_inclusions(&mut data_files, &mut file_issues, &[false]);
+    assert!(data_files.is_empty());
+    assert!(!file_issues[0].included);
+}//end process_get_review_records1()
+
+/// Test 1 for crate::input::parse_dropped_paths() and crate::input::add_dropped_input_paths()
+#[test]
+pub fn input_add_dropped_input_paths1() {
+    let dropped = crate::input::parse_dropped_paths("file:///tmp/alveo%20files/a.txt\r\n/tmp/b.txt\n\n");
+    assert_eq!(dropped, vec![std::path::PathBuf::from("/tmp/alveo files/a.txt"), std::path::PathBuf::from("/tmp/b.txt")]);
+
+    let temp_dir = TempDir::new("drop");
+    let dir = temp_dir.path();
+    let sub_dir = dir.join("sub");
+    std::fs::create_dir_all(&sub_dir).unwrap();
+    std::fs::write(dir.join("one.txt"), "1").unwrap();
+    std::fs::write(dir.join("notes.csv"), "2").unwrap();
+    std::fs::write(sub_dir.join("two.TXT"), "3").unwrap();
+    let mut input_paths = vec![dir.join("one.txt")];
+    let (added, errs) = crate::input::add_dropped_input_paths(&mut input_paths, &vec![dir.clone(), dir.join("notes.csv")]);
+    assert!(errs.is_empty());
+    // one.txt was already chosen, and notes.csv isn't an input file
+    assert_eq!(added, 1);
+    assert_eq!(input_paths, vec![dir.join("one.txt"), sub_dir.join("two.TXT")]);
+}//end input_add_dropped_input_paths1()
+
+/// Test 1 for crate::input::glob_match() and crate::input::scan_input_folder()
+#[test]
+pub fn input_scan_input_folder1() {
+    assert!(crate::input::glob_match("**/*.txt", "a.txt"));
+    assert!(crate::input::glob_match("**/*.txt", "day1/run2/a.TXT"));
+    assert!(crate::input::glob_match("*ALV*.txt", "day1/my-alv-3.txt"));
+    assert!(!crate::input::glob_match("day?/*.txt", "day10/a.txt"));
+    assert!(crate::input::glob_match("archive/", "day1/archive/old/a.txt"));
+    assert!(!crate::input::glob_match("archive/", "day1/archived.txt"));
+    assert!(crate::input::glob_match("day1\\*.txt", "day1/a.txt"));
+
+    let temp_dir = TempDir::new("scan");
+    let dir = temp_dir.path();
+    let archive_dir = dir.join("day1").join("archive");
+    std::fs::create_dir_all(&archive_dir).unwrap();
+    std::fs::write(dir.join("one.txt"), "1").unwrap();
+    std::fs::write(dir.join("notes.csv"), "2").unwrap();
+    std::fs::write(dir.join("day1").join("two.txt"), "3").unwrap();
+    std::fs::write(archive_dir.join("three.txt"), "4").unwrap();
+    std::fs::write(dir.join("day1").join("old.txt"), "5").unwrap();
+    let scan = crate::input::scan_input_folder(dir, &["**/*.txt".to_string()], &["archive/".to_string(), "old.txt".to_string()]);
+    let scan = scan.unwrap();
+    assert_eq!(scan.included, vec![dir.join("day1").join("two.txt"), dir.join("one.txt")]);
+    // the archive folder is left out without looking inside
+    assert_eq!(scan.excluded, vec![dir.join("day1").join("old.txt")]);
+    assert_eq!(scan.excluded_dirs, vec![archive_dir]);
+    assert_eq!(scan.unmatched, 1);
+    assert!(scan.unreadable_dirs.is_empty());
+}//end input_scan_input_folder1()
+
+/// Test 2 for crate::input::scan_input_folder()
+#[cfg(unix)]
+#[test]
+pub fn input_scan_input_folder2() {
+    use std::os::unix::fs::PermissionsExt;
+    let temp_dir = TempDir::new("scan-links");
+    let dir = temp_dir.path();
+    let day_dir = dir.join("day1");
+    let locked_dir = dir.join("locked");
+    std::fs::create_dir_all(&day_dir).unwrap();
+    std::fs::create_dir_all(&locked_dir).unwrap();
+    std::fs::write(day_dir.join("one.txt"), "1").unwrap();
+    std::fs::write(locked_dir.join("two.txt"), "2").unwrap();
+    // a symlink back up the tree would loop forever if it were followed every time
+    std::os::unix::fs::symlink(dir, day_dir.join("loop")).unwrap();
+    std::fs::set_permissions(&locked_dir, std::fs::Permissions::from_mode(0o000)).unwrap();
+    // running as root can read the folder anyway, so only check it's reported if it really can't be read
+    let locked_readable = std::fs::read_dir(&locked_dir).is_ok();
+    let scan = crate::input::scan_input_folder(dir, &["**/*.txt".to_string()], &[]);
+    let dropped = crate::input::find_input_files_recursive(dir);
+    std::fs::set_permissions(&locked_dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let scan = scan.unwrap();
+    let (dropped_paths, dropped_errs) = dropped.unwrap();
+    assert!(scan.included.contains(&day_dir.join("one.txt")));
+    assert_eq!(scan.included, dropped_paths);
+    if locked_readable {
+        assert_eq!(scan.included.len(), 2);
+        assert!(scan.unreadable_dirs.is_empty());
+    } else {
+        assert_eq!(scan.included, vec![day_dir.join("one.txt")]);
+        assert_eq!(scan.unreadable_dirs.len(), 1);
+        assert!(scan.unreadable_dirs[0].contains("locked"));
+        assert_eq!(dropped_errs, scan.unreadable_dirs);
+    }//end else the locked folder couldn't be read
+}//end input_scan_input_folder2()
+
+/// Test 1 for crate::input::decode_input_bytes()
+#[test]
+pub fn input_decode_input_bytes1() {
+    use crate::input::{decode_input_bytes, TextEncoding};
+    let text = "Test name\t:\tSample001\r\nOperator\t:\tJos\u{E9}";
+    assert_eq!(decode_input_bytes(text.as_bytes()), (text.to_string(), TextEncoding::Utf8));
+    let mut bom_bytes = vec![0xEF, 0xBB, 0xBF];
+    bom_bytes.extend_from_slice(text.as_bytes());
+    assert_eq!(decode_input_bytes(&bom_bytes), (text.to_string(), TextEncoding::Utf8Bom));
+    let utf16le: Vec<u8> = text.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+    let mut utf16le_bom = vec![0xFF, 0xFE];
+    utf16le_bom.extend_from_slice(&utf16le);
+    assert_eq!(decode_input_bytes(&utf16le_bom), (text.to_string(), TextEncoding::Utf16Le));
+    // some exports leave out the byte order mark
+    assert_eq!(decode_input_bytes(&utf16le), (text.to_string(), TextEncoding::Utf16Le));
+    let utf16be: Vec<u8> = text.encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect();
+    assert_eq!(decode_input_bytes(&utf16be), (text.to_string(), TextEncoding::Utf16Be));
+    // 0xE9 is an accented e in Windows-1252 and 0x93/0x94 are curly quotes, but none are valid UTF-8 on their own
+    let cp1252 = b"Operator\t:\tJos\xE9 \x93Pat\x94".to_vec();
+    assert_eq!(decode_input_bytes(&cp1252), ("Operator\t:\tJos\u{E9} \u{201C}Pat\u{201D}".to_string(), TextEncoding::Windows1252));
+    assert_eq!(TextEncoding::Utf8.get_issue("a.txt"), None);
+    let note = TextEncoding::Utf16Le.get_issue("a.txt").unwrap();
+    assert_eq!(note.kind, crate::data::ParseIssueKind::Encoding{encoding: "UTF-16LE".to_string()});
+    assert!(note.is_note());
+    assert_eq!(note.to_string(), "Note in \"a.txt\": The file was read as UTF-16LE, since it wasn't plain UTF-8");
+    assert_eq!(crate::data::get_problems(&[note]), Vec::new());
+}//end input_decode_input_bytes1()
+
+/// Test 1 for crate::batch::ParseBatch
+#[test]
+pub fn batch_parse_batch1() {
+    let temp_dir = TempDir::new("batch");
+    let dir = temp_dir.path();
+    let mut paths = Vec::new();
+    for i in 0..6 {
+        let path = dir.join(format!("test{}.txt", i));
+        std::fs::write(&path, sample_curve_file_lines().join("\n").replace("Sample002", &format!("Batch{}", i))).unwrap();
+        paths.push(path);
+    }//end writing each test file
+    paths.insert(2, dir.join("missing.txt"));
+    let utf16_bytes: Vec<u8> = sample_curve_file_lines().join("\n").encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+    std::fs::write(&paths[3], [vec![0xFF, 0xFE], utf16_bytes].concat()).unwrap();
+    let results = crate::batch::ParseBatch::start_with_threads(&paths, &sample_config(), 3).take_results();
+    // results come back in the order given, no matter which thread finished first
+    assert_eq!(results.len(), paths.len());
+    assert_eq!(results.iter().map(|result| result.path.clone()).collect::<Vec<_>>(), paths);
+    assert!(matches!(results[2].outcome, crate::batch::FileOutcome::Unreadable(_)));
+    // the encoding note is kept with the file's issues, but the file is still done
+    match &results[3].outcome {
+        crate::batch::FileOutcome::Parsed(_, issues) => assert_eq!(issues.first(), results[3].get_encoding_issue().as_ref()),
+        other => panic!("Expected data from the UTF-16 file, but got {:?}", other),
+    }//end matching the outcome of the UTF-16 file
+    assert_eq!(results[3].get_status(), "Done, 1 issue(s): 1 duplicate-curve (read as UTF-16LE)");
+    match &results[6].outcome {
+        crate::batch::FileOutcome::Parsed(data, _) => {
+            assert_eq!(data.test_name, "Batch5-7654321");
+            assert_eq!(data.source_path, paths[6]);
+        },
+        other => panic!("Expected data from the last file, but got {:?}", other),
+    }//end matching the outcome of the last file
+
+    // progress is returned as the results are kept
+    let mut batch = crate::batch::ParseBatch::start_with_threads(&paths, &sample_config(), 2);
+    let mut progress = Vec::new();
+    loop {
+        let workers_done = batch.is_done();
+        progress.append(&mut batch.poll());
+        if workers_done {break;}
+        std::thread::yield_now();
+    }//end waiting for every worker to stop
+    assert_eq!(progress.iter().filter(|event| matches!(event, crate::batch::BatchProgress::Started(_))).count(), paths.len());
+    assert!(progress.contains(&crate::batch::BatchProgress::Finished(2, "Couldn't read file".to_string())));
+    assert_eq!(batch.take_results().len(), paths.len());
+
+    // cancelling while files remain gives back only what was finished, in order
+    let many_paths: Vec<std::path::PathBuf> = (0..200).map(|i| paths[i % paths.len()].clone()).collect();
+    let batch = crate::batch::ParseBatch::start_with_threads(&many_paths, &sample_config(), 1);
+    batch.cancel();
+    assert!(batch.is_cancelled());
+    let results = batch.take_results();
+    assert!(results.len() < many_paths.len());
+    assert!(results.windows(2).all(|pair| pair[0].index < pair[1].index));
+}//end batch_parse_batch1()
+
+/// Test 1 for crate::watch::poll_watch_folder()
+#[test]
+pub fn watch_poll_watch_folder1() {
+    let temp_dir = TempDir::new("watch");
+    let dir = temp_dir.path();
+    let watch_dir = dir.join("in");
+    let output_dir = dir.join("out");
+    std::fs::create_dir_all(&watch_dir).unwrap();
+    std::fs::create_dir_all(&output_dir).unwrap();
+    std::fs::write(watch_dir.join("first.txt"), sample_curve_file_lines().join("\n")).unwrap();
+    let settings = crate::watch::WatchSettings::new(watch_dir.clone(), output_dir.clone());
+    let specs = crate::spec::SpecStore::default();
+    let mut ledger = crate::watch::WatchLedger::default();
+    let now = std::time::SystemTime::now();
+    // the file was just written, so it might not be finished yet
+    let report = crate::watch::poll_watch_folder(&settings, &mut ledger, &sample_config(), &specs, now, time::UtcOffset::UTC);
+    assert!(report.processed.is_empty());
+    let later = now + std::time::Duration::from_secs(crate::watch::DEFAULT_SETTLE_SECS + 1);
+    let report = crate::watch::poll_watch_folder(&settings, &mut ledger, &sample_config(), &specs, later, time::UtcOffset::UTC);
+    assert_eq!(report.processed.len(), 1);
+    assert_eq!(report.processed[0].0, "Sample002-7654321");
+    assert!(report.problems.is_empty());
+    let workbook_path = crate::watch::get_daily_workbook_path(&output_dir, &crate::watch::get_day(later, time::UtcOffset::UTC));
+    assert_eq!(report.workbooks, vec![workbook_path.clone()]);
+    assert!(workbook_path.exists());
+    // a restart reads the ledger back, and shouldn't process the same file again
+    crate::watch::try_write_ledger(&settings.ledger_path(), &ledger).unwrap();
+    let mut ledger = crate::watch::try_read_ledger(&settings.ledger_path()).unwrap();
+    let report = crate::watch::poll_watch_folder(&settings, &mut ledger, &sample_config(), &specs, later, time::UtcOffset::UTC);
+    assert!(report.processed.is_empty());
+    assert_eq!(ledger.entries.len(), 1);
+}//end watch_poll_watch_folder1()
+
+/// Test 2 for crate::watch::poll_watch_folder()
+#[test]
+pub fn watch_poll_watch_folder2() {
+    let temp_dir = TempDir::new("watch-days");
+    let dir = temp_dir.path();
+    let watch_dir = dir.join("in");
+    let output_dir = dir.join("out");
+    std::fs::create_dir_all(&watch_dir).unwrap();
+    std::fs::create_dir_all(&output_dir).unwrap();
+    let input_path = watch_dir.join("first.txt");
+    std::fs::write(&input_path, sample_curve_file_lines().join("\n")).unwrap();
+    let settings = crate::watch::WatchSettings::new(watch_dir.clone(), output_dir.clone());
+    let specs = crate::spec::SpecStore::default();
+    let mut ledger = crate::watch::WatchLedger::default();
+    let first_day = std::time::SystemTime::now() + std::time::Duration::from_secs(crate::watch::DEFAULT_SETTLE_SECS + 1);
+    let first_report = crate::watch::poll_watch_folder(&settings, &mut ledger, &sample_config(), &specs, first_day, time::UtcOffset::UTC);
+    // the file changes two days later, so it moves to that day
+    std::fs::write(&input_path, format!("{}\n", sample_curve_file_lines().join("\n"))).unwrap();
+    let later_day = first_day + std::time::Duration::from_secs(2 * 24 * 60 * 60);
+    let later_report = crate::watch::poll_watch_folder(&settings, &mut ledger, &sample_config(), &specs, later_day, time::UtcOffset::UTC);
+    let first_workbook = crate::watch::get_daily_workbook_path(&output_dir, &crate::watch::get_day(first_day, time::UtcOffset::UTC));
+    let later_workbook = crate::watch::get_daily_workbook_path(&output_dir, &crate::watch::get_day(later_day, time::UtcOffset::UTC));
+    assert_eq!(first_report.workbooks, vec![first_workbook.clone()]);
+    assert_eq!(later_report.processed.len(), 1);
+    // the earlier workbook is rewritten without the file, and each workbook is only written once
+    assert_eq!(later_report.workbooks, vec![first_workbook, later_workbook]);
+    assert_eq!(ledger.entries.len(), 1);
+    assert_eq!(ledger.entries[0].day, crate::watch::get_day(later_day, time::UtcOffset::UTC));
+    assert!(ledger.get_day_paths(&crate::watch::get_day(first_day, time::UtcOffset::UTC)).is_empty());
+}//end watch_poll_watch_folder2()
+
+/// Test 1 for crate::watch::WatchPoll
+#[test]
+pub fn watch_watch_poll1() {
+    let temp_dir = TempDir::new("watch-poll");
+    let dir = temp_dir.path();
+    let watch_dir = dir.join("in");
+    let output_dir = dir.join("out");
+    std::fs::create_dir_all(&watch_dir).unwrap();
+    std::fs::create_dir_all(&output_dir).unwrap();
+    std::fs::write(watch_dir.join("first.txt"), sample_curve_file_lines().join("\n")).unwrap();
+    let settings = crate::watch::WatchSettings::new(watch_dir.clone(), output_dir.clone());
+    let ledger = crate::watch::WatchLedger::default();
+    let later = std::time::SystemTime::now() + std::time::Duration::from_secs(crate::watch::DEFAULT_SETTLE_SECS + 1);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let poll = crate::watch::WatchPoll::start(&settings, &ledger, &sample_config(), &crate::spec::SpecStore::default(), later, time::UtcOffset::UTC, move || sender.send(()).unwrap());
+    assert_eq!(poll.get_settings(), &settings);
+    // the worker lets us know once it's done
+    let notified = receiver.recv_timeout(std::time::Duration::from_secs(30));
+    let result = poll.take_result();
+    assert!(notified.is_ok());
+    let (new_ledger, report) = result.unwrap();
+    assert_eq!(report.processed.len(), 1);
+    assert_eq!(new_ledger.entries.len(), 1);
+    // the ledger given to the poll isn't changed, only the copy returned
+    assert!(ledger.entries.is_empty());
+}//end watch_watch_poll1()
+
+/// Converts a list of argument strings into the owned strings main() would get.
+fn to_args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}//end to_args()
+
+/// Test 1 for crate::args::is_cli_invocation()
+#[test]
+pub fn args_is_cli_invocation1() {
+    use crate::args::is_cli_invocation;
+    assert!(!is_cli_invocation(&to_args(&["alveograph-exporter-s"])));
+    assert!(is_cli_invocation(&to_args(&["alveograph-exporter-s", "export", "in.txt", "-o", "out.xlsx"])));
+    assert!(is_cli_invocation(&to_args(&["alveograph-exporter-s", "watch", "folder"])));
+    assert!(is_cli_invocation(&to_args(&["alveograph-exporter-s", "help"])));
+    assert!(is_cli_invocation(&to_args(&["alveograph-exporter-s", "-h"])));
+    assert!(is_cli_invocation(&to_args(&["alveograph-exporter-s", "--help"])));
+    // a file dropped onto the program should open the gui
+    assert!(!is_cli_invocation(&to_args(&["alveograph-exporter-s", "C:\\data\\Sample001.txt"])));
+    assert!(!is_cli_invocation(&to_args(&["alveograph-exporter-s", "--output"])));
+}//end args_is_cli_invocation1()
+
+/// Test 1 for crate::args::parse_export_args()
+#[test]
+pub fn args_parse_export_args1() {
+    use crate::args::{parse_export_args, ErrorPolicy};
+    use std::path::PathBuf;
+    let cli_args = parse_export_args(&to_args(&[
+        "a.txt", "folder", "-o", "out.csv", "--config", "other.json", "-p", "Flour",
+        "--spec", "Bread", "--specs", "specs.json", "-l", "--on-error", "skip",
+        "--ignore-issue", "bad-number", "--exclude", "old/",
+    ])).unwrap().unwrap();
+    assert_eq!(cli_args.inputs, vec![PathBuf::from("a.txt"), PathBuf::from("folder")]);
+    assert_eq!(cli_args.output, Some(PathBuf::from("out.csv")));
+    assert_eq!(cli_args.database, None);
+    assert_eq!(cli_args.format, None);
+    assert_eq!(cli_args.config, Some(PathBuf::from("other.json")));
+    assert_eq!(cli_args.profile, Some("Flour".to_string()));
+    assert_eq!(cli_args.spec, Some("Bread".to_string()));
+    assert_eq!(cli_args.specs, Some(PathBuf::from("specs.json")));
+    assert!(cli_args.long);
+    assert!(!cli_args.issues);
+    assert_eq!(cli_args.on_error, ErrorPolicy::Skip);
+    assert_eq!(cli_args.ignored_issues, vec!["bad-number".to_string()]);
+    // giving a pattern turns on recursive search
+    assert!(cli_args.recursive);
+    assert!(cli_args.include_patterns.is_empty());
+    assert_eq!(cli_args.exclude_patterns, vec!["old/".to_string()]);
+
+    assert_eq!(parse_export_args(&to_args(&["a.txt", "--help"])), Ok(None));
+    // options missing their values
+    assert!(parse_export_args(&to_args(&["a.txt", "-o"])).is_err());
+    assert!(parse_export_args(&to_args(&["a.txt", "-o", "out.xlsx", "--config"])).is_err());
+    assert!(parse_export_args(&to_args(&["a.txt", "-o", "out.xlsx", "--spec"])).is_err());
+    assert!(parse_export_args(&to_args(&["a.txt", "-o", "out.xlsx", "--format"])).is_err());
+    assert!(parse_export_args(&to_args(&["a.txt", "-o", "out.xlsx", "--format", "pdf"])).is_err());
+    assert!(parse_export_args(&to_args(&["a.txt", "-o", "out.xlsx", "--on-error", "ignore"])).is_err());
+    assert!(parse_export_args(&to_args(&["a.txt", "-o", "out.xlsx", "--ignore-issue", "everything"])).is_err());
+    // unknown options, and missing inputs or outputs
+    assert!(parse_export_args(&to_args(&["a.txt", "-o", "out.xlsx", "--verbose"])).is_err());
+    assert!(parse_export_args(&to_args(&["-o", "out.xlsx"])).is_err());
+    assert!(parse_export_args(&to_args(&["a.txt"])).is_err());
+}//end args_parse_export_args1()
+
+/// Test 1 for crate::args::parse_watch_args()
+#[test]
+pub fn args_parse_watch_args1() {
+    use crate::args::parse_watch_args;
+    use std::path::PathBuf;
+    let watch_args = parse_watch_args(&to_args(&["in", "--config", "other.json", "--interval", "30", "--settle", "0", "--once"])).unwrap().unwrap();
+    assert_eq!(watch_args.settings.watch_dir, PathBuf::from("in"));
+    assert_eq!(watch_args.settings.output_dir, PathBuf::from("in"));
+    assert_eq!(watch_args.config, Some(PathBuf::from("other.json")));
+    assert_eq!(watch_args.interval, std::time::Duration::from_secs(30));
+    assert_eq!(watch_args.settings.settle, std::time::Duration::from_secs(0));
+    assert!(watch_args.once);
+
+    assert_eq!(parse_watch_args(&to_args(&["--help"])), Ok(None));
+    assert!(parse_watch_args(&to_args(&["in", "--config"])).is_err());
+    assert!(parse_watch_args(&to_args(&["in", "--interval", "0"])).is_err());
+    assert!(parse_watch_args(&to_args(&["in", "--poll"])).is_err());
+    assert!(parse_watch_args(&to_args(&["in", "other"])).is_err());
+    assert!(parse_watch_args(&to_args(&[])).is_err());
+    assert!(parse_watch_args(&to_args(&["in", "--no-workbook"])).is_err());
+}//end args_parse_watch_args1()
@@ -0,0 +1,21 @@
+/// Returns true if text matches pattern, ignoring case.
+/// In pattern, "*" matches any run of characters (including none), and "?" matches any single character.
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // the last star we saw in pattern, and the position in text we tried it at
+    let mut star: Option<(usize,usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {p += 1; t += 1;}
+        else if p < pattern.len() && pattern[p] == '*' {star = Some((p,t)); p += 1;}
+        else if let Some((star_p, star_t)) = star {
+            // let the last star match one more character and try again
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        }//end else if we can backtrack to the last star
+        else {return false;}
+    }//end looping over each character of text
+    pattern[p..].iter().all(|c| *c == '*')
+}//end wildcard_match()
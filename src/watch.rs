@@ -0,0 +1,348 @@
+use std::{fs, path::{Path, PathBuf}, thread::{self, JoinHandle}, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use serde::{Deserialize, Serialize};
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::{config_store::ConfigStore, data::{self, Data, ParseIssue}, database, input, process::{close_workbook, get_workbook, write_output_to_sheet}, spec::{self, SpecStore}};
+
+/// The name of the ledger file kept in the output folder, recording which files were processed.
+pub const LEDGER_FILE_NAME: &str = "watch-ledger.json";
+/// The start of the name of each daily workbook, which is followed by the date.
+pub const DAILY_WORKBOOK_PREFIX: &str = "alveograph-";
+/// The default number of seconds a file must go unmodified before it's treated as fully written.
+pub const DEFAULT_SETTLE_SECS: u64 = 5;
+/// The default number of seconds to wait between checks of the watched folder.
+pub const DEFAULT_POLL_SECS: u64 = 10;
+
+/// Records a single file that was processed by watch mode.
+#[derive(Clone,PartialEq,Debug,Default,Deserialize,Serialize)]
+pub struct LedgerEntry {
+    /// The full path of the file.
+    pub path: String,
+    /// The time the file was last modified when it was processed, in seconds since the unix epoch.
+    pub modified: u64,
+    /// The size of the file in bytes when it was processed.
+    pub size: u64,
+    /// A hash of the bytes of the file, from input::hash_file_contents().
+    pub source_hash: String,
+    /// The day the file was last read, as YYYY-MM-DD in local time, which picks its daily workbook.
+    pub day: String,
+    /// Whether data could be read from the file. Files which couldn't be read
+    /// aren't tried again until they're modified.
+    pub readable: bool,
+}//end struct LedgerEntry
+
+/// Keeps track of every file processed by watch mode, so that
+/// files aren't processed again when watch mode is restarted.
+#[derive(Clone,PartialEq,Debug,Default,Deserialize,Serialize)]
+pub struct WatchLedger {
+    pub entries: Vec<LedgerEntry>,
+}//end struct WatchLedger
+
+impl WatchLedger {
+    /// Gets the entry for the file at path, if it has been processed.
+    pub fn get(&self, path: &Path) -> Option<&LedgerEntry> {
+        let path = path.to_string_lossy();
+        self.entries.iter().find(|entry| entry.path.eq(&path))
+    }//end get()
+
+    /// Adds entry to the ledger, replacing any entry for the same path.
+    pub fn record(&mut self, entry: LedgerEntry) {
+        match self.entries.iter_mut().find(|old_entry| old_entry.path.eq(&entry.path)) {
+            Some(old_entry) => *old_entry = entry,
+            None => self.entries.push(entry),
+        }//end matching whether the path is already in the ledger
+    }//end record()
+
+    /// Gets the paths of the readable files processed on day, in the order they were processed.
+    pub fn get_day_paths(&self, day: &str) -> Vec<PathBuf> {
+        self.entries.iter()
+            .filter(|entry| entry.readable && entry.day.eq(day))
+            .map(|entry| PathBuf::from(&entry.path))
+            .collect()
+    }//end get_day_paths()
+}//end impl WatchLedger
+
+/// The folders and options used by watch mode.
+#[derive(Clone,PartialEq,Debug)]
+pub struct WatchSettings {
+    /// The folder to watch for new input files.
+    pub watch_dir: PathBuf,
+    /// The folder that daily workbooks and the ledger are written to.
+    pub output_dir: PathBuf,
+    /// Whether to write a daily workbook.
+    pub workbook: bool,
+    /// A database to save results to as well, if any.
+    pub database_path: Option<PathBuf>,
+    /// How long a file must go unmodified before it's treated as fully written.
+    pub settle: Duration,
+}//end struct WatchSettings
+
+impl WatchSettings {
+    /// Creates new WatchSettings which write daily workbooks to output_dir,
+    /// without a database, using DEFAULT_SETTLE_SECS.
+    pub fn new(watch_dir: PathBuf, output_dir: PathBuf) -> WatchSettings {
+        WatchSettings{watch_dir, output_dir, workbook: true, database_path: None, settle: Duration::from_secs(DEFAULT_SETTLE_SECS)}
+    }
+
+    /// Gets the path of the ledger file in output_dir.
+    pub fn ledger_path(&self) -> PathBuf {
+        self.output_dir.join(LEDGER_FILE_NAME)
+    }//end ledger_path()
+}//end impl WatchSettings
+
+/// Describes what happened during a single check of the watched folder.
+#[derive(Clone,PartialEq,Debug,Default)]
+pub struct WatchReport {
+    /// The test name and filename of each file read, along with any issues found.
+    pub processed: Vec<(String,String,Vec<ParseIssue>)>,
+    /// A note for each file read as something other than plain UTF-8.
+    pub notes: Vec<String>,
+    /// A message for each file or output that had a problem.
+    pub problems: Vec<String>,
+    /// The daily workbooks that were written.
+    pub workbooks: Vec<PathBuf>,
+    /// The number of tests saved to the database.
+    pub saved_to_database: usize,
+}//end struct WatchReport
+
+impl WatchReport {
+    /// Gets a line describing each thing that happened, or an empty Vec if nothing did.
+    pub fn get_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (test_name, filename, issues) in self.processed.iter() {
+            lines.push(format!("Read {} from \"{}\"{}", test_name, filename, if issues.is_empty() {String::new()} else {format!(", {}", data::summarize_issues(issues))}));
+        }//end adding a line for each file read
+        lines.extend(self.notes.iter().cloned());
+        lines.extend(self.problems.iter().cloned());
+        for workbook in self.workbooks.iter() {lines.push(format!("Wrote \"{}\"", workbook.to_string_lossy()));}
+        if self.saved_to_database > 0 {lines.push(format!("Saved {} test(s) to the database", self.saved_to_database));}
+        lines
+    }//end get_lines()
+}//end impl WatchReport
+
+/// Attempts to read the ledger at ledger_path.
+/// If there isn't a ledger there yet, an empty ledger is returned.
+pub fn try_read_ledger(ledger_path: &Path) -> Result<WatchLedger,String> {
+    if !ledger_path.exists() {return Ok(WatchLedger::default());}
+    match fs::read_to_string(ledger_path) {
+        Ok(file_contents) => {
+            match serde_json::from_str(&file_contents) {
+                Ok(ledger) => Ok(ledger),
+                Err(error) => Err(error.to_string()),
+            }//end matching whether we can deserialize the ledger
+        },
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we could read string from file
+}//end try_read_ledger()
+
+/// Attempts to write ledger to ledger_path.
+pub fn try_write_ledger(ledger_path: &Path, ledger: &WatchLedger) -> Result<(),String> {
+    match serde_json::to_string_pretty(ledger) {
+        Ok(ledger_serial) => {
+            match fs::write(ledger_path, ledger_serial) {
+                Ok(_) => Ok(()),
+                Err(error) => Err(error.to_string()),
+            }//end matching whether or not write succeeded
+        },
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we could serialize the ledger
+}//end try_write_ledger()
+
+/// Gets the local offset from UTC, as used by get_day().  
+/// This should be called once at startup, before any other threads are running, because the local
+/// offset can't be determined on some platforms once they are. If it can't be determined, UTC is used instead.
+pub fn get_local_offset() -> UtcOffset {
+    UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC)
+}//end get_local_offset()
+
+/// Gets the day of time as YYYY-MM-DD with the local offset from get_local_offset(), as used for daily workbooks.
+pub fn get_day(time: SystemTime, offset: UtcOffset) -> String {
+    let format_des = time::macros::format_description!("[year]-[month]-[day]");
+    OffsetDateTime::from(time).to_offset(offset).format(format_des).unwrap_or_default()
+}//end get_day()
+
+/// Gets the path of the daily workbook for day in output_dir.
+pub fn get_daily_workbook_path(output_dir: &Path, day: &str) -> PathBuf {
+    output_dir.join(format!("{}{}.xlsx", DAILY_WORKBOOK_PREFIX, day))
+}//end get_daily_workbook_path()
+
+/// Gets the modified time, in seconds since the unix epoch, and size of the file at path.
+fn get_file_stamp(path: &Path) -> Option<(u64,u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some((modified, metadata.len()))
+}//end get_file_stamp()
+
+/// Finds the input files in watch_dir which are ready to be processed, along with their
+/// modified time and size. A file is ready if it isn't in the ledger with the same modified
+/// time and size, and it hasn't been modified for at least settle, meaning it's fully written.
+pub fn find_ready_files(watch_dir: &Path, ledger: &WatchLedger, now: SystemTime, settle: Duration) -> Result<Vec<(PathBuf,u64,u64)>,String> {
+    let input_paths = input::gather_input_paths(&vec![watch_dir.to_path_buf()])?;
+    let now_secs = now.duration_since(UNIX_EPOCH).map(|since| since.as_secs()).unwrap_or(0);
+    let mut ready = Vec::new();
+    for input_path in input_paths {
+        let (modified, size) = match get_file_stamp(&input_path) {
+            Some(stamp) => stamp,
+            None => continue,
+        };
+        if let Some(entry) = ledger.get(&input_path) {
+            if entry.modified == modified && entry.size == size {continue;}
+        }//end if we've already processed this version of the file
+        if now_secs.saturating_sub(modified) < settle.as_secs() {continue;}
+        ready.push((input_path, modified, size));
+    }//end checking whether each file is ready
+    Ok(ready)
+}//end find_ready_files()
+
+/// Checks the watched folder once, reading any new or modified files which are fully written.
+/// The daily workbook for each day with new data is rewritten with every file processed on that
+/// day, and new data is saved to the database, if there is one. When a modified file was last
+/// read on an earlier day, it moves to today, so the workbook for that earlier day is rewritten too.
+/// Files are only added to the ledger once their output is written, so that files
+/// whose output couldn't be written are tried again on the next check.  
+/// offset is the local offset from get_local_offset(), used to find which day now is.
+pub fn poll_watch_folder(settings: &WatchSettings, ledger: &mut WatchLedger, config: &ConfigStore, specs: &SpecStore, now: SystemTime, offset: UtcOffset) -> WatchReport {
+    let mut report = WatchReport::default();
+    let ready_files = match find_ready_files(&settings.watch_dir, ledger, now, settings.settle) {
+        Ok(ready_files) => ready_files,
+        Err(msg) => {report.problems.push(msg); return report;},
+    };
+    let today = get_day(now, offset);
+    let mut new_entries = Vec::new();
+    let mut new_data = Vec::new();
+    // the days of readable files which are moving to today, whose workbooks would still have them
+    let mut old_days = Vec::new();
+    for (input_path, modified, size) in ready_files {
+        let input_file = match input::read_input_file_with_encoding(&input_path) {
+            Ok(input_file) => input_file,
+            // the file might still be locked by whatever is writing it, so try again next time
+            Err(msg) => {report.problems.push(format!("Couldn't read \"{}\", and will try again: {}", input_path.to_string_lossy(), msg)); continue;},
+        };
+        let mut entry = LedgerEntry{path: input_path.to_string_lossy().to_string(), modified, size, source_hash: input_file.hash.clone(), day: today.clone(), readable: true};
+        if let Some(old_entry) = ledger.get(&input_path) {
+            if old_entry.source_hash.eq(&entry.source_hash) {
+                // only the timestamp changed, so there's nothing new to read
+                entry.day = old_entry.day.clone();
+                entry.readable = old_entry.readable;
+                ledger.record(entry);
+                continue;
+            }//end if the contents haven't changed
+            if old_entry.readable && !old_entry.day.eq(&today) {old_days.push(old_entry.day.clone());}
+        }//end if this file was processed before
+        let filename = input::get_filename(&input_path);
+        if let Some(note) = input_file.encoding.get_issue(&filename) {report.notes.push(note.to_string());}
+        match data::read_data_from_file(&filename, &input_file.contents, config) {
+            Ok((mut data,errs)) => {
+                data.source_path = input_path.clone();
+                data.source_hash = input_file.hash.clone();
+                report.processed.push((data.test_name.clone(), filename, errs));
+                new_data.push(data);
+            },
+            Err(issue) => {
+                report.problems.push(issue.to_string());
+                entry.readable = false;
+            },
+        }//end matching whether we could read data from the file
+        new_entries.push(entry);
+    }//end reading each file that's ready
+
+    let mut outputs_written = true;
+    let mut days: Vec<String> = new_entries.iter().filter(|entry| entry.readable).map(|entry| entry.day.clone()).chain(old_days).collect();
+    days.sort();
+    days.dedup();
+    if settings.workbook && !days.is_empty() {
+        let mut day_ledger = ledger.clone();
+        for entry in new_entries.iter() {day_ledger.record(entry.clone());}
+        for day in days {
+            match write_daily_workbook(&settings.output_dir, &day, &day_ledger, config, specs) {
+                Ok(workbook_path) => report.workbooks.push(workbook_path),
+                Err(msg) => {report.problems.push(format!("{} The new file(s) will be tried again.", msg)); outputs_written = false;},
+            }//end matching whether we could write the daily workbook
+        }//end writing the workbook for each day with new data
+    }//end if we should write daily workbooks
+    if let Some(database_path) = &settings.database_path {
+        if !new_data.is_empty() {
+            spec::assign_spec_names(&mut new_data, specs);
+            match database::write_output_to_database(database_path, &new_data, config) {
+                Ok((inserted, _)) => report.saved_to_database = inserted,
+                Err(msg) => {report.problems.push(format!("There was an issue saving to the database \"{}\", and the new file(s) will be tried again:\n{}", database_path.to_string_lossy(), msg)); outputs_written = false;},
+            }//end matching whether we could save to the database
+        }//end if there's anything to save
+    }//end if we should save to a database
+    if outputs_written {
+        for entry in new_entries {ledger.record(entry);}
+    }//end if the new files don't need to be tried again
+    report
+}//end poll_watch_folder()
+
+/// A single check of the watched folder with poll_watch_folder(), running on a worker
+/// thread so that the gui stays responsive while files are read and workbooks are written.
+pub struct WatchPoll {
+    /// The settings the folder is being checked with.
+    settings: WatchSettings,
+    /// The worker thread, which returns the updated ledger and what happened.
+    worker: JoinHandle<(WatchLedger,WatchReport)>,
+}//end struct WatchPoll
+
+impl WatchPoll {
+    /// Starts checking the watched folder on a worker thread, with a copy of ledger.  
+    /// on_done is called from the worker thread once the check is finished, such as to wake up the gui.
+    pub fn start(settings: &WatchSettings, ledger: &WatchLedger, config: &ConfigStore, specs: &SpecStore, now: SystemTime, offset: UtcOffset, on_done: impl FnOnce() + Send + 'static) -> WatchPoll {
+        let (worker_settings, mut ledger, config, specs) = (settings.clone(), ledger.clone(), config.clone(), specs.clone());
+        let worker = thread::spawn(move || {
+            let report = poll_watch_folder(&worker_settings, &mut ledger, &config, &specs, now, offset);
+            on_done();
+            (ledger, report)
+        });
+        WatchPoll{settings: settings.clone(), worker}
+    }//end start()
+
+    /// Gets the settings the folder is being checked with.
+    pub fn get_settings(&self) -> &WatchSettings {
+        &self.settings
+    }//end get_settings(self)
+
+    /// Returns true once the worker has finished checking the folder.
+    pub fn is_done(&self) -> bool {
+        self.worker.is_finished()
+    }//end is_done(self)
+
+    /// Waits for the worker to finish, then returns the updated ledger and what happened.  
+    /// Returns an error if the worker stopped unexpectedly, in which case the ledger wasn't updated.
+    pub fn take_result(self) -> Result<(WatchLedger,WatchReport),String> {
+        match self.worker.join() {
+            Ok(result) => Ok(result),
+            Err(_) => Err(format!("The check of \"{}\" stopped unexpectedly, so it will be tried again.", self.settings.watch_dir.to_string_lossy())),
+        }//end matching whether the worker finished normally
+    }//end take_result(self)
+}//end impl for WatchPoll
+
+/// Writes the daily workbook for day to output_dir, with the data from every readable
+/// file processed on day according to ledger, read again from each file.
+/// Returns the path of the workbook written.
+pub fn write_daily_workbook(output_dir: &Path, day: &str, ledger: &WatchLedger, config: &ConfigStore, specs: &SpecStore) -> Result<PathBuf,String> {
+    let mut data_files: Vec<Data> = Vec::new();
+    for input_path in ledger.get_day_paths(day) {
+        // files removed since they were processed are left out
+        let input_file = match input::read_input_file_with_encoding(&input_path) {
+            Ok(input_file) => input_file,
+            Err(_) => continue,
+        };
+        if let Ok((mut data,_)) = data::read_data_from_file(&input::get_filename(&input_path), &input_file.contents, config) {
+            data.source_path = input_path.clone();
+            data.source_hash = input_file.hash;
+            data_files.push(data);
+        }//end if we could read the data again
+    }//end reading each file from day
+    spec::assign_spec_names(&mut data_files, specs);
+    let workbook_path = get_daily_workbook_path(output_dir, day);
+    let mut wb = get_workbook();
+    if let Err(err) = write_output_to_sheet(&mut wb, &data_files, "alveograph-exporter-output", &config.row_order_preference, &config.diff_tolerances, specs) {
+        return Err(format!("There was an issue writing output data to the sheet:\n{}", err));
+    }//end if there was an error writing to the sheet
+    if let Err(err) = close_workbook(&mut wb, &workbook_path) {
+        return Err(format!("There was an issue closing the workbook \"{}\". Is it open?\n{}", workbook_path.to_string_lossy(), err));
+    }//end if there was an error closing the workbook
+    Ok(workbook_path)
+}//end write_daily_workbook()